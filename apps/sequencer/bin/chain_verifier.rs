@@ -0,0 +1,84 @@
+use blocksense_config::{init_config, AllFeedsConfig};
+use blocksense_utils::logging::init_shared_logging_handle;
+use sequencer::chain_verifier::{diff_feeds, replay_blocks};
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn print_usage() {
+    println!("Usage:");
+    println!("chain_verifier <blocks_dump> <feeds_config> [<initial_feeds_config>]");
+    println!(" ");
+    println!("ARGS");
+    println!(
+        "<blocks_dump>              file with the messages of the `blockchain` topic, one per line"
+    );
+    println!("<feeds_config>             feeds config file to compare the feeds on chain to");
+    println!(
+        "<initial_feeds_config>     feeds config file the sequencers were started with, defaults to <feeds_config>"
+    );
+}
+
+fn read_feeds_config(path: &str) -> Option<AllFeedsConfig> {
+    init_config::<AllFeedsConfig>(Path::new(path))
+        .inspect_err(|e| eprintln!("Could not read feeds config {path}: {e}"))
+        .ok()
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (blocks_dump, feeds_config, initial_feeds_config) = match &args[..] {
+        [blocks_dump, feeds_config] => (blocks_dump, feeds_config, feeds_config),
+        [blocks_dump, feeds_config, initial_feeds_config] => {
+            (blocks_dump, feeds_config, initial_feeds_config)
+        }
+        _ => {
+            print_usage();
+            return ExitCode::FAILURE;
+        }
+    };
+    init_shared_logging_handle("INFO", false);
+
+    let dump = match std::fs::read_to_string(blocks_dump) {
+        Ok(dump) => dump,
+        Err(e) => {
+            eprintln!("Could not read blocks dump {blocks_dump}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(feeds_config) = read_feeds_config(feeds_config) else {
+        return ExitCode::FAILURE;
+    };
+    let Some(initial_feeds_config) = read_feeds_config(initial_feeds_config) else {
+        return ExitCode::FAILURE;
+    };
+
+    let replayed = match replay_blocks(&dump, &initial_feeds_config.feeds) {
+        Ok(replayed) => replayed,
+        Err(e) => {
+            eprintln!("Chain verification failed: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for (issuer_id, blocks) in &replayed.blocks_per_issuer {
+        let restarts = replayed
+            .restarts_per_issuer
+            .get(issuer_id)
+            .copied()
+            .unwrap_or_default();
+        println!("Verified {blocks} blocks of sequencer {issuer_id}, restarted {restarts} times");
+    }
+
+    let diff = diff_feeds(&replayed.feeds, &feeds_config.feeds);
+    if diff.is_empty() {
+        println!(
+            "All {} feeds on chain match the feeds config",
+            replayed.feeds.len()
+        );
+        return ExitCode::SUCCESS;
+    }
+    println!("Feeds only on chain: {:?}", diff.only_on_chain);
+    println!("Feeds only in config: {:?}", diff.only_in_config);
+    println!("Feeds with different config: {:?}", diff.different);
+    ExitCode::FAILURE
+}
@@ -1,9 +1,11 @@
+use actix_web::middleware::from_fn;
 use actix_web::{web, App, HttpServer};
 use blocksense_feed_registry::feed_registration_cmds::FeedsManagementCmds;
 use blocksense_gnosis_safe::data_types::ReporterResponse;
 use blocksense_gnosis_safe::utils::SignatureWithAddress;
-use sequencer::providers::provider::init_shared_rpc_providers;
+use sequencer::providers::provider::{init_shared_rpc_providers, new_provider_metrics};
 use sequencer::sequencer_state::SequencerState;
+use sequencer::shutdown::{graceful_shutdown, shutdown_signal};
 use tokio::sync::mpsc;
 
 use blocksense_utils::logging::{
@@ -11,6 +13,7 @@ use blocksense_utils::logging::{
     SharedLoggingHandle,
 };
 use sequencer::http_handlers::admin::add_admin_services;
+use sequencer::http_handlers::admin_auth::admin_auth;
 use sequencer::http_handlers::data_feeds::add_main_services;
 
 use actix_web::web::Data;
@@ -21,7 +24,7 @@ use sequencer::http_handlers::admin::metrics;
 use std::env;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{info, warn};
 
 use blocksense_utils::build_info::{
     BLOCKSENSE_VERSION, GIT_BRANCH, GIT_DIRTY, GIT_HASH, GIT_HASH_SHORT, GIT_TAG,
@@ -40,27 +43,18 @@ pub async fn prepare_sequencer_state(
     sequencer_config: &SequencerConfig,
     feeds_config: AllFeedsConfig,
     metrics_prefix: Option<&str>,
-) -> (
+) -> eyre::Result<(
     UnboundedReceiver<VotedFeedUpdateWithProof>, // aggregated_votes_to_block_creator_recv
     UnboundedReceiver<FeedsManagementCmds>,      // feeds_management_cmd_to_block_creator_recv
     UnboundedReceiver<FeedsManagementCmds>,      // feeds_slots_manager_cmd_recv
     UnboundedReceiver<(ReporterResponse, SignatureWithAddress)>, // aggregate_batch_sig_recv
     Data<SequencerState>,
-) {
+)> {
     let log_handle: SharedLoggingHandle = get_shared_logging_handle();
 
-    tokio::task::Builder::new()
-        .name("interrupt_watcher")
-        .spawn_local(async move {
-            info!("Watching for Ctrl-C...");
-            tokio::signal::ctrl_c().await.unwrap();
-            info!("Ctrl-C detected; terminating...");
-            std::process::exit(0);
-        })
-        .expect("Failed to spawn interrupt watcher!");
-
+    let provider_metrics = new_provider_metrics(metrics_prefix);
     let providers =
-        init_shared_rpc_providers(sequencer_config, metrics_prefix, &feeds_config).await;
+        init_shared_rpc_providers(sequencer_config, &provider_metrics, &feeds_config).await;
     let feed_id_allocator: ConcurrentAllocator = init_concurrent_allocator();
     let (aggregated_votes_to_block_creator_send, aggregated_votes_to_block_creator_recv): VoteChannel = mpsc::unbounded_channel();
     let (feeds_management_cmd_to_block_creator_send, feeds_management_cmd_to_block_creator_recv) =
@@ -71,6 +65,7 @@ pub async fn prepare_sequencer_state(
     let sequencer_state: Data<SequencerState> = web::Data::new(SequencerState::new(
         feeds_config,
         providers,
+        provider_metrics,
         log_handle,
         sequencer_config,
         metrics_prefix,
@@ -79,15 +74,29 @@ pub async fn prepare_sequencer_state(
         feeds_management_cmd_to_block_creator_send,
         feeds_slots_manager_cmd_send,
         aggregate_batch_sig_send,
-    ));
+    )?);
+
+    let shutdown_sequencer_state = sequencer_state.clone();
+    tokio::task::Builder::new()
+        .name("interrupt_watcher")
+        .spawn_local(async move {
+            info!("Watching for Ctrl-C and SIGTERM...");
+            shutdown_signal().await;
+            tokio::select! {
+                _ = graceful_shutdown(&shutdown_sequencer_state) => {}
+                _ = shutdown_signal() => warn!("Second interrupt received; terminating without waiting..."),
+            }
+            std::process::exit(0);
+        })
+        .expect("Failed to spawn interrupt watcher!");
 
-    (
+    Ok((
         aggregated_votes_to_block_creator_recv,
         feeds_management_cmd_to_block_creator_recv,
         feeds_slots_manager_cmd_recv,
         aggregate_batch_sig_recv,
         sequencer_state,
-    )
+    ))
 }
 
 pub async fn prepare_http_servers(
@@ -99,19 +108,24 @@ pub async fn prepare_http_servers(
     JoinHandle<std::io::Result<()>>,
 ) {
     let main_sequencer_state: Data<SequencerState> = sequencer_state.clone();
+    let main_shutdown_state: Data<SequencerState> = sequencer_state.clone();
     let main_http_server_fut: JoinHandle<std::io::Result<()>> = tokio::task::Builder::new()
         .name("main_http_server")
         .spawn_local(async move {
             info!("Starting main HTTP server on port {sequencer_config_main_port}...");
-            HttpServer::new(move || {
+            let server = HttpServer::new(move || {
                 App::new()
                     .app_data(main_sequencer_state.clone())
                     .configure(add_main_services)
             })
+            .disable_signals()
             .bind(("0.0.0.0", sequencer_config_main_port))
             .expect("Main HTTP server could not bind to port.")
-            .run()
-            .await
+            .run();
+            main_shutdown_state
+                .shutdown
+                .register_server(server.handle());
+            server.await
         })
         .expect("Failed to spawn main HTTP server!");
 
@@ -120,16 +134,19 @@ pub async fn prepare_http_servers(
         .name("admin_http_server")
         .spawn_local(async move {
             info!("Starting admin HTTP server on port {admin_port}...");
-            HttpServer::new(move || {
+            let server = HttpServer::new(move || {
                 App::new()
                     .app_data(admin_sequencer_state.clone())
+                    .wrap(from_fn(admin_auth))
                     .configure(add_admin_services)
             })
             .workers(1)
+            .disable_signals()
             .bind(("0.0.0.0", admin_port))
             .expect("Admin HTTP server could not bind to port.")
-            .run()
-            .await
+            .run();
+            sequencer_state.shutdown.register_server(server.handle());
+            server.await
         })
         .expect("Failed to spawn admin HTTP server!");
 
@@ -208,7 +225,9 @@ async fn main() -> std::io::Result<()> {
         feeds_slots_manager_cmd_recv,
         aggregate_batch_sig_recv,
         sequencer_state,
-    ) = prepare_sequencer_state(&sequencer_config, feeds_config, None).await;
+    ) = prepare_sequencer_state(&sequencer_config, feeds_config, None)
+        .await
+        .map_err(|e| std::io::Error::other(format!("Could not prepare sequencer state: {e:#}")))?;
 
     let collected_futures = prepare_app_workers(
         sequencer_state.clone(),
@@ -221,7 +240,7 @@ async fn main() -> std::io::Result<()> {
     .await;
 
     let (main_http_server_fut, admin_http_server_fut) = prepare_http_servers(
-        sequencer_state,
+        sequencer_state.clone(),
         sequencer_config.main_port,
         sequencer_config.admin_port,
     )
@@ -236,12 +255,14 @@ async fn main() -> std::io::Result<()> {
             .spawn_local(async move {
                 let port = sequencer_config.prometheus_port;
                 info!("Starting prometheus HTTP server on port {port}...");
-                HttpServer::new(move || App::new().service(metrics))
+                let server = HttpServer::new(move || App::new().service(metrics))
                     .workers(1)
+                    .disable_signals()
                     .bind(("0.0.0.0", port))
                     .expect("Prometheus HTTP server could not bind to port.")
-                    .run()
-                    .await
+                    .run();
+                sequencer_state.shutdown.register_server(server.handle());
+                server.await
             })
             .expect("Failed to spawn prometheus server!");
         collected_futures.push(prometheus_http_server_fut);
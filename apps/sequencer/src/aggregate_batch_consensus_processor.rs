@@ -131,10 +131,12 @@ pub async fn aggregation_batch_consensus_loop(
                         info!("Generated aggregated signature: {} for network: {} Blocksense block_height: {}", signature_bytes.encode_hex(), net, block_height);
 
                         let sequencer_state_clone = sequencer_state.clone();
+                        let in_flight = sequencer_state.shutdown.track_in_flight_tx();
                         collected_futures.push(
                             tokio::task::Builder::new()
                                 .name(format!("safe_tx_sender network={net} block={block_height}").as_str())
                                 .spawn_local(async move {
+                                    let _in_flight = in_flight;
                                     let block_height = signed_aggregate.block_height;
                                     let net = &signed_aggregate.network;
                                     let providers = sequencer_state_clone.providers.read().await;
@@ -2,12 +2,14 @@ use actix_web::web::Data;
 use alloy::hex;
 use blocksense_blockchain_data_model::in_mem_db::InMemDb;
 use blocksense_blockchain_data_model::{
-    MAX_ASSET_FEED_UPDATES_IN_BLOCK, MAX_FEED_ID_TO_DELETE_IN_BLOCK, MAX_NEW_FEEDS_IN_BLOCK,
+    AssetFeedUpdate, MAX_ASSET_FEED_UPDATES_IN_BLOCK, MAX_FEED_ID_TO_DELETE_IN_BLOCK,
+    MAX_NEW_FEEDS_IN_BLOCK, MAX_UPDATED_FEEDS_IN_BLOCK,
 };
 use blocksense_config::BlockConfig;
+use blocksense_crypto::{sign_message, JsonSerializableSignature};
 use blocksense_data_feeds::feeds_processing::VotedFeedUpdateWithProof;
 use blocksense_feed_registry::feed_registration_cmds::{
-    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed,
+    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed, UpdateAssetFeed,
 };
 use blocksense_feed_registry::registry::SlotTimeTracker;
 use blocksense_feed_registry::types::Repeatability;
@@ -66,12 +68,22 @@ pub async fn block_creator_loop(
 
             let mut new_feeds_to_register = Vec::new();
             let mut feeds_ids_to_delete = Vec::new();
+            let mut feeds_to_update = Vec::new();
+            // Feeds management cmds that overflowed the capacity of a block, in the order they
+            // were received
+            let mut backlog_feed_management_cmds: VecDeque<FeedsManagementCmds> =
+                Default::default();
 
             let backlog_updates = &mut backlog_updates;
             let updates = &mut updates;
 
             let new_feeds_to_register = &mut new_feeds_to_register;
             let feeds_ids_to_delete = &mut feeds_ids_to_delete;
+            let feeds_to_update = &mut feeds_to_update;
+            let backlog_feed_management_cmds = &mut backlog_feed_management_cmds;
+
+            let mut shutdown_recv = sequencer_state.shutdown.subscribe();
+            let mut shutting_down = false;
 
             loop {
                 // Loop forever
@@ -80,12 +92,13 @@ pub async fn block_creator_loop(
                     _ = block_generation_time_tracker
                     .await_end_of_current_slot(&Repeatability::Periodic) => {
                          // Only emit a block if data is present
-                        if !updates.is_empty() || !new_feeds_to_register.is_empty() || !feeds_ids_to_delete.is_empty() {
+                        if !updates.is_empty() || !new_feeds_to_register.is_empty() || !feeds_ids_to_delete.is_empty() || !feeds_to_update.is_empty() {
                             debug!("Emitting block, since there is data present...");
                             if let Err(e) = generate_block(
                                 updates,
                                 new_feeds_to_register,
                                 feeds_ids_to_delete,
+                                feeds_to_update,
                                 &batched_votes_send,
                                 &sequencer_state,
                                 (block_generation_time_tracker.get_last_slot() + 1) as u64,
@@ -96,6 +109,7 @@ pub async fn block_creator_loop(
                             updates.clear();
                             new_feeds_to_register.clear();
                             feeds_ids_to_delete.clear();
+                            feeds_to_update.clear();
 
                             // Fill the updates that overflowed the capacity of the last block
                             while let Some(v) = backlog_updates.pop_front() {
@@ -104,16 +118,36 @@ pub async fn block_creator_loop(
                                 }
                                 updates.push(v);
                             }
+                            fill_feed_management_cmds_backlog(backlog_feed_management_cmds, new_feeds_to_register, feeds_ids_to_delete, feeds_to_update);
+                        }
+                        if shutting_down {
+                            let dropped_updates = updates.len() + backlog_updates.len();
+                            if dropped_updates > 0 {
+                                warn!("Dropping {dropped_updates} updates that did not fit in the last block before shutdown");
+                            }
+                            let dropped_cmds = new_feeds_to_register.len() + feeds_ids_to_delete.len() + feeds_to_update.len() + backlog_feed_management_cmds.len();
+                            if dropped_cmds > 0 {
+                                warn!("Dropping {dropped_cmds} feeds management cmds that did not fit in the last block before shutdown");
+                            }
+                            // Dropping `batched_votes_send` lets the votes result sender stop
+                            // once it has dispatched the batches of the last block.
+                            info!("Reached the end of the last slot before shutdown; stopping block creator");
+                            return Ok(());
                         }
                     }
 
+                    _ = shutdown_recv.changed(), if !shutting_down => {
+                        info!("Shutdown requested; block creator will stop at the end of the current slot");
+                        shutting_down = true;
+                    }
+
                     feed_update = aggregated_votes_to_block_creator_recv.recv() => {
                         debug!("Received votes over `aggregated_votes_to_block_creator_recv`: {feed_update:?}");
                         recvd_feed_update_to_block(feed_update, updates, backlog_updates, max_feed_updates_to_batch, &sequencer_state.active_feeds).await;
                     }
 
                     feed_management_cmd = feed_management_cmds_recv.recv() => {
-                        recvd_feed_management_cmd_to_block(feed_management_cmd, new_feeds_to_register, feeds_ids_to_delete);
+                        recvd_feed_management_cmd_to_block(feed_management_cmd, new_feeds_to_register, feeds_ids_to_delete, feeds_to_update, backlog_feed_management_cmds);
                     }
                 }
             }
@@ -131,22 +165,19 @@ async fn recvd_feed_update_to_block(
 ) {
     match recvd_feed_update {
         Some(voted_update) => {
-            let digits_in_fraction: usize = {
-                if let Some(feed_config) =
-                    feeds_config.read().await.get(&voted_update.update.feed_id)
-                {
-                    feed_config.additional_feed_info.decimals.into()
-                } else {
-                    error!("Propagating result for unregistered feed {}! Support left for legacy one shot feeds of 32 bytes size. Decimal default to 18", voted_update.update.feed_id);
-                    18
+            let feed_update = match feed_update_to_block(&voted_update, feeds_config).await {
+                Ok(feed_update) => feed_update,
+                Err(e) => {
+                    // Neither the block nor the contracts can hold it.
+                    error!("Dropping feed update: {e}");
+                    return;
                 }
             };
-
-            let (key, val) = voted_update.update.encode(
-                digits_in_fraction,
-                voted_update.update.end_slot_timestamp as u64,
+            info!(
+                "adding {:?} => {:?} to updates",
+                feed_update.feed_id(),
+                feed_update.feed_data
             );
-            info!("adding {:?} => {:?} to updates", key, val);
             if updates_to_block.len() < max_feed_updates_to_batch {
                 updates_to_block.push(voted_update);
             } else {
@@ -164,45 +195,118 @@ async fn recvd_feed_update_to_block(
     };
 }
 
+/// Encodes a feed update the way it is posted to the contract, to be stored in a block.
+async fn feed_update_to_block(
+    voted_update: &VotedFeedUpdateWithProof,
+    feeds_config: &Arc<RwLock<HashMap<u32, FeedConfig>>>,
+) -> anyhow::Result<AssetFeedUpdate> {
+    let digits_in_fraction = feed_digits_in_fraction(voted_update, feeds_config).await;
+    let (_, val) = voted_update.update.encode(
+        digits_in_fraction,
+        voted_update.update.end_slot_timestamp as u64,
+    )?;
+    AssetFeedUpdate::new(voted_update.update.feed_id, val)
+}
+
+async fn feed_digits_in_fraction(
+    voted_update: &VotedFeedUpdateWithProof,
+    feeds_config: &Arc<RwLock<HashMap<u32, FeedConfig>>>,
+) -> usize {
+    if let Some(feed_config) = feeds_config.read().await.get(&voted_update.update.feed_id) {
+        feed_config.additional_feed_info.decimals.into()
+    } else {
+        error!("Propagating result for unregistered feed {}! Support left for legacy one shot feeds of 32 bytes size. Decimal default to 18", voted_update.update.feed_id);
+        18
+    }
+}
+
 // When we recv feed management commands, we prepare them to be placed in the next generated block
 fn recvd_feed_management_cmd_to_block(
     feed_management_cmd: Option<FeedsManagementCmds>,
     new_feeds_to_register: &mut Vec<RegisterNewAssetFeed>,
     feeds_ids_to_delete: &mut Vec<DeleteAssetFeed>,
+    feeds_to_update: &mut Vec<UpdateAssetFeed>,
+    backlog_cmds: &mut VecDeque<FeedsManagementCmds>,
 ) {
     match feed_management_cmd {
-        Some(cmd) => match cmd {
-            FeedsManagementCmds::RegisterNewAssetFeed(reg_cmd) => {
-                if new_feeds_to_register.len() < MAX_NEW_FEEDS_IN_BLOCK {
-                    new_feeds_to_register.push(reg_cmd);
-                } else {
-                    error!(
-                        "new_feeds_to_register.len() >= MAX_NEW_FEEDS_IN_BLOCK ({} >= {})",
-                        new_feeds_to_register.len(),
-                        MAX_NEW_FEEDS_IN_BLOCK
-                    );
-                }
+        Some(cmd) => {
+            // Nothing overtakes the backlog, so that the cmds of a feed are applied in order.
+            if !backlog_cmds.is_empty() {
+                backlog_cmds.push_back(cmd);
+                return;
             }
-            FeedsManagementCmds::DeleteAssetFeed(rm_cmd) => {
-                if feeds_ids_to_delete.len() < MAX_FEED_ID_TO_DELETE_IN_BLOCK {
-                    feeds_ids_to_delete.push(rm_cmd);
-                } else {
-                    error!(
-                        "feeds_ids_to_delete.len() < MAX_FEED_ID_TO_DELETE_IN_BLOCK ({} >= {})",
-                        feeds_ids_to_delete.len(),
-                        MAX_FEED_ID_TO_DELETE_IN_BLOCK
-                    );
-                }
+            if let Err(cmd) = add_feed_management_cmd_to_block(
+                cmd,
+                new_feeds_to_register,
+                feeds_ids_to_delete,
+                feeds_to_update,
+            ) {
+                warn!("Block is full, feeds management cmd is moved to the next block");
+                backlog_cmds.push_back(cmd);
             }
-        },
+        }
         None => info!("Woke up on empty channel - feed_management_cmds_recv"),
     }
 }
 
+// Moves the feeds management cmds that overflowed the capacity of the last block to the next one
+fn fill_feed_management_cmds_backlog(
+    backlog_cmds: &mut VecDeque<FeedsManagementCmds>,
+    new_feeds_to_register: &mut Vec<RegisterNewAssetFeed>,
+    feeds_ids_to_delete: &mut Vec<DeleteAssetFeed>,
+    feeds_to_update: &mut Vec<UpdateAssetFeed>,
+) {
+    while let Some(cmd) = backlog_cmds.pop_front() {
+        if let Err(cmd) = add_feed_management_cmd_to_block(
+            cmd,
+            new_feeds_to_register,
+            feeds_ids_to_delete,
+            feeds_to_update,
+        ) {
+            backlog_cmds.push_front(cmd);
+            break;
+        }
+    }
+}
+
+/// Returns the cmd back if there is no room left for it in the block.
+fn add_feed_management_cmd_to_block(
+    cmd: FeedsManagementCmds,
+    new_feeds_to_register: &mut Vec<RegisterNewAssetFeed>,
+    feeds_ids_to_delete: &mut Vec<DeleteAssetFeed>,
+    feeds_to_update: &mut Vec<UpdateAssetFeed>,
+) -> Result<(), FeedsManagementCmds> {
+    match cmd {
+        FeedsManagementCmds::RegisterNewAssetFeed(reg_cmd) => {
+            if new_feeds_to_register.len() < MAX_NEW_FEEDS_IN_BLOCK {
+                new_feeds_to_register.push(reg_cmd);
+            } else {
+                return Err(FeedsManagementCmds::RegisterNewAssetFeed(reg_cmd));
+            }
+        }
+        FeedsManagementCmds::DeleteAssetFeed(rm_cmd) => {
+            if feeds_ids_to_delete.len() < MAX_FEED_ID_TO_DELETE_IN_BLOCK {
+                feeds_ids_to_delete.push(rm_cmd);
+            } else {
+                return Err(FeedsManagementCmds::DeleteAssetFeed(rm_cmd));
+            }
+        }
+        FeedsManagementCmds::UpdateAssetFeed(update_cmd) => {
+            if feeds_to_update.len() < MAX_UPDATED_FEEDS_IN_BLOCK {
+                feeds_to_update.push(update_cmd);
+            } else {
+                return Err(FeedsManagementCmds::UpdateAssetFeed(update_cmd));
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn generate_block(
     updates: &mut Vec<VotedFeedUpdateWithProof>,
     new_feeds_to_register: &mut Vec<RegisterNewAssetFeed>,
     feeds_ids_to_delete: &mut Vec<DeleteAssetFeed>,
+    feeds_to_update: &mut Vec<UpdateAssetFeed>,
     batched_votes_send: &UnboundedSender<BatchedAggegratesToSend>,
     sequencer_state: &Data<SequencerState>,
     block_height: u64,
@@ -210,7 +314,8 @@ async fn generate_block(
     let sequencer_id = sequencer_state.sequencer_config.read().await.sequencer_id;
     let new_feeds_to_register = mem::take(new_feeds_to_register);
     let feeds_ids_to_delete = mem::take(feeds_ids_to_delete);
-    let updates = mem::take(updates);
+    let feeds_to_update = mem::take(feeds_to_update);
+    let received_updates = mem::take(updates);
 
     let mut new_feeds_in_block = Vec::new();
     for register_new_asset_feed in &new_feeds_to_register {
@@ -222,19 +327,47 @@ async fn generate_block(
         feeds_ids_to_delete_in_block.push(delete_feed_id_cmd.id);
     }
 
-    let block_is_empty = new_feeds_to_register.is_empty() && feeds_ids_to_delete.is_empty();
+    let mut updated_feeds_in_block = Vec::new();
+    for update_asset_feed in &feeds_to_update {
+        updated_feeds_in_block.push(feed_config_to_block(&update_asset_feed.config));
+    }
+
+    // Updates that can not be added to the block are not sent to the contracts either.
+    let mut feed_updates_in_block = Vec::new();
+    let mut updates = Vec::new();
+    for v in received_updates {
+        match feed_update_to_block(&v, &sequencer_state.active_feeds).await {
+            Ok(feed_update) => {
+                feed_updates_in_block.push(feed_update);
+                updates.push(v);
+            }
+            Err(e) => error!(
+                "Dropping update of feed {} that can not be added to block {block_height}: {e}",
+                v.update.feed_id
+            ),
+        }
+    }
+
+    let block_is_empty = new_feeds_to_register.is_empty()
+        && feeds_ids_to_delete.is_empty()
+        && feeds_to_update.is_empty()
+        && updates.is_empty();
     let mut serialized_header = Vec::new();
     let mut serialized_feed_actions = Vec::new();
+    let mut serialized_feed_updates = Vec::new();
+    let mut header_signature = None;
     // Block holding the db write mutex:
     if !block_is_empty {
         // Create the block that will contain the new feeds, deleted feeds and updates of feed values
         let mut blockchain_db = sequencer_state.blockchain_db.write().await;
-        let (header, feed_actions) = blockchain_db
+        let (header, feed_actions, feed_updates) = blockchain_db
             .create_new_block(
                 sequencer_id,
                 block_height,
                 new_feeds_in_block,
                 feeds_ids_to_delete_in_block,
+                updated_feeds_in_block,
+                feed_updates_in_block,
             )
             .map_err(|e| eyre::eyre!(e.to_string()))?;
         serialized_header = match header.clone().serialize() {
@@ -249,14 +382,28 @@ async fn generate_block(
                 panic!("could not serialize block feed_actions: {e}")
             }
         };
+        serialized_feed_updates = match feed_updates.clone().serialize() {
+            Ok(res) => res,
+            Err(e) => {
+                panic!("could not serialize block feed_updates: {e}")
+            }
+        };
         let header_merkle_root = InMemDb::calc_merkle_root(&mut header.clone())
             .map_err(|e| eyre::eyre!(e.to_string()))?;
+        let header_hash =
+            InMemDb::node_to_hash(header_merkle_root).map_err(|e| eyre::eyre!(e.to_string()))?;
         info!(
             "Generated new block {:?} with hash {:?}",
-            header,
-            InMemDb::node_to_hash(header_merkle_root).map_err(|e| eyre::eyre!(e.to_string()))?
+            header, header_hash
         );
-        if let Err(e) = blockchain_db.add_next_block(header, feed_actions) {
+        header_signature =
+            sequencer_state
+                .block_signing_key
+                .as_ref()
+                .map(|sk| JsonSerializableSignature {
+                    sig: sign_message(sk, &header_hash),
+                });
+        if let Err(e) = blockchain_db.add_next_block(header, feed_actions, feed_updates) {
             eyre::bail!(e.to_string());
         }
     }
@@ -265,13 +412,10 @@ async fn generate_block(
     if !updates.is_empty() {
         debug!("Sending batched votes over `batched_votes_send`...");
 
-        let mut value_updates = Vec::new();
-        let mut proofs = HashMap::new();
-        for v in updates {
-            let feed_id = v.update.feed_id;
-            value_updates.push(v.update);
-            proofs.insert(feed_id, v.proof);
-        }
+        let value_updates: Vec<_> = updates.into_iter().map(|v| v.update).collect();
+        sequencer_state
+            .published_updates
+            .publish_feed_updates(block_height, &value_updates);
 
         if let Err(e) = batched_votes_send.send(BatchedAggegratesToSend {
             block_height,
@@ -306,10 +450,23 @@ async fn generate_block(
         };
     }
 
+    // Process cmds to update the configs of existing feeds:
+    for cmd in feeds_to_update {
+        match sequencer_state
+            .feeds_slots_manager_cmd_send
+            .send(FeedsManagementCmds::UpdateAssetFeed(cmd))
+        {
+            Ok(_) => info!("forward update cmd"),
+            Err(e) => error!("Could not forward update cmd: {e}"),
+        };
+    }
+
     if !block_is_empty {
         let block_to_kafka = json!({
             "BlockHeader": hex::encode(serialized_header),
             "FeedActions": hex::encode(serialized_feed_actions),
+            "FeedUpdates": hex::encode(serialized_feed_updates),
+            "Signature": header_signature,
         });
 
         if let Some(kafka_endpoint) = &sequencer_state.kafka_endpoint {
@@ -334,12 +491,18 @@ async fn generate_block(
 #[cfg(test)]
 mod tests {
     use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use blocksense_blockchain_data_model::MAX_NEW_FEEDS_IN_BLOCK;
     use blocksense_config::get_test_config_with_no_providers;
+    use blocksense_config::test_feed_config;
     use blocksense_config::AllFeedsConfig;
     use blocksense_config::BlockConfig;
     use blocksense_data_feeds::feeds_processing::VotedFeedUpdate;
     use blocksense_data_feeds::feeds_processing::VotedFeedUpdateWithProof;
+    use blocksense_feed_registry::feed_registration_cmds::{
+        DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed,
+    };
     use blocksense_feed_registry::types::{FeedType, Timestamp};
+    use blocksense_registry::config::NumericalEncoding;
     use std::time::Duration;
     use tokio::sync::mpsc;
     use tokio::time;
@@ -375,7 +538,7 @@ mod tests {
             .aggregated_votes_to_block_creator_send
             .clone();
         super::block_creator_loop(
-            sequencer_state,
+            sequencer_state.clone(),
             aggregated_votes_to_block_creator_recv,
             feeds_management_cmd_to_block_creator_recv,
             batched_votes_send,
@@ -394,6 +557,7 @@ mod tests {
                 end_of_timeslot,
                 FeedType::Numerical(0.0),
                 18,
+                NumericalEncoding::Unsigned,
             )
             .unwrap(),
             proof: Vec::new(),
@@ -407,6 +571,7 @@ mod tests {
                 end_of_timeslot,
                 FeedType::Numerical(0.0),
                 18,
+                NumericalEncoding::Unsigned,
             )
             .unwrap(),
             proof: Vec::new(),
@@ -420,6 +585,7 @@ mod tests {
                 end_of_timeslot,
                 FeedType::Numerical(0.0),
                 18,
+                NumericalEncoding::Unsigned,
             )
             .unwrap(),
             proof: Vec::new(),
@@ -433,6 +599,7 @@ mod tests {
                 end_of_timeslot,
                 FeedType::Numerical(0.0),
                 18,
+                NumericalEncoding::Unsigned,
             )
             .unwrap(),
             proof: Vec::new(),
@@ -452,5 +619,181 @@ mod tests {
         } else {
             panic!("Batched votes were not received");
         }
+
+        // Both blocks commit to the updates they published.
+        let blockchain_db = sequencer_state.blockchain_db.read().await;
+        let latest_height = blockchain_db.get_latest_block_height();
+        let header = blockchain_db
+            .get_block_header_by_height(latest_height)
+            .expect("Latest block must be stored");
+        let feed_updates = blockchain_db
+            .get_feed_updates(&header.feed_updates_merkle_root)
+            .expect("Feed updates of the block must be stored");
+        let feed_ids: Vec<u32> = feed_updates.iter().map(|u| u.feed_id()).collect();
+        assert_eq!(feed_ids, vec![0xaf000004]);
+        let prev_header = blockchain_db
+            .get_block_header_by_hash(header.prev_block_hash)
+            .expect("Previous block must be stored");
+        let prev_feed_updates = blockchain_db
+            .get_feed_updates(&prev_header.feed_updates_merkle_root)
+            .unwrap();
+        assert_eq!(prev_feed_updates.iter().count(), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_block_creator_drops_updates_that_do_not_fit_in_block() {
+        let block_config = BlockConfig {
+            max_feed_updates_to_batch: 3,
+            block_generation_period: 100,
+            genesis_block_timestamp_ms: None,
+            aggregation_consensus_discard_period_blocks: 100,
+        };
+        let (
+            sequencer_state,
+            aggregated_votes_to_block_creator_recv,
+            feeds_management_cmd_to_block_creator_recv,
+            _feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_block_creator_drops_updates_that_do_not_fit_in_block",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+
+        let (batched_votes_send, mut batched_votes_recv) = mpsc::unbounded_channel();
+        let vote_send = sequencer_state
+            .aggregated_votes_to_block_creator_send
+            .clone();
+        super::block_creator_loop(
+            sequencer_state.clone(),
+            aggregated_votes_to_block_creator_recv,
+            feeds_management_cmd_to_block_creator_recv,
+            batched_votes_send,
+            block_config,
+        )
+        .await;
+
+        let vote = |feed_id, value| VotedFeedUpdateWithProof {
+            update: VotedFeedUpdate {
+                feed_id,
+                value,
+                end_slot_timestamp: 0,
+            },
+            proof: Vec::new(),
+        };
+        vote_send
+            .send(vote(1, FeedType::Bytes(vec![1; 64 * 1024])))
+            .unwrap();
+        vote_send
+            .send(vote(2, FeedType::Bytes(vec![2; 32])))
+            .unwrap();
+
+        time::sleep(Duration::from_millis(500)).await;
+        let batched = batched_votes_recv
+            .recv()
+            .await
+            .expect("Batched votes were not received");
+        let feed_ids: Vec<u32> = batched.updates.iter().map(|u| u.feed_id).collect();
+        assert_eq!(feed_ids, vec![2]);
+        assert!(batched_votes_recv.is_empty());
+
+        let blockchain_db = sequencer_state.blockchain_db.read().await;
+        let header = blockchain_db
+            .get_block_header_by_height(blockchain_db.get_latest_block_height())
+            .expect("Latest block must be stored");
+        let feed_updates = blockchain_db
+            .get_feed_updates(&header.feed_updates_merkle_root)
+            .unwrap();
+        let feed_ids: Vec<u32> = feed_updates.iter().map(|u| u.feed_id()).collect();
+        assert_eq!(feed_ids, vec![2]);
+    }
+
+    #[actix_web::test]
+    async fn test_block_creator_moves_feed_management_cmds_over_block_capacity_to_next_block() {
+        let block_config = BlockConfig {
+            max_feed_updates_to_batch: 3,
+            block_generation_period: 100,
+            genesis_block_timestamp_ms: None,
+            aggregation_consensus_discard_period_blocks: 100,
+        };
+        let (
+            sequencer_state,
+            aggregated_votes_to_block_creator_recv,
+            feeds_management_cmd_to_block_creator_recv,
+            mut feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_block_creator_moves_feed_management_cmds_over_block_capacity_to_next_block",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+
+        let cmd_send = sequencer_state
+            .feeds_management_cmd_to_block_creator_send
+            .clone();
+        let registered = MAX_NEW_FEEDS_IN_BLOCK as u32 + 1;
+        for id in 0..registered {
+            cmd_send
+                .send(FeedsManagementCmds::RegisterNewAssetFeed(
+                    RegisterNewAssetFeed {
+                        config: test_feed_config(id, 0),
+                    },
+                ))
+                .unwrap();
+        }
+        cmd_send
+            .send(FeedsManagementCmds::DeleteAssetFeed(DeleteAssetFeed {
+                id: 0,
+            }))
+            .unwrap();
+
+        let (batched_votes_send, _batched_votes_recv) = mpsc::unbounded_channel();
+        super::block_creator_loop(
+            sequencer_state.clone(),
+            aggregated_votes_to_block_creator_recv,
+            feeds_management_cmd_to_block_creator_recv,
+            batched_votes_send,
+            block_config,
+        )
+        .await;
+        time::sleep(Duration::from_millis(500)).await;
+
+        // Every cmd is forwarded once its block is generated, in the order it was received.
+        let mut forwarded = Vec::new();
+        while let Ok(cmd) = feeds_slots_manager_cmd_recv.try_recv() {
+            forwarded.push(match cmd {
+                FeedsManagementCmds::RegisterNewAssetFeed(cmd) => (true, cmd.config.id),
+                FeedsManagementCmds::DeleteAssetFeed(cmd) => (false, cmd.id),
+                FeedsManagementCmds::UpdateAssetFeed(_) => panic!("Unexpected update cmd"),
+            });
+        }
+        let mut expected: Vec<(bool, u32)> = (0..registered).map(|id| (true, id)).collect();
+        expected.push((false, 0));
+        assert_eq!(forwarded, expected);
+
+        let blockchain_db = sequencer_state.blockchain_db.read().await;
+        let mut new_feeds = Vec::new();
+        let mut blocks = 0;
+        let mut header = blockchain_db
+            .get_block_header_by_height(blockchain_db.get_latest_block_height())
+            .expect("Latest block must be stored");
+        while let Some(feed_actions) =
+            blockchain_db.get_feed_actions(&header.add_remove_feeds_merkle_root)
+        {
+            if feed_actions.new_feeds.iter().any(Option::is_some) {
+                new_feeds.extend(feed_actions.new_feeds.iter().flatten().map(|f| f.id));
+                blocks += 1;
+            }
+            match blockchain_db.get_block_header_by_hash(header.prev_block_hash) {
+                Some(prev_header) => header = prev_header,
+                None => break,
+            }
+        }
+        new_feeds.sort();
+        assert_eq!(new_feeds, (0..registered).collect::<Vec<_>>());
+        // Registering all the feeds took more than one block.
+        assert!(blocks >= 2);
     }
 }
@@ -1,13 +1,17 @@
 use actix_web::web::Data;
 use alloy::hex;
+use blocksense_blockchain_data_model::block_store::check_feed_actions;
+use blocksense_blockchain_data_model::in_mem_db::InMemDb;
 use blocksense_blockchain_data_model::{BlockHeader, FeedActions};
+use blocksense_crypto::{verify_signature, JsonSerializableSignature, PublicKey};
 use blocksense_feed_registry::feed_registration_cmds::{
-    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed,
+    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed, UpdateAssetFeed,
 };
 use eyre::Result;
 use rdkafka::config::ClientConfig;
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::message::{BorrowedMessage, Message};
+use std::collections::HashMap;
 use std::io::Error;
 use tokio_stream::StreamExt;
 use tracing::{debug, error, info, warn};
@@ -97,6 +101,55 @@ async fn process_msg_from_stream(
     }
 }
 
+/// Checks that the header of a streamed block is signed by the sequencer that issued it.
+fn verify_block_signature(
+    sequencer_pub_keys: &HashMap<u64, PublicKey>,
+    header: &BlockHeader,
+    signature: &serde_json::Value,
+) -> Result<()> {
+    let Some(pub_key) = sequencer_pub_keys.get(&header.issuer_id) else {
+        eyre::bail!("No pub key for block issuer {}", header.issuer_id);
+    };
+    let signature: JsonSerializableSignature = match serde_json::from_value(signature.clone()) {
+        Ok(s) => s,
+        Err(e) => eyre::bail!("Missing or malformed block signature: {e}"),
+    };
+    let header_hash = InMemDb::calc_merkle_root(&mut header.clone())
+        .and_then(InMemDb::node_to_hash)
+        .map_err(|e| eyre::eyre!(e.to_string()))?;
+    if !verify_signature(pub_key, &signature.sig, &header_hash) {
+        eyre::bail!(
+            "Signature of block {} does not match the key of issuer {}",
+            header.block_height,
+            header.issuer_id
+        );
+    }
+    Ok(())
+}
+
+/// Decodes the feed actions of a streamed block and checks that they are the ones its header
+/// commits to.
+fn decode_feed_actions(
+    header: &BlockHeader,
+    block: &serde_json::Value,
+) -> Result<Option<FeedActions>> {
+    let Some(feed_actions) = block["FeedActions"].as_str() else {
+        return Ok(None);
+    };
+    let bytes = match hex::decode(feed_actions) {
+        Ok(bytes) => bytes,
+        Err(e) => eyre::bail!("Decoding FeedActions failed: {e}"),
+    };
+    let feed_actions = match FeedActions::deserialize(&bytes) {
+        Ok(fa) => fa,
+        Err(e) => eyre::bail!("FeedActions::deserialize error: {e}"),
+    };
+    if let Err(e) = check_feed_actions(header, &feed_actions) {
+        eyre::bail!("Block {} rejected: {e}", header.block_height);
+    }
+    Ok(Some(feed_actions))
+}
+
 async fn process_block(
     sequencer_id: u64,
     sequencer_state: &Data<SequencerState>,
@@ -110,12 +163,19 @@ async fn process_block(
                         Ok(h) => h,
                         Err(e) => eyre::bail!("BlockHeader::deserialize error: {e}"),
                     };
+                    if !sequencer_state.sequencer_pub_keys.is_empty() {
+                        verify_block_signature(
+                            &sequencer_state.sequencer_pub_keys,
+                            &header,
+                            &block["Signature"],
+                        )?;
+                    }
                     // A sequencer needs to process blocks that come from peer sequencers
                     // and all the blocks that it has emitted with block height
                     // higher than what it has in storage. A scenario of a sequencer
                     // having in storage a lower block height compared to the messages
-                    // it has posted to the message queue are on restart, since we don't
-                    // yet have persistent storage.
+                    // it has posted to the message queue are on restart, unless its
+                    // blocks are kept in a persistent block store.
                     let process_block = header.issuer_id != sequencer_id
                         || sequencer_state
                             .blockchain_db
@@ -124,42 +184,49 @@ async fn process_block(
                             .get_latest_block_height()
                             < header.block_height;
                     if process_block {
-                        if let Some(feed_actions) = block["FeedActions"].as_str() {
-                            if let Ok(bytes) = hex::decode(feed_actions) {
-                                let feed_actions = match FeedActions::deserialize(&bytes) {
-                                    Ok(fa) => fa,
-                                    Err(e) => eyre::bail!("FeedActions::deserialize error: {e}"),
+                        if let Some(feed_actions) = decode_feed_actions(&header, block)? {
+                            for new_block_feed in feed_actions.new_feeds.into_iter().flatten() {
+                                let new_feed_config = block_feed_to_feed_config(&new_block_feed);
+                                info!("new_feed_config = {:?}", new_feed_config);
+                                let cmd = FeedsManagementCmds::RegisterNewAssetFeed(
+                                    RegisterNewAssetFeed {
+                                        config: new_feed_config.clone(),
+                                    },
+                                );
+                                match sequencer_state.feeds_slots_manager_cmd_send.send(cmd) {
+                                    Ok(_) => info!("forward register cmd"),
+                                    Err(e) => {
+                                        error!("Could not forward register cmd: {e}")
+                                    }
+                                };
+                            }
+                            for rm_feed_id in feed_actions.feed_ids_to_rm.into_iter().flatten() {
+                                info!("rm_feed_id = {:?}", rm_feed_id);
+                                let cmd = FeedsManagementCmds::DeleteAssetFeed(DeleteAssetFeed {
+                                    id: rm_feed_id,
+                                });
+                                match sequencer_state.feeds_slots_manager_cmd_send.send(cmd) {
+                                    Ok(_) => info!("forward remove cmd"),
+                                    Err(e) => {
+                                        error!("Could not forward remove cmd: {e}")
+                                    }
+                                };
+                            }
+                            for updated_block_feed in
+                                feed_actions.updated_feeds.into_iter().flatten()
+                            {
+                                let updated_feed_config =
+                                    block_feed_to_feed_config(&updated_block_feed);
+                                info!("updated_feed_config = {:?}", updated_feed_config);
+                                let cmd = FeedsManagementCmds::UpdateAssetFeed(UpdateAssetFeed {
+                                    config: updated_feed_config,
+                                });
+                                match sequencer_state.feeds_slots_manager_cmd_send.send(cmd) {
+                                    Ok(_) => info!("forward update cmd"),
+                                    Err(e) => {
+                                        error!("Could not forward update cmd: {e}")
+                                    }
                                 };
-                                for new_block_feed in feed_actions.new_feeds.into_iter().flatten() {
-                                    let new_feed_config =
-                                        block_feed_to_feed_config(&new_block_feed);
-                                    info!("new_feed_config = {:?}", new_feed_config);
-                                    let cmd = FeedsManagementCmds::RegisterNewAssetFeed(
-                                        RegisterNewAssetFeed {
-                                            config: new_feed_config.clone(),
-                                        },
-                                    );
-                                    match sequencer_state.feeds_slots_manager_cmd_send.send(cmd) {
-                                        Ok(_) => info!("forward register cmd"),
-                                        Err(e) => {
-                                            error!("Could not forward register cmd: {e}")
-                                        }
-                                    };
-                                }
-                                for rm_feed_id in feed_actions.feed_ids_to_rm.into_iter().flatten()
-                                {
-                                    info!("rm_feed_id = {:?}", rm_feed_id);
-                                    let cmd =
-                                        FeedsManagementCmds::DeleteAssetFeed(DeleteAssetFeed {
-                                            id: rm_feed_id,
-                                        });
-                                    match sequencer_state.feeds_slots_manager_cmd_send.send(cmd) {
-                                        Ok(_) => info!("forward remove cmd"),
-                                        Err(e) => {
-                                            error!("Could not forward remove cmd: {e}")
-                                        }
-                                    };
-                                }
                             }
                         }
                     }
@@ -175,3 +242,77 @@ async fn process_block(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocksense_crypto::{generate_keys, sign_message, SecretKey};
+
+    #[test]
+    fn test_verify_block_signature() {
+        let (sk, pk) = generate_keys(&[1; 35]);
+        let (other_sk, _) = generate_keys(&[2; 35]);
+        let pub_keys = HashMap::from([(1, pk)]);
+        let header = BlockHeader {
+            issuer_id: 1,
+            block_height: 7,
+            ..Default::default()
+        };
+        let header_hash =
+            InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut header.clone()).unwrap()).unwrap();
+        let sign = |sk: &SecretKey| {
+            serde_json::to_value(JsonSerializableSignature {
+                sig: sign_message(sk, &header_hash),
+            })
+            .unwrap()
+        };
+
+        assert!(verify_block_signature(&pub_keys, &header, &sign(&sk)).is_ok());
+        assert!(verify_block_signature(&pub_keys, &header, &sign(&other_sk)).is_err());
+        assert!(verify_block_signature(&pub_keys, &header, &serde_json::Value::Null).is_err());
+
+        let unknown_issuer = BlockHeader {
+            issuer_id: 2,
+            ..header.clone()
+        };
+        assert!(verify_block_signature(&pub_keys, &unknown_issuer, &sign(&sk)).is_err());
+    }
+
+    #[test]
+    fn test_decode_feed_actions() {
+        let mut feed_actions = FeedActions {
+            block_height: 7,
+            ..Default::default()
+        };
+        feed_actions.feed_ids_to_rm[0] = Some(42);
+        let header = BlockHeader {
+            block_height: 7,
+            add_remove_feeds_merkle_root: InMemDb::node_to_hash(
+                InMemDb::calc_merkle_root(&mut feed_actions.clone()).unwrap(),
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let block = |feed_actions: &FeedActions| {
+            serde_json::json!({
+                "FeedActions": hex::encode(feed_actions.clone().serialize().unwrap()),
+            })
+        };
+
+        assert_eq!(
+            decode_feed_actions(&header, &block(&feed_actions)).unwrap(),
+            Some(feed_actions.clone())
+        );
+        assert_eq!(
+            decode_feed_actions(&header, &serde_json::json!({})).unwrap(),
+            None
+        );
+        assert!(
+            decode_feed_actions(&header, &serde_json::json!({"FeedActions": "not hex"})).is_err()
+        );
+
+        let mut tampered = feed_actions.clone();
+        tampered.feed_ids_to_rm[0] = Some(43);
+        assert!(decode_feed_actions(&header, &block(&tampered)).is_err());
+    }
+}
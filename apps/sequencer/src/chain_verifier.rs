@@ -0,0 +1,251 @@
+//! Offline verification of a dump of the `blockchain` topic.
+//!
+//! The dump holds one block per line, in the JSON format produced by `generate_block`.
+//! Every issuer's chain is replayed into its own `InMemDb`, which checks the
+//! `prev_block_hash` linkage and the merkle roots of the block contents, while the feed
+//! actions of all blocks are applied in order to the feeds the sequencers started with.
+//!
+//! A sequencer without a persistent block store starts a new chain from genesis when it
+//! restarts, with the feeds of its config. A block of a known issuer referring to genesis
+//! therefore starts a new segment of its chain, and resets the feeds to the initial ones.
+
+use alloy::hex;
+use blocksense_blockchain_data_model::block_store::{BlockStore, GENESIS_HASH};
+use blocksense_blockchain_data_model::in_mem_db::InMemDb;
+use blocksense_blockchain_data_model::{BlockFeedConfig, BlockHeader, FeedActions, FeedUpdates};
+use blocksense_registry::config::FeedConfig;
+use eyre::{eyre, Result};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+
+use crate::feeds::feed_config_conversions::feed_config_to_block;
+
+#[derive(Debug, Default)]
+pub struct ReplayedChain {
+    /// Number of blocks verified per issuer.
+    pub blocks_per_issuer: BTreeMap<u64, u64>,
+    /// Number of times an issuer's chain started again from genesis.
+    pub restarts_per_issuer: BTreeMap<u64, u64>,
+    /// Feeds registered and not deleted by the blocks, by feed id, as they are on chain.
+    pub feeds: BTreeMap<u32, BlockFeedConfig>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct FeedsDiff {
+    /// Feeds registered on chain that are missing from the feeds config.
+    pub only_on_chain: Vec<u32>,
+    /// Feeds in the feeds config that were never registered on chain.
+    pub only_in_config: Vec<u32>,
+    /// Feeds present in both whose configs differ.
+    pub different: Vec<u32>,
+}
+
+impl FeedsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_on_chain.is_empty() && self.only_in_config.is_empty() && self.different.is_empty()
+    }
+}
+
+fn decode_part<T>(
+    block: &serde_json::Value,
+    part: &str,
+    deserialize: impl Fn(&[u8]) -> anyhow::Result<T>,
+) -> Result<T> {
+    let Some(hex_str) = block[part].as_str() else {
+        eyre::bail!("Missing {part}");
+    };
+    let bytes = hex::decode(hex_str).map_err(|e| eyre!("Decoding {part} failed: {e}"))?;
+    deserialize(&bytes).map_err(|e| eyre!("{part}::deserialize error: {e}"))
+}
+
+fn block_feeds(feeds: &[FeedConfig]) -> BTreeMap<u32, BlockFeedConfig> {
+    feeds
+        .iter()
+        .map(|feed| (feed.id, feed_config_to_block(feed)))
+        .collect()
+}
+
+/// Verifies the blocks in `dump` and rebuilds the feeds they leave registered, starting from
+/// `initial_feeds`, the feeds of the config the sequencers were started with.
+pub fn replay_blocks(dump: &str, initial_feeds: &[FeedConfig]) -> Result<ReplayedChain> {
+    let mut chains: HashMap<u64, InMemDb> = HashMap::new();
+    let mut replayed = ReplayedChain {
+        feeds: block_feeds(initial_feeds),
+        ..Default::default()
+    };
+    for (line_number, line) in dump.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+        if line.is_empty() {
+            continue;
+        }
+        let block: serde_json::Value = serde_json::from_str(line)
+            .map_err(|e| eyre!("Line {line_number}: Error parsing block: {e}"))?;
+        let header = decode_part(&block, "BlockHeader", BlockHeader::deserialize)
+            .map_err(|e| eyre!("Line {line_number}: {e}"))?;
+        let feed_actions = decode_part(&block, "FeedActions", FeedActions::deserialize)
+            .map_err(|e| eyre!("Line {line_number}: {e}"))?;
+        let feed_updates = decode_part(&block, "FeedUpdates", FeedUpdates::deserialize)
+            .map_err(|e| eyre!("Line {line_number}: {e}"))?;
+
+        let issuer_id = header.issuer_id;
+        let block_height = header.block_height;
+        if header.prev_block_hash == GENESIS_HASH && chains.remove(&issuer_id).is_some() {
+            info!("Line {line_number}: Sequencer {issuer_id} restarted at block {block_height}");
+            *replayed.restarts_per_issuer.entry(issuer_id).or_default() += 1;
+            replayed.feeds = block_feeds(initial_feeds);
+        }
+        chains
+            .entry(issuer_id)
+            .or_insert_with(InMemDb::new)
+            .add_next_block(header, feed_actions.clone(), feed_updates)
+            .map_err(|e| {
+                eyre!("Line {line_number}: Invalid block {block_height} of issuer {issuer_id}: {e}")
+            })?;
+        *replayed.blocks_per_issuer.entry(issuer_id).or_default() += 1;
+
+        for new_feed in feed_actions.new_feeds.iter().flatten() {
+            if replayed
+                .feeds
+                .insert(new_feed.id, new_feed.clone())
+                .is_some()
+            {
+                warn!("Line {line_number}: Feed {} registered again", new_feed.id);
+            }
+        }
+        for feed_id in feed_actions.feed_ids_to_rm.iter().flatten() {
+            if replayed.feeds.remove(feed_id).is_none() {
+                warn!("Line {line_number}: Deleting feed {feed_id} which is not registered");
+            }
+        }
+        for updated_feed in feed_actions.updated_feeds.iter().flatten() {
+            if replayed
+                .feeds
+                .insert(updated_feed.id, updated_feed.clone())
+                .is_none()
+            {
+                warn!(
+                    "Line {line_number}: Updating feed {} which is not registered",
+                    updated_feed.id
+                );
+            }
+        }
+    }
+    Ok(replayed)
+}
+
+/// Compares the feeds rebuilt from the chain to `feeds_config`. The configs are compared in
+/// the form they take in blocks, as blocks carry only a part of the fields.
+pub fn diff_feeds(
+    chain_feeds: &BTreeMap<u32, BlockFeedConfig>,
+    feeds_config: &[FeedConfig],
+) -> FeedsDiff {
+    let config_feeds = block_feeds(feeds_config);
+    let mut diff = FeedsDiff::default();
+    for (id, feed) in chain_feeds {
+        match config_feeds.get(id) {
+            Some(config_feed) if config_feed != feed => diff.different.push(*id),
+            Some(_) => {}
+            None => diff.only_on_chain.push(*id),
+        }
+    }
+    diff.only_in_config = config_feeds
+        .keys()
+        .filter(|id| !chain_feeds.contains_key(id))
+        .copied()
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocksense_config::test_feed_config;
+    use serde_json::json;
+
+    fn block_to_json(header: BlockHeader, actions: FeedActions, updates: FeedUpdates) -> String {
+        json!({
+            "BlockHeader": hex::encode(header.clone().serialize().unwrap()),
+            "FeedActions": hex::encode(actions.clone().serialize().unwrap()),
+            "FeedUpdates": hex::encode(updates.clone().serialize().unwrap()),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_replay_blocks() {
+        let initial_feeds = [test_feed_config(1, 0)];
+        let mut db = InMemDb::new();
+        let mut dump = Vec::new();
+        let mut push_block = |db: &mut InMemDb, height, new_feeds, to_delete| {
+            let (header, actions, updates) = db
+                .create_new_block(1, height, new_feeds, to_delete, vec![], vec![])
+                .unwrap();
+            dump.push(block_to_json(
+                header.clone(),
+                actions.clone(),
+                updates.clone(),
+            ));
+            db.add_next_block(header, actions, updates).unwrap();
+            dump.join("\n")
+        };
+        push_block(
+            &mut db,
+            1,
+            vec![feed_config_to_block(&test_feed_config(2, 0))],
+            vec![],
+        );
+        let blocks = push_block(
+            &mut db,
+            2,
+            vec![feed_config_to_block(&test_feed_config(3, 0))],
+            vec![1],
+        );
+
+        let replayed = replay_blocks(&blocks, &initial_feeds).unwrap();
+        assert_eq!(replayed.blocks_per_issuer, BTreeMap::from([(1, 2)]));
+        assert!(replayed.restarts_per_issuer.is_empty());
+        assert_eq!(replayed.feeds.keys().collect::<Vec<_>>(), vec![&2, &3]);
+
+        let diff = diff_feeds(
+            &replayed.feeds,
+            &[test_feed_config(2, 0), test_feed_config(3, 0)],
+        );
+        assert!(diff.is_empty());
+        let diff = diff_feeds(
+            &replayed.feeds,
+            &[test_feed_config(2, 1), test_feed_config(4, 0)],
+        );
+        assert_eq!(
+            diff,
+            FeedsDiff {
+                only_on_chain: vec![3],
+                only_in_config: vec![4],
+                different: vec![2],
+            }
+        );
+
+        // After a restart the chain starts again from genesis, with the initial feeds.
+        let mut restarted_db = InMemDb::new();
+        let blocks = push_block(
+            &mut restarted_db,
+            10,
+            vec![feed_config_to_block(&test_feed_config(4, 0))],
+            vec![],
+        );
+        let replayed = replay_blocks(&blocks, &initial_feeds).unwrap();
+        assert_eq!(replayed.blocks_per_issuer, BTreeMap::from([(1, 3)]));
+        assert_eq!(replayed.restarts_per_issuer, BTreeMap::from([(1, 1)]));
+        assert_eq!(replayed.feeds.keys().collect::<Vec<_>>(), vec![&1, &4]);
+
+        // A block that does not refer to the previous one breaks the chain.
+        let (header, actions, updates) = restarted_db
+            .create_new_block(1, 15, vec![], vec![], vec![], vec![])
+            .unwrap();
+        let broken = BlockHeader {
+            prev_block_hash: [1; 32],
+            ..header
+        };
+        let mut dump = blocks.lines().map(str::to_string).collect::<Vec<_>>();
+        dump.push(block_to_json(broken, actions, updates));
+        assert!(replay_blocks(&dump.join("\n"), &initial_feeds).is_err());
+    }
+}
@@ -117,6 +117,15 @@ impl Allocator {
         Ok(free_index)
     }
 
+    /// Releases the allocation at `index`, so that it can be allocated again right away
+    /// instead of after its voting end time has passed.
+    pub fn deallocate(&mut self, index: u32) -> Result<(), String> {
+        self.allocations
+            .remove(&index)
+            .map(|_| ())
+            .ok_or(format!("index {index} is not allocated"))
+    }
+
     fn get_free_index(&self) -> Result<u32, &str> {
         (self.space_lower_bound..=self.space_upper_bound)
             .find(|index| !self.allocations.contains_key(index))
@@ -176,6 +185,11 @@ impl ConcurrentAllocator {
         )
     }
 
+    pub fn deallocate(&self, index: u32) -> Result<(), String> {
+        let mut allocator = self.allocator.write().unwrap();
+        allocator.deallocate(index)
+    }
+
     pub fn space_size(&self) -> u32 {
         let allocator = self.allocator.read().unwrap();
         allocator.space_size()
@@ -421,6 +435,40 @@ mod tests {
         assert!(allocate_request_7.is_ok_and(|index| index == 1));
     }
 
+    #[test]
+    fn test_allocation_deallocate() {
+        // setup
+        let number_of_slots: u8 = 1;
+        let schema_id: Uuid = Uuid::parse_str("a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7d8").unwrap();
+        let voting_start_timestamp: DateTime<Utc> = Utc::now();
+        let ten_seconds: TimeDelta = TimeDelta::new(10, 0).unwrap();
+        let voting_end_timestamp: DateTime<Utc> = voting_start_timestamp.add(ten_seconds);
+        let mut allocator: Allocator = Allocator::new(1..=2);
+        for _ in 0..2 {
+            let _ = allocator.allocate(
+                number_of_slots,
+                schema_id,
+                voting_start_timestamp,
+                voting_end_timestamp,
+            );
+        }
+        assert_eq!(allocator.num_free_indexes(), 0);
+
+        // run
+        assert!(allocator.deallocate(1).is_ok());
+
+        // assert - the released index is free before the voting end time
+        assert_eq!(allocator.num_free_indexes(), 1);
+        assert!(allocator.deallocate(1).is_err());
+        let result = allocator.allocate(
+            number_of_slots,
+            schema_id,
+            voting_start_timestamp,
+            voting_end_timestamp,
+        );
+        assert!(result.is_ok_and(|index| index == 1));
+    }
+
     #[test]
     fn test_concurrent_allocator_allocation() {
         let allocator = Arc::new(ConcurrentAllocator::new(1..=5));
@@ -1,5 +1,10 @@
-use blocksense_blockchain_data_model::{BlockFeedConfig, DataChunk, Resources, DATA_CHUNK_SIZE};
-use blocksense_registry::config::{AssetPair, FeedConfig, FeedQuorum, FeedSchedule, PriceFeedInfo};
+use blocksense_blockchain_data_model::{
+    BlockFeedConfig, BlockStructField, DataChunk, Resources, DATA_CHUNK_SIZE,
+};
+use blocksense_registry::config::{
+    AssetPair, FeedConfig, FeedQuorum, FeedSchedule, NumericalEncoding, PriceFeedInfo,
+    StructFieldConfig,
+};
 
 use anyhow::Result;
 use serde_json::Value;
@@ -31,6 +36,26 @@ pub fn feed_config_to_block(feed_config: &FeedConfig) -> BlockFeedConfig {
         value_type: string_to_data_chunk(&feed_config.value_type),
         aggregate_type: string_to_data_chunk(&feed_config.quorum.aggregation),
         stride: feed_config.stride,
+        signed_values: feed_config.value_encoding == NumericalEncoding::Signed,
+        struct_fields: feed_config
+            .struct_fields
+            .iter()
+            .map(|field| BlockStructField {
+                name: string_to_data_chunk(&field.name),
+                value_type: string_to_data_chunk(&field.value_type),
+                aggregation: string_to_data_chunk(field.aggregation.as_deref().unwrap_or("")),
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("Struct fields are limited by the feeds config validation"),
+        category: string_to_data_chunk(&feed_config.additional_feed_info.category),
+        market_hours: string_to_data_chunk(
+            feed_config
+                .additional_feed_info
+                .market_hours
+                .as_deref()
+                .unwrap_or(""),
+        ),
     }
 }
 
@@ -88,6 +113,20 @@ pub fn block_feed_to_feed_config(block_feed: &BlockFeedConfig) -> FeedConfig {
         feed_type: data_chunk_to_string(&block_feed._type),
         oracle_id: data_chunk_to_string(&block_feed.script),
         value_type: data_chunk_to_string(&block_feed.value_type),
+        value_encoding: if block_feed.signed_values {
+            NumericalEncoding::Signed
+        } else {
+            NumericalEncoding::Unsigned
+        },
+        struct_fields: block_feed
+            .struct_fields
+            .iter()
+            .map(|field| StructFieldConfig {
+                name: data_chunk_to_string(&field.name),
+                value_type: data_chunk_to_string(&field.value_type),
+                aggregation: non_empty(data_chunk_to_string(&field.aggregation)),
+            })
+            .collect(),
         stride: block_feed.stride,
         quorum: FeedQuorum {
             percentage: u8_array_to_f32(block_feed.quorum_percentage),
@@ -105,8 +144,8 @@ pub fn block_feed_to_feed_config(block_feed: &BlockFeedConfig) -> FeedConfig {
                 quote: data_chunk_to_string(&block_feed.pair.quote),
             },
             decimals: block_feed.decimals,
-            category: "".to_string(),
-            market_hours: None,
+            category: data_chunk_to_string(&block_feed.category),
+            market_hours: non_empty(data_chunk_to_string(&block_feed.market_hours)),
             //TODO(adikov): start handling errors properly
             arguments: byte_arrays_to_json(&block_feed.resources)
                 .expect("Can't parse arguments to bytes"),
@@ -120,6 +159,47 @@ fn data_chunk_to_string(bytes: &DataChunk) -> String {
     String::from_utf8(null_terminated.to_vec()).unwrap_or_default()
 }
 
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
 fn u8_array_to_f32(bytes: [u8; 4]) -> f32 {
     f32::from_be_bytes(bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocksense_config::test_feed_config;
+
+    #[test]
+    fn test_struct_feed_config_roundtrip() {
+        let mut feed_config = test_feed_config(1, 1);
+        feed_config.value_type = "struct".to_string();
+        feed_config.struct_fields = vec![
+            StructFieldConfig {
+                name: "bid".to_string(),
+                value_type: "numerical".to_string(),
+                aggregation: None,
+            },
+            StructFieldConfig {
+                name: "ask".to_string(),
+                value_type: "decimal".to_string(),
+                aggregation: Some("average".to_string()),
+            },
+        ];
+        feed_config.compatibility_info = None;
+
+        let block_feed = feed_config_to_block(&feed_config);
+        assert_eq!(block_feed_to_feed_config(&block_feed), feed_config);
+
+        feed_config.additional_feed_info.market_hours = None;
+        let block_feed = feed_config_to_block(&feed_config);
+        assert_eq!(
+            block_feed_to_feed_config(&block_feed)
+                .additional_feed_info
+                .market_hours,
+            None
+        );
+    }
+}
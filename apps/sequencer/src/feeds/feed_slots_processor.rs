@@ -1,11 +1,14 @@
-use crate::reporters::reporter::SharedReporters;
+use crate::reporters::reporter::{active_reporter_ids, count_active_reporters, SharedReporters};
 use crate::sequencer_state::SequencerState;
 use actix_web::web::Data;
 use blocksense_data_feeds::feeds_processing::{
     DoSkipReason, DontSkipReason, SkipDecision, VotedFeedUpdateWithProof,
 };
 use blocksense_feed_registry::feed_registration_cmds::ProcessorResultValue;
-use blocksense_feed_registry::registry::{AllFeedsReports, FeedAggregateHistory, SlotTimeTracker};
+use blocksense_feed_registry::market_hours::MarketCalendar;
+use blocksense_feed_registry::registry::{
+    await_time, AllFeedsReports, FeedAggregateHistory, SlotTimeTracker,
+};
 use blocksense_feed_registry::types::{
     FeedMetaData, FeedType, FeedsSlotProcessorCmds, Repeatability, Timestamp,
 };
@@ -21,11 +24,25 @@ use tokio::sync::RwLock;
 use tracing::error;
 use tracing::{debug, info};
 
+/// How often oneshot feeds check if enough votes arrived to finalize before the voting end time.
+const ONESHOT_QUORUM_POLL_INTERVAL_MS: u64 = 500;
+
 pub struct FeedSlotsProcessor {
     name: String,
     key: u32,
 }
 
+/// Parameters of a feed's slots, taken from its `FeedMetaData`.
+struct SlotParams {
+    report_interval_ms: u64,
+    first_report_start_time: u128,
+    quorum_percentage: f32,
+    skip_publish_if_less_then_percentage: f64,
+    always_publish_heartbeat_ms: Option<u128>,
+    aggregator: FeedAggregate,
+    feed_type: FeedType,
+}
+
 impl FeedSlotsProcessor {
     pub fn new(name: String, key: u32) -> FeedSlotsProcessor {
         FeedSlotsProcessor { name, key }
@@ -62,11 +79,126 @@ impl FeedSlotsProcessor {
     async fn get_num_valid_reportes(&self, reporters: &SharedReporters) -> usize {
         let feed_id = self.key;
         debug!("Get a read lock on all reporters [feed {feed_id}]");
-        let res = reporters.read().await.len();
+        let res = count_active_reporters(reporters).await;
         debug!("Release the read lock on all reports [feed {feed_id}]");
         res
     }
 
+    /// Scores the active reporters on the votes of the finished slot. Votes are only compared
+    /// against aggregates backed by a quorum.
+    async fn record_reputations(
+        &self,
+        sequencer_state: &Data<SequencerState>,
+        reports: &FeedReports,
+        consumed_reports: &ConsumedReports,
+    ) {
+        let reporter_ids = active_reporter_ids(&sequencer_state.reporters).await;
+        let aggregate = consumed_reports
+            .result_post_to_contract
+            .as_ref()
+            .filter(|_| consumed_reports.is_quorum_reached)
+            .map(|result| &result.update.value);
+        sequencer_state
+            .reporter_reputation
+            .write()
+            .await
+            .record_slot(self.key, &reporter_ids, reports, aggregate);
+    }
+
+    /// Resolves once a quorum of valid votes is collected for a oneshot feed whose voting
+    /// has started. Returns the time at which the feed can be finalized.
+    async fn await_oneshot_quorum(
+        &self,
+        voting_start_time: Timestamp,
+        quorum_percentage: f32,
+        sequencer_state: &Data<SequencerState>,
+    ) -> Timestamp {
+        let feed_id = self.key;
+        loop {
+            await_time(ONESHOT_QUORUM_POLL_INTERVAL_MS).await;
+            let current_time_as_ms = current_unix_time();
+            if current_time_as_ms < voting_start_time {
+                continue;
+            }
+            let num_votes = match sequencer_state.reports.read().await.get(feed_id) {
+                Some(reports) => reports
+                    .read()
+                    .await
+                    .report
+                    .values()
+                    .filter(|vote| vote.result.is_ok())
+                    .count(),
+                None => 0,
+            };
+            let num_valid_reporters = self
+                .get_num_valid_reportes(&sequencer_state.reporters)
+                .await;
+            let required_votes_count = quorum_percentage * 0.01f32 * (num_valid_reporters as f32);
+            if num_votes > 0 && num_votes as f32 >= required_votes_count {
+                info!(
+                    "Quorum of {num_votes} votes reached for oneshot feed {} with id {feed_id}, finalizing before voting end.",
+                    self.name
+                );
+                return current_time_as_ms;
+            }
+        }
+    }
+
+    /// Waits for the end of the current slot and returns its end timestamp. Oneshot feeds
+    /// are finalized as soon as quorum is reached, even if their voting is not over yet.
+    async fn await_end_of_voting(
+        &self,
+        feed_slots_time_tracker: &SlotTimeTracker,
+        repeatability: &Repeatability,
+        end_slot_timestamp: Timestamp,
+        voting_start_time: Timestamp,
+        quorum_percentage: f32,
+        sequencer_state: &Data<SequencerState>,
+    ) -> Timestamp {
+        if *repeatability != Repeatability::Oneshot {
+            feed_slots_time_tracker
+                .await_end_of_current_slot(repeatability)
+                .await;
+            return end_slot_timestamp;
+        }
+        tokio::select! {
+            _ = feed_slots_time_tracker.await_end_of_current_slot(repeatability) => end_slot_timestamp,
+            finalized_at = self.await_oneshot_quorum(voting_start_time, quorum_percentage, sequencer_state) => finalized_at,
+        }
+    }
+
+    async fn get_market_calendar(
+        &self,
+        sequencer_state: &Data<SequencerState>,
+    ) -> Option<Arc<MarketCalendar>> {
+        let active_feeds = sequencer_state.active_feeds.read().await;
+        let feed_config = active_feeds.get(&self.key)?;
+        sequencer_state.market_calendars.for_feed(feed_config)
+    }
+
+    /// Drops the votes of a slot during which the feed's market was closed the whole time.
+    async fn discard_slot_while_market_closed(
+        &self,
+        slot: u64,
+        sequencer_state: &Data<SequencerState>,
+        feed_metrics: &Option<Arc<RwLock<FeedsMetrics>>>,
+    ) {
+        let feed_id = self.key;
+        info!(
+            "Market is closed for {} with id {}, skipping slot {}.",
+            self.name, feed_id, slot
+        );
+        if let Some(reports) = self
+            .get_reports_for_feed(feed_id, &sequencer_state.reports)
+            .await
+        {
+            reports.write().await.clear();
+        }
+        if let Some(feed_metrics) = feed_metrics {
+            inc_metric!(feed_metrics, feed_id, skipped_market_closed);
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn process_end_of_slot(
         &self,
@@ -81,6 +213,7 @@ impl FeedSlotsProcessor {
         feed_type: &FeedType,
         sequencer_state: &Data<SequencerState>,
         history: &Arc<RwLock<FeedAggregateHistory>>,
+        market_calendar: Option<&MarketCalendar>,
     ) -> Result<ConsumedReports> {
         let feed_id = self.key;
         let num_valid_reporters = {
@@ -94,6 +227,7 @@ impl FeedSlotsProcessor {
             ad_score: None,
             result_post_to_contract: None,
             end_slot_timestamp,
+            dropped_votes: 0,
         };
 
         info!(
@@ -110,6 +244,11 @@ impl FeedSlotsProcessor {
                 debug!("Get a write lock on reports [feed {feed_id}]");
                 let mut reports = reports.write().await;
                 debug!("Acquired a write lock on reports [feed {feed_id}]");
+                let reporter_weights = sequencer_state
+                    .reporter_reputation
+                    .read()
+                    .await
+                    .aggregation_weights();
                 consumed_reports = consume_reports(
                     self.name.as_str(),
                     &reports.report,
@@ -123,10 +262,14 @@ impl FeedSlotsProcessor {
                     is_oneshot,
                     aggregator,
                     Some(history.clone()),
+                    market_calendar,
                     feed_id,
+                    &reporter_weights,
                 )
                 .await;
 
+                self.record_reputations(sequencer_state, &reports, &consumed_reports)
+                    .await;
                 reports.clear();
                 drop(reports);
                 debug!("Release the write lock on reports [feed {feed_id}]");
@@ -149,6 +292,14 @@ impl FeedSlotsProcessor {
         let feed_id = self.key;
         self.increase_quorum_metric(&feed_metrics, consumed_reports.is_quorum_reached)
             .await;
+        if let Some(feed_metrics) = &feed_metrics {
+            feed_metrics
+                .read()
+                .await
+                .dropped_votes
+                .with_label_values(&[&feed_id.to_string()])
+                .inc_by(consumed_reports.dropped_votes as u64);
+        }
 
         if !consumed_reports.is_quorum_reached {
             debug!("Quorum not reached for feed_id = {feed_id}");
@@ -166,6 +317,9 @@ impl FeedSlotsProcessor {
                     DoSkipReason::NothingToPost => {
                         inc_metric!(feed_metrics, feed_id, skipped_nothing_to_post)
                     }
+                    DoSkipReason::AggregationFailed => {
+                        inc_metric!(feed_metrics, feed_id, skipped_aggregation_failed)
+                    }
                 },
                 SkipDecision::DontSkip(reason) => match reason {
                     DontSkipReason::ThresholdCrossed => {
@@ -251,6 +405,28 @@ impl FeedSlotsProcessor {
         }
     }
 
+    async fn read_slot_params(&self, feed: &Arc<RwLock<FeedMetaData>>) -> Result<SlotParams> {
+        let feed_id = self.key;
+        debug!("Get a read lock on feed meta [feed {feed_id}]");
+        let datafeed = feed.read().await;
+        let feed_type = datafeed
+            .get_value_variant()
+            .map_err(|msg| eyre!("{msg} for feed: {}", self.name))?;
+        let params = SlotParams {
+            report_interval_ms: datafeed.get_report_interval_ms(),
+            first_report_start_time: datafeed.get_first_report_start_time_ms(),
+            quorum_percentage: datafeed.get_quorum_percentage(),
+            skip_publish_if_less_then_percentage: datafeed
+                .get_skip_publish_if_less_then_percentage()
+                as f64,
+            always_publish_heartbeat_ms: datafeed.get_always_publish_heartbeat_ms(),
+            aggregator: datafeed.get_feed_aggregator(),
+            feed_type,
+        };
+        debug!("Release the read lock on feed meta [feed {feed_id}]");
+        Ok(params)
+    }
+
     pub async fn start_loop(
         &self,
         sequencer_state: &Data<SequencerState>,
@@ -261,38 +437,12 @@ impl FeedSlotsProcessor {
         _cmd_sender: Option<mpsc::UnboundedSender<FeedsSlotProcessorCmds>>,
     ) -> Result<ProcessorResultValue> {
         let feed_id = self.key;
-        let (
-            is_oneshot,
-            report_interval_ms,
-            first_report_start_time,
-            quorum_percentage,
-            skip_publish_if_less_then_percentage,
-            always_publish_heartbeat_ms,
-            aggregator,
-            feed_type,
-        ) = {
-            debug!("Get a read lock on feed meta [feed {feed_id}]");
-            let datafeed = feed.read().await;
-            (
-                datafeed.is_oneshot(),
-                datafeed.get_report_interval_ms(),
-                datafeed.get_first_report_start_time_ms(),
-                datafeed.get_quorum_percentage(),
-                datafeed.get_skip_publish_if_less_then_percentage() as f64,
-                datafeed.get_always_publish_heartbeat_ms(),
-                datafeed.get_feed_aggregator(),
-                datafeed.value_type.clone(),
-            )
-        };
-
-        let feed_type = FeedType::get_variant_from_string(feed_type.as_str())
-            .map_err(|msg| eyre!("{msg} for feed: {}", self.name))?;
-
-        debug!("Release the read lock on feed meta [feed {feed_id}]");
-        let feed_slots_time_tracker = SlotTimeTracker::new(
+        let is_oneshot = feed.read().await.is_oneshot();
+        let mut params = self.read_slot_params(feed).await?;
+        let mut feed_slots_time_tracker = SlotTimeTracker::new(
             format!("feed_processor_{}", self.key),
-            Duration::from_millis(report_interval_ms),
-            first_report_start_time,
+            Duration::from_millis(params.report_interval_ms),
+            params.first_report_start_time,
         );
 
         let mut is_processed = false;
@@ -308,6 +458,40 @@ impl FeedSlotsProcessor {
                     "Oneshot feed processed",
                 )));
             }
+            // Pick up config updates of periodic feeds at the slot boundary. The history and
+            // round counters of the feed live outside of the processor and are kept.
+            if is_processed && !is_oneshot {
+                match self.read_slot_params(feed).await {
+                    Ok(new_params) => {
+                        if new_params.report_interval_ms != params.report_interval_ms
+                            || new_params.first_report_start_time != params.first_report_start_time
+                        {
+                            info!(
+                                "Slots of feed {} with id {feed_id} changed to {}ms starting at {}",
+                                self.name,
+                                new_params.report_interval_ms,
+                                new_params.first_report_start_time
+                            );
+                            feed_slots_time_tracker = SlotTimeTracker::new(
+                                format!("feed_processor_{}", self.key),
+                                Duration::from_millis(new_params.report_interval_ms),
+                                new_params.first_report_start_time,
+                            );
+                        }
+                        params = new_params;
+                    }
+                    Err(e) => error!("Keeping the previous config of feed {feed_id}: {e}"),
+                }
+            }
+            let SlotParams {
+                report_interval_ms,
+                first_report_start_time,
+                quorum_percentage,
+                skip_publish_if_less_then_percentage,
+                always_publish_heartbeat_ms,
+                ref aggregator,
+                ref feed_type,
+            } = params;
             let current_time_as_ms = current_unix_time();
             let slot = {
                 if is_oneshot {
@@ -332,10 +516,30 @@ impl FeedSlotsProcessor {
                     }
                 },
 
-                _ = feed_slots_time_tracker
-                .await_end_of_current_slot(&repeatability) => {
+                end_slot_timestamp = self.await_end_of_voting(
+                    &feed_slots_time_tracker,
+                    &repeatability,
+                    first_report_start_time + (report_interval_ms as u128) * (slot as u128 + 1),
+                    first_report_start_time,
+                    quorum_percentage,
+                    sequencer_state,
+                ) => {
                     is_processed = true;
-                    let end_slot_timestamp = first_report_start_time + (report_interval_ms as u128) * (slot as u128 + 1);
+
+                    // Oneshot feeds are not bound to trading sessions.
+                    let market_calendar = if is_oneshot {
+                        None
+                    } else {
+                        self.get_market_calendar(sequencer_state).await
+                    };
+                    let is_market_closed = market_calendar.as_ref().is_some_and(|calendar| {
+                        let start_slot_timestamp = end_slot_timestamp - report_interval_ms as u128;
+                        calendar.open_duration_ms(start_slot_timestamp, end_slot_timestamp) == 0
+                    });
+                    if is_market_closed {
+                        self.discard_slot_while_market_closed(slot, sequencer_state, &feed_metrics).await;
+                        continue;
+                    }
 
                     debug!("Awaiting process_end_of_slot [feed {}]", self.key);
                     match self.process_end_of_slot(
@@ -345,11 +549,12 @@ impl FeedSlotsProcessor {
                         skip_publish_if_less_then_percentage,
                         always_publish_heartbeat_ms,
                         end_slot_timestamp,
-                        aggregator,
+                        aggregator.clone(),
                         slot,
-                        &feed_type,
+                        feed_type,
                         sequencer_state,
                         history,
+                        market_calendar.as_deref(),
                         ).await {
                             Ok(consumed_reports) => {
                                 debug!("Continued after process_end_of_slot [feed {}]", self.key);
@@ -457,7 +662,8 @@ pub mod tests {
             "numerical".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let history = Arc::new(RwLock::new(FeedAggregateHistory::new()));
 
@@ -543,9 +749,9 @@ pub mod tests {
         let metics_prefix = name;
         let feed_metadata = FeedMetaData::new_oneshot(
             name.to_string(),
-            voting_wait_duration_ms,
             100.0, // 100%
             voting_start_time,
+            voting_start_time + Duration::from_millis(voting_wait_duration_ms),
         );
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let all_feeds_reports = AllFeedsReports::new();
@@ -649,7 +855,8 @@ pub mod tests {
             "numerical".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let history = Arc::new(RwLock::new(FeedAggregateHistory::new()));
 
@@ -665,11 +872,13 @@ pub mod tests {
             id: 42,
             pub_key: "ea30b1533ef5638af7b70a036275642fc453ace97ed2c6b9d220fe1f59a24d61f481a777aa8a579f20e95a74cd4567ed36a3".to_string(),
             address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            suspended: false,
         });
         cfg.reporters.push(Reporter {
             id: 14,
             pub_key: "ea30813e2f8cf968e27bad29167b41bce038a3ce9b7b368de05e5cf1af3de919eeba267b8706f55c356d5f71891eff116b98".to_string(),
             address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            suspended: false,
         });
 
         let feeds_config = AllFeedsConfig {
@@ -752,7 +961,8 @@ pub mod tests {
             "numerical".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let history = Arc::new(RwLock::new(FeedAggregateHistory::new()));
 
@@ -860,7 +1070,8 @@ pub mod tests {
             "numerical".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let history = Arc::new(RwLock::new(FeedAggregateHistory::new()));
 
@@ -966,7 +1177,8 @@ pub mod tests {
             "numerical".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
         let feed_metadata_arc = Arc::new(RwLock::new(feed_metadata));
         let history = Arc::new(RwLock::new(FeedAggregateHistory::new()));
 
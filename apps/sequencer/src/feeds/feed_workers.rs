@@ -1,12 +1,14 @@
 use crate::aggregate_batch_consensus_processor::aggregation_batch_consensus_loop;
 use crate::block_creator::block_creator_loop;
 use crate::blocks_reader::blocks_reader_loop;
+use crate::feeds::feeds_config_reloader::feeds_config_watcher_loop;
 use crate::feeds::feeds_slots_manager::feeds_slots_manager_loop;
+use crate::feeds::history_persistence::{history_persistence_loop, restore_feed_histories};
 use crate::feeds::votes_result_sender::votes_result_sender_loop;
 use crate::metrics_collector::metrics_collector_loop;
 use crate::sequencer_state::SequencerState;
 use actix_web::web::Data;
-use blocksense_config::SequencerConfig;
+use blocksense_config::{get_feeds_config_file_path, SequencerConfig};
 use blocksense_data_feeds::feeds_processing::VotedFeedUpdateWithProof;
 use blocksense_feed_registry::feed_registration_cmds::FeedsManagementCmds;
 use blocksense_gnosis_safe::data_types::ReporterResponse;
@@ -23,6 +25,9 @@ use tokio::task::JoinHandle;
 /// - Votes result sender loop
 /// - Metrics collector loop
 /// - Aggregation batch consensus loop
+/// - History persistence loop, if enabled. The feed histories are restored before any other
+///   worker is started.
+/// - Feeds config watcher loop, if enabled
 pub async fn prepare_app_workers(
     sequencer_state: Data<SequencerState>,
     sequencer_config: &SequencerConfig,
@@ -31,6 +36,10 @@ pub async fn prepare_app_workers(
     feeds_slots_manager_cmd_recv: UnboundedReceiver<FeedsManagementCmds>,
     aggregate_batch_sig_recv: UnboundedReceiver<(ReporterResponse, SignatureWithAddress)>,
 ) -> FuturesUnordered<JoinHandle<Result<(), Error>>> {
+    if let Some(history_persistence) = &sequencer_config.history_persistence {
+        restore_feed_histories(&sequencer_state, history_persistence).await;
+    }
+
     let (batched_votes_send, batched_votes_recv) = mpsc::unbounded_channel();
 
     let feeds_slots_manager_loop_fut =
@@ -52,6 +61,25 @@ pub async fn prepare_app_workers(
 
     let blocks_reader = blocks_reader_loop(sequencer_state.clone()).await;
 
+    let history_persistence = match &sequencer_config.history_persistence {
+        Some(config) => {
+            Some(history_persistence_loop(sequencer_state.clone(), config.clone()).await)
+        }
+        None => None,
+    };
+
+    let feeds_config_watcher = match &sequencer_config.feeds_config_watch {
+        Some(config) => Some(
+            feeds_config_watcher_loop(
+                sequencer_state.clone(),
+                get_feeds_config_file_path(),
+                config.clone(),
+            )
+            .await,
+        ),
+        None => None,
+    };
+
     let aggregation_batch_consensus = aggregation_batch_consensus_loop(
         sequencer_state,
         sequencer_config.block_config.clone(),
@@ -67,6 +95,12 @@ pub async fn prepare_app_workers(
     collected_futures.push(metrics_collector);
     collected_futures.push(blocks_reader);
     collected_futures.push(aggregation_batch_consensus);
+    if let Some(history_persistence) = history_persistence {
+        collected_futures.push(history_persistence);
+    }
+    if let Some(feeds_config_watcher) = feeds_config_watcher {
+        collected_futures.push(feeds_config_watcher);
+    }
 
     collected_futures
 }
@@ -0,0 +1,248 @@
+use actix_web::rt::time;
+use actix_web::web::Data;
+use blocksense_config::{AllFeedsConfig, FeedsConfigWatch, Validated};
+use blocksense_feed_registry::feed_registration_cmds::{
+    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed, UpdateAssetFeed,
+};
+use blocksense_feed_registry::market_hours::MarketCalendars;
+use blocksense_registry::config::FeedConfig;
+use eyre::{eyre, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::time::Duration;
+use tracing::{error, info};
+
+use crate::sequencer_state::SequencerState;
+
+/// Difference between the last applied feeds config file and its current content.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct FeedsConfigChanges {
+    pub registered: Vec<u32>,
+    pub deleted: Vec<u32>,
+    /// Registered feeds whose config changed in the file.
+    pub updated: Vec<u32>,
+}
+
+/// Computes the commands that apply the changes from `applied_feeds` to the feeds of
+/// `feeds_config`. The active feeds are not diffed against, since they lag behind the commands
+/// still pending in the block creator and include the feeds managed through other means.
+pub fn diff_feeds_config(
+    applied_feeds: &HashMap<u32, FeedConfig>,
+    feeds_config: &AllFeedsConfig,
+) -> (FeedsConfigChanges, Vec<FeedsManagementCmds>) {
+    let mut changes = FeedsConfigChanges::default();
+    let mut cmds = Vec::new();
+    for feed in &feeds_config.feeds {
+        match applied_feeds.get(&feed.id) {
+            None => {
+                changes.registered.push(feed.id);
+                cmds.push(FeedsManagementCmds::RegisterNewAssetFeed(
+                    RegisterNewAssetFeed {
+                        config: feed.clone(),
+                    },
+                ));
+            }
+            Some(applied_feed) if applied_feed != feed => {
+                changes.updated.push(feed.id);
+                cmds.push(FeedsManagementCmds::UpdateAssetFeed(UpdateAssetFeed {
+                    config: feed.clone(),
+                }));
+            }
+            Some(_) => {}
+        }
+    }
+    let mut deleted: Vec<u32> = applied_feeds
+        .keys()
+        .filter(|id| !feeds_config.feeds.iter().any(|feed| feed.id == **id))
+        .copied()
+        .collect();
+    deleted.sort();
+    for id in &deleted {
+        cmds.push(FeedsManagementCmds::DeleteAssetFeed(DeleteAssetFeed {
+            id: *id,
+        }));
+    }
+    changes.deleted = deleted;
+    changes.registered.sort();
+    changes.updated.sort();
+    (changes, cmds)
+}
+
+fn read_feeds_config(path: &Path, market_calendars: &MarketCalendars) -> Result<AllFeedsConfig> {
+    let data = std::fs::read_to_string(path)?;
+    let feeds_config: AllFeedsConfig = serde_json::from_str(&data)?;
+    feeds_config
+        .validate("FeedsConfig")
+        .map_err(|e| eyre!("Validation error {e}"))?;
+    for feed in &feeds_config.feeds {
+        market_calendars
+            .validate_feed(feed)
+            .map_err(|e| eyre!("Validation error FeedsConfig: {e}"))?;
+    }
+    Ok(feeds_config)
+}
+
+/// Reads and validates the feeds config at `path` and schedules the registration, update
+/// and deletion of the feeds that changed in it since it was last applied, through the block
+/// creator. Nothing is scheduled if the file is invalid. Changes that do not fit in one block
+/// are carried over to the next blocks by the block creator, so all of them get applied.
+pub async fn reload_feeds_config(
+    sequencer_state: &Data<SequencerState>,
+    path: &Path,
+) -> Result<FeedsConfigChanges> {
+    let feeds_config = read_feeds_config(path, &sequencer_state.market_calendars)
+        .map_err(|e| eyre!("Rejected reload of feeds config {path:?}: {e}"))?;
+
+    // Held until the commands are sent, so that concurrent reloads do not schedule them twice.
+    let mut applied_feeds = sequencer_state.applied_feeds_config.lock().await;
+    let (changes, cmds) = diff_feeds_config(&applied_feeds, &feeds_config);
+    for cmd in cmds {
+        if let Err(e) = sequencer_state
+            .feeds_management_cmd_to_block_creator_send
+            .send(cmd)
+        {
+            panic!("Sequencer internal error, could not forward feeds management cmd {e}");
+        }
+    }
+    *applied_feeds = feeds_config
+        .feeds
+        .into_iter()
+        .map(|feed| (feed.id, feed))
+        .collect();
+    drop(applied_feeds);
+    info!(
+        "Reloaded feeds config {path:?}: scheduled registration of {:?}, update of {:?} and deletion of {:?}",
+        changes.registered, changes.updated, changes.deleted
+    );
+    Ok(changes)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub async fn feeds_config_watcher_loop(
+    sequencer_state: Data<SequencerState>,
+    path: PathBuf,
+    config: FeedsConfigWatch,
+) -> tokio::task::JoinHandle<Result<(), Error>> {
+    tokio::task::Builder::new()
+        .name("feeds_config_watcher")
+        .spawn_local(async move {
+            info!(
+                "Watching feeds config {path:?} for changes every {}ms...",
+                config.poll_interval_ms
+            );
+            let mut last_modified = modified_time(&path);
+            let mut interval = time::interval(Duration::from_millis(config.poll_interval_ms));
+            loop {
+                interval.tick().await;
+                let modified = modified_time(&path);
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                if let Err(e) = reload_feeds_config(&sequencer_state, &path).await {
+                    error!("{e}");
+                }
+            }
+        })
+        .expect("Failed to spawn feeds config watcher!")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use blocksense_config::{get_test_config_with_no_providers, test_feed_config};
+
+    #[test]
+    fn test_diff_feeds_config() {
+        let applied_feeds: HashMap<u32, FeedConfig> = [1, 2, 3]
+            .into_iter()
+            .map(|id| (id, test_feed_config(id, 0)))
+            .collect();
+        let feeds_config = AllFeedsConfig {
+            feeds: vec![
+                test_feed_config(1, 0),
+                test_feed_config(3, 1),
+                test_feed_config(5, 0),
+                test_feed_config(4, 0),
+            ],
+        };
+
+        let (changes, cmds) = diff_feeds_config(&applied_feeds, &feeds_config);
+        assert_eq!(
+            changes,
+            FeedsConfigChanges {
+                registered: vec![4, 5],
+                deleted: vec![2],
+                updated: vec![3],
+            }
+        );
+        assert_eq!(cmds.len(), 4);
+        assert!(matches!(
+            &cmds[1],
+            FeedsManagementCmds::UpdateAssetFeed(UpdateAssetFeed { config }) if config.id == 3
+        ));
+        assert!(matches!(
+            &cmds[3],
+            FeedsManagementCmds::DeleteAssetFeed(DeleteAssetFeed { id: 2 })
+        ));
+
+        let (changes, cmds) = diff_feeds_config(
+            &applied_feeds,
+            &AllFeedsConfig {
+                feeds: applied_feeds.values().cloned().collect(),
+            },
+        );
+        assert_eq!(changes, FeedsConfigChanges::default());
+        assert!(cmds.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_feeds_config_applies_changes_once() {
+        let (sequencer_state, _, mut cmds_recv, _, _) =
+            create_sequencer_state_from_sequencer_config(
+                get_test_config_with_no_providers(),
+                "test_reload_feeds_config_applies_changes_once",
+                AllFeedsConfig {
+                    feeds: vec![test_feed_config(1, 0)],
+                },
+            )
+            .await;
+        let dir = std::env::temp_dir().join("test_reload_feeds_config_applies_changes_once");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("feeds_config.json");
+        let feeds_config = AllFeedsConfig {
+            feeds: vec![test_feed_config(1, 0), test_feed_config(2, 0)],
+        };
+        std::fs::write(&path, serde_json::to_string(&feeds_config).unwrap()).unwrap();
+
+        let changes = reload_feeds_config(&sequencer_state, &path).await.unwrap();
+        assert_eq!(changes.registered, vec![2]);
+        assert!(matches!(
+            cmds_recv.try_recv().unwrap(),
+            FeedsManagementCmds::RegisterNewAssetFeed(RegisterNewAssetFeed { config }) if config.id == 2
+        ));
+
+        // Feed 2 is not active until the block creator handles its registration, yet reloading
+        // the unchanged file schedules nothing.
+        let changes = reload_feeds_config(&sequencer_state, &path).await.unwrap();
+        assert_eq!(changes, FeedsConfigChanges::default());
+        assert!(cmds_recv.try_recv().is_err());
+
+        // Feeds traded on markets without a calendar are rejected with the whole file.
+        let mut unknown_market = test_feed_config(3, 0);
+        unknown_market.additional_feed_info.market_hours = Some("Moon_Exchange".to_owned());
+        let feeds_config = AllFeedsConfig {
+            feeds: vec![test_feed_config(1, 0), unknown_market],
+        };
+        std::fs::write(&path, serde_json::to_string(&feeds_config).unwrap()).unwrap();
+        assert!(reload_feeds_config(&sequencer_state, &path).await.is_err());
+        assert!(cmds_recv.try_recv().is_err());
+    }
+}
@@ -1,18 +1,19 @@
 use crate::feeds::feed_slots_processor::FeedSlotsProcessor;
 use crate::sequencer_state::SequencerState;
 use actix_web::web;
+use blocksense_config::Validated;
 use blocksense_feed_registry::feed_registration_cmds::{
     DeleteAssetFeed, FeedsManagementCmds, ProcessorResultValue, RegisterNewAssetFeed,
+    UpdateAssetFeed,
 };
 use blocksense_feed_registry::types::{FeedMetaData, FeedsSlotProcessorCmds::Terminate};
 use blocksense_registry::config::FeedConfig;
-use eyre::Result;
+use blocksense_utils::time::current_unix_time;
+use eyre::{eyre, Result};
 use futures::select;
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::io::Error;
 use std::sync::Arc;
-use std::time::Duration;
-use std::time::UNIX_EPOCH;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
@@ -232,6 +233,13 @@ async fn handle_feeds_slots_manager_cmd(
                 }
             };
         }
+        FeedsManagementCmds::UpdateAssetFeed(update_asset_feed) => {
+            let feed_id = update_asset_feed.config.id;
+            match update_asset_feed_config(&sequencer_state, &update_asset_feed).await {
+                Ok(_) => info!("Updating config of feed id {feed_id} complete!"),
+                Err(e) => warn!("Failed to update config of feed id {feed_id}: {e}"),
+            };
+        }
     };
     //Register reader task again once the command is processed
     let command_watcher = tokio::task::Builder::new()
@@ -271,18 +279,10 @@ pub async fn register_feed_with_config(
             eyre::bail!("Cannot register feed ID, feed with this ID {new_feed_id} already exists.");
         }
 
-        let new_feed_metadata = FeedMetaData::new(
-            new_name,
-            new_feed_config.schedule.interval_ms,
-            new_feed_config.quorum.percentage,
-            new_feed_config.schedule.deviation_percentage,
-            new_feed_config.schedule.heartbeat_ms,
-            UNIX_EPOCH
-                + Duration::from_millis(new_feed_config.schedule.first_report_start_unix_time_ms),
-            new_feed_config.value_type.clone(),
-            new_feed_config.quorum.aggregation.clone(),
-            None,
-        );
+        let new_feed_metadata = match FeedMetaData::from_config(new_feed_config) {
+            Ok(feed_metadata) => feed_metadata,
+            Err(e) => eyre::bail!("Cannot register feed ID {new_feed_id}: {e}"),
+        };
         reg.push(new_feed_id, new_feed_metadata);
     }
     {
@@ -338,6 +338,72 @@ async fn deregister_asset_feed(
     Ok(())
 }
 
+/// Checks that `config` is valid and can replace the config of the registered feed with the
+/// same id. Since the slots processor of the feed keeps running, the type of its values can not
+/// change and its slots can not start in the future.
+pub async fn check_feed_config_update(
+    sequencer_state: &web::Data<SequencerState>,
+    config: &FeedConfig,
+) -> Result<()> {
+    let feed_id = config.id;
+    config.validate("FeedConfig").map_err(|e| eyre!("{e}"))?;
+    sequencer_state
+        .market_calendars
+        .validate_feed(config)
+        .map_err(|e| eyre!("{e}"))?;
+    FeedMetaData::from_config(config)
+        .map_err(|e| eyre!("Invalid config of feed {feed_id}: {e}"))?;
+    if let Some(current) = sequencer_state.active_feeds.read().await.get(&feed_id) {
+        if config.value_type != current.value_type || config.struct_fields != current.struct_fields
+        {
+            eyre::bail!("The value type and struct fields of feed {feed_id} can not change");
+        }
+    }
+    if u128::from(config.schedule.first_report_start_unix_time_ms) > current_unix_time() {
+        eyre::bail!("The first report of feed {feed_id} can not start in the future");
+    }
+    Ok(())
+}
+
+/// Replaces the config of a registered feed. Its slots processor picks up the new parameters
+/// at its next slot boundary, keeping the feed's history and round counters.
+async fn update_asset_feed_config(
+    sequencer_state: &web::Data<SequencerState>,
+    cmd: &UpdateAssetFeed,
+) -> Result<()> {
+    let feed_id = cmd.config.id;
+    if let Err(e) = check_feed_config_update(sequencer_state, &cmd.config).await {
+        eyre::bail!("Cannot update feed ID {feed_id}: {e}");
+    }
+    {
+        let reg = sequencer_state.registry.read().await;
+        let Some(feed) = reg.get(feed_id) else {
+            eyre::bail!("Cannot update feed ID, feed with this ID {feed_id} does not exists.");
+        };
+        if let Err(e) = feed.write().await.update_from_config(&cmd.config) {
+            eyre::bail!("Cannot update feed ID {feed_id}: {e}");
+        }
+    }
+    {
+        let mut active_feeds = sequencer_state.active_feeds.write().await;
+        active_feeds.insert(feed_id, cmd.config.clone());
+    }
+    Ok(())
+}
+
+/// Removes a finalized oneshot feed and releases its storage index, so that it can be
+/// allocated to the next event feed.
+pub async fn deregister_oneshot_feed(sequencer_state: &web::Data<SequencerState>, feed_id: u32) {
+    sequencer_state.registry.write().await.remove(feed_id);
+    sequencer_state.reports.write().await.remove(feed_id);
+    if let Some(allocator) = sequencer_state.feed_id_allocator.read().await.as_ref() {
+        if let Err(e) = allocator.deallocate(feed_id) {
+            warn!("Could not release storage index of oneshot feed {feed_id}: {e}");
+        }
+    }
+    info!("Deregistered oneshot feed {feed_id}");
+}
+
 async fn read_next_feed_slots_manager_cmd(
     mut cmd_channel: mpsc::UnboundedReceiver<FeedsManagementCmds>,
 ) -> Result<ProcessorResultValue> {
@@ -356,7 +422,7 @@ async fn read_next_feed_slots_manager_cmd(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::providers::provider::init_shared_rpc_providers;
+    use crate::providers::provider::{init_shared_rpc_providers, new_provider_metrics};
     use blocksense_config::{test_feed_config, AllFeedsConfig, SequencerConfig};
     use blocksense_feed_registry::types::{test_payload_from_result, FeedType};
     use std::time::Duration;
@@ -364,7 +430,9 @@ mod tests {
     use blocksense_config::get_test_config_with_no_providers;
 
     use crate::feeds::feed_slots_processor::tests::check_received;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
     use blocksense_utils::logging::init_shared_logging_handle;
+    use ringbuf::traits::Observer;
 
     #[actix_web::test]
     async fn test_feed_slots_manager_loop() {
@@ -379,8 +447,9 @@ mod tests {
         };
         let metrics_prefix = Some("test_feed_slots_manager_loop_");
 
+        let provider_metrics = new_provider_metrics(metrics_prefix);
         let providers =
-            init_shared_rpc_providers(&sequencer_config, metrics_prefix, &feeds_config).await;
+            init_shared_rpc_providers(&sequencer_config, &provider_metrics, &feeds_config).await;
 
         let original_report_data = FeedType::Numerical(13.0);
 
@@ -395,18 +464,22 @@ mod tests {
             mpsc::unbounded_channel();
         let (aggregate_batch_sig_send, _aggregate_batch_sig_recv) = mpsc::unbounded_channel();
 
-        let sequencer_state = web::Data::new(SequencerState::new(
-            feeds_config,
-            providers,
-            log_handle,
-            &sequencer_config,
-            metrics_prefix,
-            None,
-            vote_send,
-            feeds_management_cmd_to_block_creator_send,
-            feeds_slots_manager_cmd_send,
-            aggregate_batch_sig_send,
-        ));
+        let sequencer_state = web::Data::new(
+            SequencerState::new(
+                feeds_config,
+                providers,
+                provider_metrics,
+                log_handle,
+                &sequencer_config,
+                metrics_prefix,
+                None,
+                vote_send,
+                feeds_management_cmd_to_block_creator_send,
+                feeds_slots_manager_cmd_send,
+                aggregate_batch_sig_send,
+            )
+            .unwrap(),
+        );
 
         sequencer_state
             .reports
@@ -431,4 +504,124 @@ mod tests {
         .await;
         check_received(received, (1_u32, original_report_data));
     }
+
+    async fn push_report(sequencer_state: &web::Data<SequencerState>, feed_id: u32, value: f64) {
+        sequencer_state
+            .reports
+            .write()
+            .await
+            .push(
+                feed_id,
+                42,
+                test_payload_from_result(Ok(FeedType::Numerical(value))),
+            )
+            .await;
+    }
+
+    #[actix_web::test]
+    async fn test_feed_slots_manager_loop_applies_feed_config_update() {
+        const TIME_INTERVAL: u64 = 1_000_u64;
+        const UPDATED_TIME_INTERVAL: u64 = 500_u64;
+        let feed_id = 1;
+        let mut feed_config = test_feed_config(feed_id, 0);
+        feed_config.schedule.interval_ms = TIME_INTERVAL;
+        let (
+            sequencer_state,
+            mut vote_recv,
+            _feeds_management_cmd_to_block_creator_recv,
+            feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_feed_slots_manager_loop_applies_feed_config_update",
+            AllFeedsConfig {
+                feeds: vec![feed_config.clone()],
+            },
+        )
+        .await;
+        let _future =
+            feeds_slots_manager_loop(sequencer_state.clone(), feeds_slots_manager_cmd_recv).await;
+        let timeout = Duration::from_millis(2 * TIME_INTERVAL + 1000);
+
+        push_report(&sequencer_state, feed_id, 13.0).await;
+        let first = tokio::time::timeout(timeout, vote_recv.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.update.value, FeedType::Numerical(13.0));
+
+        let mut updated_config = feed_config.clone();
+        updated_config.schedule.interval_ms = UPDATED_TIME_INTERVAL;
+        sequencer_state
+            .feeds_slots_manager_cmd_send
+            .send(FeedsManagementCmds::UpdateAssetFeed(UpdateAssetFeed {
+                config: updated_config,
+            }))
+            .unwrap();
+
+        // The slot that is already running keeps its length, the following ones are shorter.
+        push_report(&sequencer_state, feed_id, 26.0).await;
+        let second = tokio::time::timeout(timeout, vote_recv.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.update.value, FeedType::Numerical(26.0));
+        push_report(&sequencer_state, feed_id, 52.0).await;
+        let third = tokio::time::timeout(timeout, vote_recv.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(third.update.value, FeedType::Numerical(52.0));
+        assert_eq!(
+            third.update.end_slot_timestamp - second.update.end_slot_timestamp,
+            UPDATED_TIME_INTERVAL as u128
+        );
+        assert_eq!(
+            sequencer_state.active_feeds.read().await[&feed_id]
+                .schedule
+                .interval_ms,
+            UPDATED_TIME_INTERVAL
+        );
+
+        // The history of the feed was kept across the update.
+        let history = sequencer_state.feed_aggregate_history.read().await;
+        assert_eq!(history.get(feed_id).unwrap().occupied_len(), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_check_feed_config_update() {
+        let feed_config = test_feed_config(1, 0);
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_check_feed_config_update",
+            AllFeedsConfig {
+                feeds: vec![feed_config.clone()],
+            },
+        )
+        .await;
+
+        let mut updated_config = feed_config.clone();
+        updated_config.schedule.interval_ms *= 2;
+        assert!(check_feed_config_update(&sequencer_state, &updated_config)
+            .await
+            .is_ok());
+
+        let mut updated_config = feed_config.clone();
+        updated_config.value_type = "text".to_owned();
+        assert!(check_feed_config_update(&sequencer_state, &updated_config)
+            .await
+            .is_err());
+
+        let mut updated_config = feed_config.clone();
+        updated_config.schedule.first_report_start_unix_time_ms += 3_600_000;
+        assert!(check_feed_config_update(&sequencer_state, &updated_config)
+            .await
+            .is_err());
+
+        let mut updated_config = feed_config.clone();
+        updated_config.quorum.aggregation = "mode".to_owned();
+        assert!(check_feed_config_update(&sequencer_state, &updated_config)
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,151 @@
+use actix_web::rt::time;
+use actix_web::web::Data;
+use blocksense_config::HistoryPersistence;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::sequencer_state::SequencerState;
+
+const SEQUENCER_HISTORY_FILE: &str = "feed_aggregate_history.json";
+const REPORTER_REPUTATION_FILE: &str = "reporter_reputation.json";
+
+fn provider_history_file(dir: &Path, network: &str) -> PathBuf {
+    dir.join(format!("provider_{network}_history.json"))
+}
+
+/// Restores the sequencer's and all providers' feed histories, along with the reporters'
+/// reputation, from the snapshots in the configured directory. The providers' histories are
+/// afterwards reconciled with the chain, since updates might have been published (or lost)
+/// while the sequencer was down.
+pub async fn restore_feed_histories(
+    sequencer_state: &Data<SequencerState>,
+    config: &HistoryPersistence,
+) {
+    let dir = Path::new(&config.dir);
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        error!("Could not create history snapshot dir {}: {e}", config.dir);
+        return;
+    }
+
+    let path = dir.join(SEQUENCER_HISTORY_FILE);
+    match sequencer_state
+        .feed_aggregate_history
+        .write()
+        .await
+        .load_from_file(&path)
+    {
+        Ok(count) => info!("Restored {count} feed history entries from {path:?}"),
+        Err(e) => error!("Could not restore feed history from {path:?}: {e}"),
+    }
+
+    let path = dir.join(REPORTER_REPUTATION_FILE);
+    match sequencer_state
+        .reporter_reputation
+        .write()
+        .await
+        .load_from_file(&path)
+    {
+        Ok(count) => info!("Restored the reputation of {count} reporters from {path:?}"),
+        Err(e) => error!("Could not restore reporter reputation from {path:?}: {e}"),
+    }
+
+    let providers = sequencer_state.providers.read().await.clone();
+    for (network, provider) in providers {
+        let is_enabled = sequencer_state
+            .sequencer_config
+            .read()
+            .await
+            .providers
+            .get(&network)
+            .is_some_and(|p| p.is_enabled);
+        let mut provider = provider.lock().await;
+
+        let path = provider_history_file(dir, &network);
+        match provider.history.load_from_file(&path) {
+            Ok(count) => {
+                info!("Restored {count} feed history entries for network {network} from {path:?}")
+            }
+            Err(e) => error!("Could not restore feed history for network {network}: {e}"),
+        }
+
+        if !is_enabled {
+            continue;
+        }
+        let mut feed_ids: Vec<u32> = provider.publishing_criteria.keys().copied().collect();
+        feed_ids.sort();
+        for feed_id in feed_ids {
+            let Some(capacity) = provider.get_history_capacity(feed_id) else {
+                continue;
+            };
+            if let Err(e) = provider
+                .reconcile_history_with_chain(feed_id, capacity as u32)
+                .await
+            {
+                warn!(
+                    "Could not reconcile history of feed id {feed_id} with network {network}: {e}"
+                );
+            }
+        }
+    }
+}
+
+/// Writes snapshots of the sequencer's and all providers' feed histories and of the
+/// reporters' reputation.
+pub async fn persist_feed_histories(
+    sequencer_state: &Data<SequencerState>,
+    config: &HistoryPersistence,
+) {
+    let dir = Path::new(&config.dir);
+
+    let path = dir.join(SEQUENCER_HISTORY_FILE);
+    if let Err(e) = sequencer_state
+        .feed_aggregate_history
+        .read()
+        .await
+        .save_to_file(&path)
+    {
+        error!("Could not persist feed history to {path:?}: {e}");
+    }
+
+    let path = dir.join(REPORTER_REPUTATION_FILE);
+    if let Err(e) = sequencer_state
+        .reporter_reputation
+        .read()
+        .await
+        .save_to_file(&path)
+    {
+        error!("Could not persist reporter reputation to {path:?}: {e}");
+    }
+
+    let providers = sequencer_state.providers.read().await.clone();
+    for (network, provider) in providers {
+        let path = provider_history_file(dir, &network);
+        if let Err(e) = provider.lock().await.history.save_to_file(&path) {
+            error!("Could not persist feed history for network {network} to {path:?}: {e}");
+        }
+    }
+}
+
+pub async fn history_persistence_loop(
+    sequencer_state: Data<SequencerState>,
+    config: HistoryPersistence,
+) -> tokio::task::JoinHandle<Result<(), Error>> {
+    tokio::task::Builder::new()
+        .name("history_persistence")
+        .spawn_local(async move {
+            info!(
+                "Starting history persistence loop, snapshotting to {} every {}ms...",
+                config.dir, config.snapshot_interval_ms
+            );
+            let mut interval = time::interval(Duration::from_millis(config.snapshot_interval_ms));
+            // The first tick completes immediately and the histories were just restored.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                persist_feed_histories(&sequencer_state, &config).await;
+            }
+        })
+        .expect("Failed to spawn history persistence loop!")
+}
@@ -3,5 +3,8 @@ pub mod feed_allocator;
 pub mod feed_config_conversions;
 pub mod feed_slots_processor;
 pub mod feed_workers;
+pub mod feeds_config_reloader;
 pub mod feeds_slots_manager;
+pub mod history_persistence;
+pub mod published_updates_stream;
 pub mod votes_result_sender;
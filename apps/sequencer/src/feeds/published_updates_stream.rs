@@ -0,0 +1,271 @@
+//! Fan-out of the aggregated feed updates, and of the transactions publishing them, to the
+//! clients of `/stream_published_updates`.
+//!
+//! Every event gets a sequence number, increasing in the order in which events are published.
+//! Recent events are kept in a bounded buffer, so that a client reconnecting with the last
+//! sequence number it saw gets the events it missed before the live ones.
+
+use blocksense_data_feeds::feeds_processing::VotedFeedUpdate;
+use blocksense_feed_registry::types::{FeedType, Timestamp};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Number of events kept for clients resuming from an earlier block height.
+pub const RECENT_EVENTS_CAPACITY: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublishedEvent {
+    /// A `VotedFeedUpdate` included in block `block_height`.
+    FeedUpdate {
+        block_height: u64,
+        feed_id: u32,
+        value: FeedType,
+        end_slot_timestamp: Timestamp,
+    },
+    /// A transaction that published the updates of block `block_height` to `network`.
+    Transaction {
+        block_height: u64,
+        network: String,
+        tx_hash: String,
+        feed_ids: Vec<u32>,
+    },
+}
+
+impl PublishedEvent {
+    pub fn block_height(&self) -> u64 {
+        match self {
+            PublishedEvent::FeedUpdate { block_height, .. }
+            | PublishedEvent::Transaction { block_height, .. } => *block_height,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PublishedEvent::FeedUpdate { .. } => "feed_update",
+            PublishedEvent::Transaction { .. } => "transaction",
+        }
+    }
+}
+
+/// A published event along with its sequence number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: PublishedEvent,
+}
+
+/// Where a resuming client left off. Transactions are published after the updates of later
+/// blocks, so only the sequence number resumes exactly after the last event a client got.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResumeFrom {
+    /// Events of blocks after the given height.
+    BlockHeight(u64),
+    /// Events after the one with the given sequence number.
+    Sequence(u64),
+}
+
+impl ResumeFrom {
+    fn includes(&self, event: &SequencedEvent) -> bool {
+        match *self {
+            ResumeFrom::BlockHeight(height) => event.event.block_height() > height,
+            ResumeFrom::Sequence(sequence) => event.sequence > sequence,
+        }
+    }
+}
+
+/// Events a client subscribed to.
+#[derive(Debug, Clone, Default)]
+pub struct EventsFilter {
+    /// Feed ids to stream, all feeds if `None`.
+    pub feed_ids: Option<HashSet<u32>>,
+    pub include_transactions: bool,
+}
+
+impl EventsFilter {
+    pub fn matches(&self, event: &PublishedEvent) -> bool {
+        match event {
+            PublishedEvent::FeedUpdate { feed_id, .. } => self
+                .feed_ids
+                .as_ref()
+                .is_none_or(|feed_ids| feed_ids.contains(feed_id)),
+            PublishedEvent::Transaction { feed_ids, .. } => {
+                self.include_transactions
+                    && self
+                        .feed_ids
+                        .as_ref()
+                        .is_none_or(|wanted| feed_ids.iter().any(|id| wanted.contains(id)))
+            }
+        }
+    }
+}
+
+struct RecentEvents {
+    next_sequence: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+pub struct PublishedUpdatesStream {
+    send: broadcast::Sender<SequencedEvent>,
+    recent: Mutex<RecentEvents>,
+}
+
+impl Default for PublishedUpdatesStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PublishedUpdatesStream {
+    pub fn new() -> PublishedUpdatesStream {
+        let (send, _) = broadcast::channel(RECENT_EVENTS_CAPACITY);
+        PublishedUpdatesStream {
+            send,
+            recent: Mutex::new(RecentEvents {
+                next_sequence: 1,
+                events: VecDeque::with_capacity(RECENT_EVENTS_CAPACITY),
+            }),
+        }
+    }
+
+    pub fn publish(&self, event: PublishedEvent) {
+        // The buffer lock is held while sending, so that `subscribe` sees every event either
+        // in the buffer or on its receiver, but never in both, and sequence numbers are sent
+        // in order.
+        let mut recent = self
+            .recent
+            .lock()
+            .expect("Poisoned published events buffer");
+        let event = SequencedEvent {
+            sequence: recent.next_sequence,
+            event,
+        };
+        recent.next_sequence += 1;
+        if recent.events.len() == RECENT_EVENTS_CAPACITY {
+            recent.events.pop_front();
+        }
+        recent.events.push_back(event.clone());
+        // Fails only when nobody is subscribed.
+        let _ = self.send.send(event);
+    }
+
+    pub fn publish_feed_updates(&self, block_height: u64, updates: &[VotedFeedUpdate]) {
+        for update in updates {
+            self.publish(PublishedEvent::FeedUpdate {
+                block_height,
+                feed_id: update.feed_id,
+                value: update.value.clone(),
+                end_slot_timestamp: update.end_slot_timestamp,
+            });
+        }
+    }
+
+    /// Subscribes to the live events. If `resume_from` is set, the buffered events after it
+    /// are returned too, to be sent before the live ones.
+    pub fn subscribe(
+        &self,
+        resume_from: Option<ResumeFrom>,
+    ) -> (Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>) {
+        let recent = self
+            .recent
+            .lock()
+            .expect("Poisoned published events buffer");
+        let missed = match resume_from {
+            Some(resume_from) => recent
+                .events
+                .iter()
+                .filter(|event| resume_from.includes(event))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        (missed, self.send.subscribe())
+    }
+
+    pub fn subscribers_count(&self) -> usize {
+        self.send.receiver_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_update(block_height: u64, feed_id: u32) -> PublishedEvent {
+        PublishedEvent::FeedUpdate {
+            block_height,
+            feed_id,
+            value: FeedType::Numerical(feed_id as f64),
+            end_slot_timestamp: 0,
+        }
+    }
+
+    fn events(missed: Vec<SequencedEvent>) -> Vec<(u64, PublishedEvent)> {
+        missed.into_iter().map(|e| (e.sequence, e.event)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_published_updates_stream() {
+        let stream = PublishedUpdatesStream::new();
+        stream.publish(feed_update(1, 1));
+        stream.publish(feed_update(2, 2));
+
+        let (missed, mut recv) = stream.subscribe(Some(ResumeFrom::BlockHeight(1)));
+        assert_eq!(events(missed), vec![(2, feed_update(2, 2))]);
+        let (missed, _) = stream.subscribe(None);
+        assert!(missed.is_empty());
+
+        let transaction = PublishedEvent::Transaction {
+            block_height: 1,
+            network: "ETH".to_string(),
+            tx_hash: "0x01".to_string(),
+            feed_ids: vec![3],
+        };
+        stream.publish(feed_update(3, 3));
+        stream.publish(transaction.clone());
+        let received = recv.recv().await.unwrap();
+        assert_eq!((received.sequence, received.event), (3, feed_update(3, 3)));
+        let received = recv.recv().await.unwrap();
+        assert_eq!(
+            (received.sequence, received.event),
+            (4, transaction.clone())
+        );
+
+        // The transaction of block 1 came after the update of block 3, so only the sequence
+        // number resumes after it.
+        let (missed, _) = stream.subscribe(Some(ResumeFrom::Sequence(2)));
+        assert_eq!(
+            events(missed),
+            vec![(3, feed_update(3, 3)), (4, transaction.clone())]
+        );
+        let (missed, _) = stream.subscribe(Some(ResumeFrom::BlockHeight(2)));
+        assert_eq!(events(missed), vec![(3, feed_update(3, 3))]);
+
+        let filter = EventsFilter {
+            feed_ids: Some(HashSet::from([3])),
+            include_transactions: false,
+        };
+        assert!(filter.matches(&feed_update(3, 3)));
+        assert!(!filter.matches(&feed_update(3, 2)));
+        assert!(!filter.matches(&transaction));
+        let filter = EventsFilter {
+            include_transactions: true,
+            ..filter
+        };
+        assert!(filter.matches(&transaction));
+    }
+
+    #[test]
+    fn test_recent_events_are_bounded() {
+        let stream = PublishedUpdatesStream::new();
+        for height in 0..RECENT_EVENTS_CAPACITY as u64 + 10 {
+            stream.publish(feed_update(height, 1));
+        }
+        let (missed, _) = stream.subscribe(Some(ResumeFrom::Sequence(0)));
+        assert_eq!(missed.len(), RECENT_EVENTS_CAPACITY);
+        assert_eq!(missed[0].event.block_height(), 10);
+        assert_eq!(missed[0].sequence, 11);
+    }
+}
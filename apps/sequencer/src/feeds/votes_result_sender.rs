@@ -46,6 +46,11 @@ pub async fn votes_result_sender_loop(
                         let sequencer_state = sequencer_state.clone();
                         async_send_to_contracts(sequencer_state, updates, batch_count);
                     }
+                    None if sequencer_state.shutdown.is_requested() => {
+                        info!("Dispatched the batches of the last block; stopping votes result sender");
+                        sequencer_state.shutdown.notify_votes_sender_stopped();
+                        return Ok(());
+                    }
                     None => {
                         panic!("Sender got RecvError"); // This error indicates a severe internal error.
                     }
@@ -64,9 +69,11 @@ fn async_send_to_contracts(
     updates: BatchedAggegratesToSend,
     batch_count: usize,
 ) {
+    let in_flight = sequencer_state.shutdown.track_in_flight_tx();
     let sender = tokio::task::Builder::new()
         .name(format!("batch_sender_{batch_count}").as_str())
         .spawn_local(async move {
+            let _in_flight = in_flight;
             debug!("Spawned batch_sender_{batch_count}");
             match eth_batch_send_to_all_contracts(sequencer_state, updates, Periodic).await {
                 Ok(res) => info!("Sending updates complete {res}."),
@@ -11,20 +11,28 @@ use alloy::{
     rpc::types::eth::TransactionRequest,
 };
 
-use blocksense_config::{AllFeedsConfig, SequencerConfig};
+use blocksense_config::{get_feeds_config_file_path, AllFeedsConfig, SequencerConfig};
 use blocksense_feed_registry::feed_registration_cmds::{
-    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed,
+    DeleteAssetFeed, FeedsManagementCmds, RegisterNewAssetFeed, UpdateAssetFeed,
 };
-use blocksense_registry::config::{FeedConfig, OracleScript, OraclesResponse};
+use blocksense_registry::config::{FeedConfig, NumericalEncoding, OracleScript, OraclesResponse};
 use blocksense_utils::logging::tokio_console_active;
+use blocksense_utils::time::current_unix_time;
 use eyre::eyre;
 use eyre::Result;
 use futures::StreamExt;
+use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 
+use crate::feeds::feeds_config_reloader;
+use crate::feeds::feeds_slots_manager::check_feed_config_update;
 use crate::http_handlers::data_feeds::register_feed;
 use crate::providers::eth_send_utils::deploy_contract;
 use crate::providers::provider::{SharedRpcProviders, PRICE_FEED_CONTRACT_NAME};
+use crate::providers::providers_management::{self, ProviderChange};
+use crate::reporters::reporters_management::{self, ReporterKeys};
+use blocksense_feed_registry::market_hours::MarketStatus;
+use blocksense_feed_registry::reputation::ReporterReputation;
 use blocksense_feed_registry::types::FeedType;
 use blocksense_metrics::metrics_collector::gather_and_dump_metrics;
 use tokio::time::Duration;
@@ -35,7 +43,9 @@ pub async fn get_key_from_contract(
     providers: &SharedRpcProviders,
     network: &String,
     key: String,
+    variant: FeedType,
     decimals: u8,
+    encoding: NumericalEncoding,
 ) -> Result<String> {
     let providers = providers.read().await;
 
@@ -66,10 +76,8 @@ pub async fn get_key_from_contract(
 
     let result = provider.call(&tx).await?;
     info!("Call result: {:?}", result);
-    // TODO: get from metadata the type of the value.
-    // TODO: Refector to not use dummy argument
     let return_val =
-        match FeedType::from_bytes(result.to_vec(), FeedType::Numerical(0.0), decimals as usize) {
+        match FeedType::from_bytes(result.to_vec(), variant, decimals as usize, encoding) {
             Ok(val) => val,
             Err(e) => {
                 return Err(eyre!("Could not deserialize feed from bytes {}", e));
@@ -119,10 +127,15 @@ pub async fn get_key(
         Err(e) => return Err(error::ErrorBadRequest(e.to_string())),
     };
 
-    let decimals = {
+    let (variant, decimals, encoding) = {
         let feeds_config = sequencer_state.active_feeds.read().await;
         if let Some(feed_config) = feeds_config.get(&feed_id) {
-            feed_config.additional_feed_info.decimals
+            (
+                FeedType::get_variant_from_config(feed_config)
+                    .map_err(error::ErrorInternalServerError)?,
+                feed_config.additional_feed_info.decimals,
+                feed_config.value_encoding,
+            )
         } else {
             return Err(error::ErrorBadRequest("Non-existent feed_id requested!"));
         }
@@ -131,7 +144,14 @@ pub async fn get_key(
     info!("getting key {} for network {} ...", key, network);
     let result = actix_web::rt::time::timeout(
         Duration::from_secs(7),
-        get_key_from_contract(&sequencer_state.providers, &network, key, decimals),
+        get_key_from_contract(
+            &sequencer_state.providers,
+            &network,
+            key,
+            variant,
+            decimals,
+            encoding,
+        ),
     )
     .await;
     match result {
@@ -289,6 +309,13 @@ pub async fn register_asset_feed(
     debug!("revcd body = {:?}!", body);
 
     let new_feed_config: FeedConfig = serde_json::from_str(std::str::from_utf8(&body)?)?;
+    if let Err(e) = sequencer_state
+        .market_calendars
+        .validate_feed(&new_feed_config)
+    {
+        error!("Can not register this data feed. {e}");
+        return Err(error::ErrorBadRequest(e.to_string()));
+    }
 
     {
         let reg = sequencer_state.registry.read().await;
@@ -321,6 +348,57 @@ pub async fn register_asset_feed(
     Ok(HttpResponse::Ok().into())
 }
 
+#[post("/update_asset_feed")]
+pub async fn update_asset_feed(
+    mut payload: web::Payload,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("update_asset_feed");
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        // limit max size of in-memory payload
+        if (body.len() + chunk.len()) > MAX_SIZE {
+            return Err(error::ErrorBadRequest("overflow"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    debug!("revcd body = {:?}!", body);
+
+    let feed_config: FeedConfig = serde_json::from_str(std::str::from_utf8(&body)?)?;
+    if let Err(e) = check_feed_config_update(&sequencer_state, &feed_config).await {
+        error!("Can not update this data feed. {e}");
+        return Err(error::ErrorBadRequest(e.to_string()));
+    }
+
+    {
+        let reg = sequencer_state.registry.read().await;
+        let feed_id = feed_config.id;
+
+        if !reg.get_keys().contains(&feed_id) {
+            let err_msg =
+                format!("Can not update this data feed. Feed with ID {feed_id} does not exist.");
+            error!(err_msg);
+            return Err(error::ErrorBadRequest(err_msg));
+        }
+    }
+    match sequencer_state
+        .feeds_management_cmd_to_block_creator_send
+        .send(FeedsManagementCmds::UpdateAssetFeed(UpdateAssetFeed {
+            config: feed_config.clone(),
+        })) {
+        Ok(_) => {
+            info!("Scheduled update of asset data feed: {feed_config:?}",);
+        }
+        Err(e) => {
+            panic!("Sequencer internal error, could not forward feed update cmd {e}")
+        }
+    };
+
+    Ok(HttpResponse::Ok().into())
+}
+
 async fn set_provider_is_enabled(
     req: HttpRequest,
     sequencer_state: web::Data<SequencerState>,
@@ -420,6 +498,58 @@ pub async fn enable_provider(
     set_provider_is_enabled(req, sequencer_state, true).await
 }
 
+fn parse_network_name(req: &HttpRequest) -> Result<String, Error> {
+    match req.match_info().get("network_name") {
+        Some(network_name) => Ok(network_name.to_string()),
+        None => Err(error::ErrorBadRequest("Missing field 'network_name'")),
+    }
+}
+
+/// Adds a network to publish to, with the provider config and optional history loading
+/// given in the body.
+#[post("/add_provider/{network_name}")]
+pub async fn add_provider(
+    req: HttpRequest,
+    change: web::Json<ProviderChange>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("add_provider");
+    let network_name = parse_network_name(&req)?;
+    providers_management::add_provider(&sequencer_state, &network_name, change.into_inner())
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+/// Replaces the provider config of a network, keeping the history and round counters of
+/// its feeds.
+#[post("/update_provider/{network_name}")]
+pub async fn update_provider(
+    req: HttpRequest,
+    change: web::Json<ProviderChange>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("update_provider");
+    let network_name = parse_network_name(&req)?;
+    providers_management::update_provider(&sequencer_state, &network_name, change.into_inner())
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+#[post("/remove_provider/{network_name}")]
+pub async fn remove_provider(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("remove_provider");
+    let network_name = parse_network_name(&req)?;
+    providers_management::remove_provider(&sequencer_state, &network_name)
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
 #[get("/list_provider_status")]
 pub async fn list_provider_status(sequencer_state: web::Data<SequencerState>) -> HttpResponse {
     let provider_status = sequencer_state.provider_status.read().await;
@@ -447,6 +577,222 @@ pub async fn get_history(sequencer_state: web::Data<SequencerState>) -> HttpResp
     }
 }
 
+#[derive(Serialize)]
+struct FeedMarketStatus {
+    market_hours: Option<String>,
+    #[serde(flatten)]
+    status: MarketStatus,
+}
+
+fn feed_market_status(
+    sequencer_state: &SequencerState,
+    feed_config: &FeedConfig,
+    timestamp: u128,
+) -> FeedMarketStatus {
+    let market_hours = feed_config.additional_feed_info.market_hours.clone();
+    let status = sequencer_state
+        .market_calendars
+        .status(market_hours.as_deref(), timestamp);
+    FeedMarketStatus {
+        market_hours,
+        status,
+    }
+}
+
+#[get("/get_market_status")]
+pub async fn get_market_status(
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let now = current_unix_time();
+    let active_feeds = sequencer_state.active_feeds.read().await;
+    let statuses: BTreeMap<u32, FeedMarketStatus> = active_feeds
+        .iter()
+        .map(|(id, feed)| (*id, feed_market_status(&sequencer_state, feed, now)))
+        .collect();
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(serde_json::to_string_pretty(&statuses)?))
+}
+
+#[get("/get_market_status/{feed_id}")]
+pub async fn get_feed_market_status(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let bad_input = error::ErrorBadRequest("Incorrect input.");
+    let feed_id: String = req.match_info().get("feed_id").ok_or(bad_input)?.parse()?;
+
+    let feed_id: u32 = match feed_id.parse() {
+        Ok(r) => r,
+        Err(e) => return Err(error::ErrorBadRequest(e.to_string())),
+    };
+
+    let active_feeds = sequencer_state.active_feeds.read().await;
+    let feed_config = active_feeds
+        .get(&feed_id)
+        .ok_or(error::ErrorNotFound("Data feed with this ID not found"))?;
+    let status = feed_market_status(&sequencer_state, feed_config, current_unix_time());
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(serde_json::to_string_pretty(&status)?))
+}
+
+#[derive(Serialize)]
+struct ReporterReputationStatus {
+    score: f64,
+    #[serde(flatten)]
+    reputation: ReporterReputation,
+}
+
+impl From<&ReporterReputation> for ReporterReputationStatus {
+    fn from(reputation: &ReporterReputation) -> Self {
+        ReporterReputationStatus {
+            score: reputation.score(),
+            reputation: reputation.clone(),
+        }
+    }
+}
+
+#[get("/get_reporters_reputation")]
+pub async fn get_reporters_reputation(
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let reputations = sequencer_state.reporter_reputation.read().await;
+    let statuses: BTreeMap<u64, ReporterReputationStatus> = reputations
+        .reporters()
+        .iter()
+        .map(|(id, reputation)| (*id, reputation.into()))
+        .collect();
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(serde_json::to_string_pretty(&statuses)?))
+}
+
+#[get("/get_reporter_reputation/{reporter_id}")]
+pub async fn get_reporter_reputation(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let bad_input = error::ErrorBadRequest("Incorrect input.");
+    let reporter_id: String = req
+        .match_info()
+        .get("reporter_id")
+        .ok_or(bad_input)?
+        .parse()?;
+
+    let reporter_id: u64 = match reporter_id.parse() {
+        Ok(r) => r,
+        Err(e) => return Err(error::ErrorBadRequest(e.to_string())),
+    };
+
+    let reputations = sequencer_state.reporter_reputation.read().await;
+    let status: ReporterReputationStatus = reputations
+        .get(reporter_id)
+        .ok_or(error::ErrorNotFound(
+            "No reputation for reporter with this ID",
+        ))?
+        .into();
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(serde_json::to_string_pretty(&status)?))
+}
+
+fn parse_reporter_id(req: &HttpRequest) -> Result<u32, Error> {
+    let bad_input = error::ErrorBadRequest("Incorrect input.");
+    let reporter_id: String = req
+        .match_info()
+        .get("reporter_id")
+        .ok_or(bad_input)?
+        .parse()?;
+    reporter_id
+        .parse()
+        .map_err(|e: std::num::ParseIntError| error::ErrorBadRequest(e.to_string()))
+}
+
+#[get("/list_reporters")]
+pub async fn list_reporters(
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let sequencer_config = sequencer_state.sequencer_config.read().await;
+    let reporters: BTreeMap<u32, &blocksense_config::Reporter> = sequencer_config
+        .reporters
+        .iter()
+        .map(|r| (r.id, r))
+        .collect();
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .body(serde_json::to_string_pretty(&reporters)?))
+}
+
+#[post("/add_reporter")]
+pub async fn add_reporter(
+    reporter: web::Json<blocksense_config::Reporter>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("add_reporter");
+    reporters_management::add_reporter(&sequencer_state, reporter.into_inner())
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+#[post("/remove_reporter/{reporter_id}")]
+pub async fn remove_reporter(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("remove_reporter");
+    let reporter_id = parse_reporter_id(&req)?;
+    reporters_management::remove_reporter(&sequencer_state, reporter_id)
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+/// Rejects the votes of a reporter and excludes it from quorums until it is resumed.
+#[post("/suspend_reporter/{reporter_id}")]
+pub async fn suspend_reporter(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("suspend_reporter");
+    let reporter_id = parse_reporter_id(&req)?;
+    reporters_management::set_reporter_suspended(&sequencer_state, reporter_id, true)
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+#[post("/resume_reporter/{reporter_id}")]
+pub async fn resume_reporter(
+    req: HttpRequest,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("resume_reporter");
+    let reporter_id = parse_reporter_id(&req)?;
+    reporters_management::set_reporter_suspended(&sequencer_state, reporter_id, false)
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
+/// Replaces the BLS pub key and the second round address of a reporter.
+#[post("/rotate_reporter_key/{reporter_id}")]
+pub async fn rotate_reporter_key(
+    req: HttpRequest,
+    keys: web::Json<ReporterKeys>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("rotate_reporter_key");
+    let reporter_id = parse_reporter_id(&req)?;
+    reporters_management::rotate_reporter_keys(&sequencer_state, reporter_id, keys.into_inner())
+        .await
+        .map_err(|e| error::ErrorBadRequest(e.to_string()))?;
+    Ok(HttpResponse::Ok().into())
+}
+
 #[post("/delete_asset_feed/{feed_id}")]
 pub async fn delete_asset_feed(
     req: HttpRequest,
@@ -494,6 +840,23 @@ pub async fn delete_asset_feed(
         .body(format!("{}", feed.read().await.get_report_interval_ms())))
 }
 
+#[post("/reload_feeds_config")]
+pub async fn reload_feeds_config(
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let _span = info_span!("reload_feeds_config");
+    let path = get_feeds_config_file_path();
+    match feeds_config_reloader::reload_feeds_config(&sequencer_state, &path).await {
+        Ok(changes) => Ok(HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(serde_json::to_string_pretty(&changes)?)),
+        Err(e) => {
+            error!("{e}");
+            Err(error::ErrorBadRequest(e.to_string()))
+        }
+    }
+}
+
 #[get("/get_oracle_scripts")]
 pub async fn get_oracle_scripts(
     _sequencer_state: web::Data<SequencerState>,
@@ -609,11 +972,26 @@ pub fn add_admin_services(cfg: &mut ServiceConfig) {
         .service(get_feed_config)
         .service(get_sequencer_config)
         .service(register_asset_feed)
+        .service(update_asset_feed)
         .service(delete_asset_feed)
+        .service(reload_feeds_config)
         .service(disable_provider)
         .service(enable_provider)
         .service(list_provider_status)
+        .service(add_provider)
+        .service(update_provider)
+        .service(remove_provider)
         .service(get_history)
+        .service(get_market_status)
+        .service(get_feed_market_status)
+        .service(get_reporters_reputation)
+        .service(get_reporter_reputation)
+        .service(list_reporters)
+        .service(add_reporter)
+        .service(remove_reporter)
+        .service(suspend_reporter)
+        .service(resume_reporter)
+        .service(rotate_reporter_key)
         .service(get_oracle_scripts)
         .service(health);
 }
@@ -621,7 +999,7 @@ pub fn add_admin_services(cfg: &mut ServiceConfig) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::providers::provider::init_shared_rpc_providers;
+    use crate::providers::provider::{init_shared_rpc_providers, new_provider_metrics};
     use actix_test::to_bytes;
     use actix_web::{test, App};
     use alloy::node_bindings::Anvil;
@@ -650,8 +1028,9 @@ mod tests {
 
         let metrics_prefix = Some("test_get_feed_report_interval_");
 
+        let provider_metrics = new_provider_metrics(metrics_prefix);
         let providers =
-            init_shared_rpc_providers(&sequencer_config, metrics_prefix, &feeds_config).await;
+            init_shared_rpc_providers(&sequencer_config, &provider_metrics, &feeds_config).await;
 
         let (vote_send, _vote_recv) = mpsc::unbounded_channel();
         let (
@@ -662,18 +1041,22 @@ mod tests {
             mpsc::unbounded_channel();
         let (aggregate_batch_sig_send, _aggregate_batch_sig_recv) = mpsc::unbounded_channel();
 
-        let sequencer_state = web::Data::new(SequencerState::new(
-            feeds_config,
-            providers,
-            log_handle,
-            &sequencer_config,
-            metrics_prefix,
-            None,
-            vote_send,
-            feeds_management_cmd_to_block_creator_send,
-            feeds_slots_manager_cmd_send,
-            aggregate_batch_sig_send,
-        ));
+        let sequencer_state = web::Data::new(
+            SequencerState::new(
+                feeds_config,
+                providers,
+                provider_metrics,
+                log_handle,
+                &sequencer_config,
+                metrics_prefix,
+                None,
+                vote_send,
+                feeds_management_cmd_to_block_creator_send,
+                feeds_slots_manager_cmd_send,
+                aggregate_batch_sig_send,
+            )
+            .unwrap(),
+        );
 
         let app = test::init_service(
             App::new()
@@ -751,6 +1134,130 @@ mod tests {
         assert_eq!(resp.status(), 400);
     }
 
+    #[actix_web::test]
+    async fn test_get_market_status() {
+        let sequencer_config = get_test_config_with_no_providers();
+        let crypto_feed = test_feed_config(1, 0);
+        let mut equity_feed = test_feed_config(2, 0);
+        equity_feed.additional_feed_info.market_hours = Some("NYSE".to_owned());
+        let feeds_config = AllFeedsConfig {
+            feeds: vec![crypto_feed, equity_feed],
+        };
+        let (
+            sequencer_state,
+            _vote_recv,
+            _feeds_management_cmd_to_block_creator_recv,
+            _feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            sequencer_config,
+            "test_get_market_status",
+            feeds_config,
+        )
+        .await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_admin_services),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/get_market_status")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body = test::read_body(resp).await;
+        let statuses: serde_json::Value =
+            serde_json::from_slice(&body).expect("Response is not valid JSON!");
+
+        assert_eq!(
+            statuses["1"],
+            serde_json::json!({
+                "market_hours": "Crypto",
+                "is_open": true,
+                "next_open": null,
+                "next_close": null,
+            })
+        );
+        let equity = &statuses["2"];
+        assert_eq!(equity["market_hours"], "NYSE");
+        let is_open = equity["is_open"].as_bool().unwrap();
+        assert_eq!(equity["next_close"].is_null(), !is_open);
+        assert_eq!(equity["next_open"].is_null(), is_open);
+
+        let req = test::TestRequest::get()
+            .uri("/get_market_status/2")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri("/get_market_status/3")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_get_reporter_reputation() {
+        let sequencer_config = get_test_config_with_no_providers();
+        let feeds_config = AllFeedsConfig {
+            feeds: vec![test_feed_config(1, 0)],
+        };
+        let (
+            sequencer_state,
+            _vote_recv,
+            _feeds_management_cmd_to_block_creator_recv,
+            _feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            sequencer_config,
+            "test_get_reporter_reputation",
+            feeds_config,
+        )
+        .await;
+        sequencer_state
+            .reporter_reputation
+            .write()
+            .await
+            .record_timeliness(7, false);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_admin_services),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/get_reporters_reputation")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body = test::read_body(resp).await;
+        let reputations: serde_json::Value =
+            serde_json::from_slice(&body).expect("Response is not valid JSON!");
+        let reputation = &reputations["7"];
+        assert_eq!(reputation["accuracy"], 1.0);
+        assert!(reputation["punctuality"].as_f64().unwrap() < 1.0);
+        assert_eq!(reputation["score"], reputation["punctuality"]);
+        assert_eq!(reputation["jailed"], false);
+
+        let req = test::TestRequest::get()
+            .uri("/get_reporter_reputation/7")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri("/get_reporter_reputation/8")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404);
+    }
+
     #[actix_web::test]
     async fn test_get_feeds_config() {
         let sequencer_config = get_test_config_with_no_providers();
@@ -0,0 +1,226 @@
+//! Bearer token authentication of the admin API. Every token grants a set of `AdminRole`s;
+//! reading endpoints need any role, deploying contracts needs `Deployer` and all other
+//! changes need `Operator`. `/health` stays open for liveness probes.
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::{header, Method};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpResponse};
+use blocksense_config::{AdminAuth, AdminRole};
+use blocksense_metrics::inc_metric;
+use std::fs;
+use tracing::{info, warn};
+
+use crate::sequencer_state::SequencerState;
+
+pub struct AdminTokens {
+    tokens: Vec<(String, String, Vec<AdminRole>)>,
+}
+
+impl AdminTokens {
+    /// Reads the tokens listed in `config`. Returns `None`, leaving the admin API open, if
+    /// authentication is not configured.
+    pub fn load(config: Option<&AdminAuth>) -> Option<AdminTokens> {
+        let Some(config) = config else {
+            warn!("No admin_auth configured, the admin API is not authenticated!");
+            return None;
+        };
+        let tokens = config
+            .tokens
+            .iter()
+            .map(|t| {
+                let token = fs::read_to_string(&t.token_path).unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to read admin token {} from {}: {e}",
+                        t.name, t.token_path
+                    )
+                });
+                (t.name.clone(), token.trim().to_string(), t.roles.clone())
+            })
+            .collect();
+        Some(AdminTokens { tokens })
+    }
+
+    /// Name and roles of the holder of `token`.
+    fn find(&self, token: &str) -> Option<(&str, &[AdminRole])> {
+        self.tokens
+            .iter()
+            .find(|(_, t, _)| constant_time_eq(t.as_bytes(), token.as_bytes()))
+            .map(|(name, _, roles)| (name.as_str(), roles.as_slice()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Role needed to call `path`, or `None` for endpoints open to everybody.
+fn required_role(method: &Method, path: &str) -> Option<AdminRole> {
+    if path == "/health" {
+        None
+    } else if path.starts_with("/deploy/") {
+        Some(AdminRole::Deployer)
+    } else if method == Method::GET {
+        Some(AdminRole::Observer)
+    } else {
+        Some(AdminRole::Operator)
+    }
+}
+
+fn grants(roles: &[AdminRole], required: AdminRole) -> bool {
+    required == AdminRole::Observer || roles.contains(&required)
+}
+
+fn bearer_token(req: &ServiceRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+pub async fn admin_auth(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(sequencer_state) = req.app_data::<web::Data<SequencerState>>().cloned() else {
+        return next.call(req).await.map(|res| res.map_into_left_body());
+    };
+    let (Some(admin_tokens), Some(required)) = (
+        sequencer_state.admin_tokens.as_ref(),
+        required_role(req.method(), req.path()),
+    ) else {
+        return next.call(req).await.map(|res| res.map_into_left_body());
+    };
+
+    let endpoint = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let admin_metrics = sequencer_state.admin_metrics.clone();
+    let Some((name, roles)) = bearer_token(&req).and_then(|token| admin_tokens.find(token)) else {
+        warn!("Rejected unauthenticated call to admin endpoint {endpoint}");
+        inc_metric!(admin_metrics, endpoint, unauthorized_calls);
+        let response = HttpResponse::Unauthorized()
+            .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+            .finish();
+        return Ok(req.into_response(response).map_into_right_body());
+    };
+    if !grants(roles, required) {
+        warn!("Rejected call of {name} to admin endpoint {endpoint}, which needs the {required:?} role");
+        inc_metric!(admin_metrics, endpoint, forbidden_calls);
+        let response = HttpResponse::Forbidden().finish();
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+    info!("Admin endpoint {endpoint} called by {name}");
+    next.call(req).await.map(|res| res.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_handlers::admin::add_admin_services;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, App};
+    use blocksense_config::{get_test_config_with_no_providers, AdminToken, AllFeedsConfig};
+
+    #[actix_web::test]
+    async fn test_admin_auth() {
+        let dir = std::env::temp_dir().join("test_admin_auth");
+        fs::create_dir_all(&dir).unwrap();
+        let mut tokens = Vec::new();
+        for (name, roles) in [
+            ("observer", vec![AdminRole::Observer]),
+            ("operator", vec![AdminRole::Operator]),
+        ] {
+            let token_path = dir.join(name);
+            fs::write(&token_path, format!("{name}-secret\n")).unwrap();
+            tokens.push(AdminToken {
+                name: name.to_string(),
+                token_path: token_path.to_str().unwrap().to_string(),
+                roles,
+            });
+        }
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.admin_auth = Some(AdminAuth { tokens });
+
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            sequencer_config,
+            "test_admin_auth",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .wrap(from_fn(admin_auth))
+                .configure(add_admin_services),
+        )
+        .await;
+
+        let call = |method: Method, uri: &str, token: Option<&str>| {
+            let mut req = test::TestRequest::default().method(method).uri(uri);
+            if let Some(token) = token {
+                req = req.insert_header((header::AUTHORIZATION, format!("Bearer {token}")));
+            }
+            req.to_request()
+        };
+
+        let resp = test::call_service(&app, call(Method::GET, "/health", None)).await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = test::call_service(&app, call(Method::GET, "/get_feeds_config", None)).await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(
+            &app,
+            call(Method::GET, "/get_feeds_config", Some("wrong-secret")),
+        )
+        .await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(
+            &app,
+            call(Method::GET, "/get_feeds_config", Some("observer-secret")),
+        )
+        .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = test::call_service(
+            &app,
+            call(
+                Method::POST,
+                "/main_log_level/info",
+                Some("observer-secret"),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), 403);
+        let resp = test::call_service(
+            &app,
+            call(
+                Method::GET,
+                "/deploy/ETH/price_feed",
+                Some("operator-secret"),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), 403);
+
+        let metrics = sequencer_state.admin_metrics.read().await;
+        assert_eq!(
+            metrics
+                .unauthorized_calls
+                .with_label_values(&["/get_feeds_config"])
+                .get(),
+            2
+        );
+        assert_eq!(
+            metrics
+                .forbidden_calls
+                .with_label_values(&["/deploy/{network}/{feed_type}"])
+                .get(),
+            1
+        );
+    }
+}
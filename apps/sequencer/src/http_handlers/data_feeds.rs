@@ -1,4 +1,5 @@
-use actix_web::http::StatusCode;
+use actix_web::http::{header, StatusCode};
+use alloy::hex;
 use alloy_primitives::{FixedBytes, PrimitiveSignature};
 use blocksense_gnosis_safe::utils::SignatureWithAddress;
 use blocksense_utils::time::current_unix_time;
@@ -7,10 +8,13 @@ use eyre::Result;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use actix_web::error::{ErrorBadRequest, ErrorInternalServerError};
+use actix_web::error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound};
 use actix_web::web::{self, ServiceConfig};
 use actix_web::Error;
-use actix_web::{get, post, HttpResponse};
+use actix_web::{get, post, HttpRequest, HttpResponse};
+use blocksense_feed_registry::reports_encoding::{
+    decode_reports_batch, REPORTS_BATCH_CONTENT_TYPE,
+};
 use blocksense_feed_registry::types::{
     GetLastPublishedRequestData, LastPublishedValue, ReportRelevance,
 };
@@ -21,9 +25,13 @@ use tracing::{debug, error, info, info_span, warn};
 use uuid::Uuid;
 
 use crate::feeds::feed_slots_processor::FeedSlotsProcessor;
+use crate::feeds::feeds_slots_manager::deregister_oneshot_feed;
+use crate::feeds::published_updates_stream::{EventsFilter, ResumeFrom, SequencedEvent};
 use crate::http_handlers::MAX_SIZE;
 use crate::sequencer_state::SequencerState;
 use blocksense_config::SequencerConfig;
+use blocksense_crypto::{verify_signature, verify_signatures_batch, PublicKey, Signature};
+use blocksense_data_feeds::generate_signature::report_signing_message;
 use blocksense_feed_registry::registry::FeedAggregateHistory;
 use blocksense_feed_registry::registry::VoteStatus;
 use blocksense_feed_registry::types::DataFeedPayload;
@@ -31,7 +39,10 @@ use blocksense_feed_registry::types::FeedMetaData;
 use blocksense_feeds_processing::utils::check_signature;
 use blocksense_gnosis_safe::data_types::ReporterResponse;
 use blocksense_metrics::{inc_metric, inc_vec_metric};
+use blocksense_registry::config::NumericalEncoding;
+use std::collections::{HashSet, VecDeque};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::Duration;
 
@@ -43,10 +54,22 @@ fn get_max_buffer_size(cfg: &SequencerConfig) -> usize {
     }
 }
 
+/// Signature of a report, if it was verified before `process_report`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SignatureCheck {
+    Pending,
+    Valid,
+    Invalid,
+}
+
 async fn process_report(
     sequencer_state: &web::Data<SequencerState>,
     data_feed: DataFeedPayload,
+    signature_check: SignatureCheck,
 ) -> HttpResponse {
+    if sequencer_state.shutdown.is_requested() {
+        return HttpResponse::ServiceUnavailable().body("Sequencer is shutting down");
+    }
     let reporter_id = data_feed.payload_metadata.reporter_id;
     let signature = &data_feed.payload_metadata.signature;
     let msg_timestamp = data_feed.payload_metadata.timestamp;
@@ -69,13 +92,41 @@ async fn process_report(
                 };
                 {
                     let rlocked_reporter = reporter.read().await;
-                    if !check_signature(
-                        &signature.sig,
-                        &rlocked_reporter.pub_key,
-                        data_feed.payload_metadata.feed_id.as_str(),
-                        msg_timestamp,
-                        &data_feed.result,
-                    ) {
+                    if rlocked_reporter.suspended {
+                        debug!("Recvd vote from suspended reporter_id: {reporter_id}");
+                        return HttpResponse::Forbidden().body("Reporter is suspended");
+                    }
+                    let signing_scheme = data_feed.payload_metadata.signing_scheme;
+                    let report_signing = &sequencer_state.report_signing;
+                    if !report_signing.accepts(signing_scheme) {
+                        drop(rlocked_reporter);
+                        warn!("Recvd vote signed with {signing_scheme:?}, which is not accepted, from reporter_id: {reporter_id}");
+                        inc_metric!(reporter_metrics, reporter_id, unaccepted_signing_scheme);
+                        return HttpResponse::Unauthorized()
+                            .body(format!("Signing scheme {signing_scheme:?} is not accepted"));
+                    }
+                    let valid_signature = match signature_check {
+                        SignatureCheck::Pending => match check_signature(
+                            &signature.sig,
+                            &rlocked_reporter.pub_key,
+                            signing_scheme,
+                            report_signing.deployment_id.as_str(),
+                            data_feed.payload_metadata.feed_id.as_str(),
+                            msg_timestamp,
+                            &data_feed.result,
+                        ) {
+                            Ok(valid) => valid,
+                            Err(e) => {
+                                drop(rlocked_reporter);
+                                warn!("Recvd vote that can not be signed from reporter_id: {reporter_id}: {e}");
+                                inc_metric!(reporter_metrics, reporter_id, non_valid_signature);
+                                return HttpResponse::BadRequest().body(e.to_string());
+                            }
+                        },
+                        SignatureCheck::Valid => true,
+                        SignatureCheck::Invalid => false,
+                    };
+                    if !valid_signature {
                         drop(rlocked_reporter);
                         warn!(
                             "Signature check failed for feed_id: {} from reporter_id: {}",
@@ -98,6 +149,16 @@ async fn process_report(
     };
     let reporter_metrics = reporter.read().await.reporter_metrics.clone();
 
+    // Checked once the signature is verified, so that reports forged with the id of a
+    // reporter do not use up its rate limit.
+    if let Some(reports_limiter) = &sequencer_state.reports_limiter {
+        if !reports_limiter.allow_report(reporter_id) {
+            debug!("Rate limited report from reporter_id = {reporter_id} for feed_id = {feed_id}");
+            inc_metric!(reporter_metrics, reporter_id, rate_limited_reports);
+            return HttpResponse::TooManyRequests().body("Reporter rate limit exceeded");
+        }
+    }
+
     match &data_feed.result {
         Ok(result) => {
             debug!(
@@ -128,6 +189,31 @@ async fn process_report(
         }
     };
 
+    if let Ok(value) = &data_feed.result {
+        if value.is_negative() {
+            let encoding = {
+                let active_feeds = sequencer_state.active_feeds.read().await;
+                active_feeds
+                    .get(&feed_id)
+                    .map(|f| f.value_encoding)
+                    .unwrap_or_default()
+            };
+            if encoding == NumericalEncoding::Unsigned {
+                warn!(
+                    "Recvd negative value {:?} from reporter_id = {} for unsigned feed_id = {}",
+                    value, reporter_id, feed_id
+                );
+                inc_vec_metric!(
+                    reporter_metrics,
+                    reporter_id,
+                    negative_reports_for_unsigned_feed,
+                    feed_id
+                );
+                return HttpResponse::BadRequest().into();
+            }
+        }
+    }
+
     let current_time_as_ms = current_unix_time();
 
     // check if the time stamp in the msg is <= current_time_as_ms
@@ -140,6 +226,25 @@ async fn process_report(
     match report_relevance {
         ReportRelevance::Relevant => {
             let mut reports = sequencer_state.reports.write().await;
+            if let (Some(reports_limiter), Some(revotes)) = (
+                &sequencer_state.reports_limiter,
+                reports.revotes(feed_id, reporter_id).await,
+            ) {
+                if !reports_limiter.allow_revote(revotes) {
+                    drop(reports);
+                    debug!(
+                        "Rejected revote {} from reporter_id = {reporter_id} for feed_id = {feed_id}",
+                        revotes + 1
+                    );
+                    inc_vec_metric!(
+                        reporter_metrics,
+                        reporter_id,
+                        revotes_over_limit_per_feed,
+                        feed_id
+                    );
+                    return HttpResponse::TooManyRequests().body("Revotes limit exceeded");
+                }
+            }
             match reports.push(feed_id, reporter_id, data_feed).await {
                 VoteStatus::FirstVoteForSlot => {
                     debug!(
@@ -179,6 +284,13 @@ async fn process_report(
                 late_reports_per_feed,
                 feed_id
             );
+            // Scored along with the current slot of the feed.
+            sequencer_state
+                .reports
+                .write()
+                .await
+                .push_late(feed_id, reporter_id)
+                .await;
         }
         ReportRelevance::NonRelevantInFuture => {
             debug!(
@@ -196,11 +308,37 @@ async fn process_report(
     HttpResponse::BadRequest().into()
 }
 
+/// Applies the per IP rate limit of `reports_limits` to a request posting reports.
+async fn allow_reports_request(req: &HttpRequest, sequencer_state: &SequencerState) -> bool {
+    let (Some(reports_limiter), Some(peer_addr)) =
+        (&sequencer_state.reports_limiter, req.peer_addr())
+    else {
+        return true;
+    };
+    let ip = peer_addr.ip();
+    if reports_limiter.allow_request(ip) {
+        return true;
+    }
+    debug!("Rate limited request from {ip} to {}", req.path());
+    // Not labeled by IP, which would make the number of series unbounded.
+    sequencer_state
+        .reporter_metrics
+        .read()
+        .await
+        .rate_limited_ip_requests
+        .inc();
+    false
+}
+
 #[post("/post_report")]
 pub async fn post_report(
+    req: HttpRequest,
     mut payload: web::Payload,
     sequencer_state: web::Data<SequencerState>,
 ) -> Result<HttpResponse, Error> {
+    if !allow_reports_request(&req, &sequencer_state).await {
+        return Ok(HttpResponse::TooManyRequests().body("Rate limit exceeded"));
+    }
     let max_size = get_max_buffer_size(&*sequencer_state.sequencer_config.read().await);
     let mut body = web::BytesMut::new();
     while let Some(chunk) = payload.next().await {
@@ -219,7 +357,7 @@ pub async fn post_report(
     let v: serde_json::Value = serde_json::from_str(std::str::from_utf8(&body)?)?;
     let data_feed: DataFeedPayload = serde_json::from_value(v)?;
 
-    Ok(process_report(&sequencer_state, data_feed).await)
+    Ok(process_report(&sequencer_state, data_feed, SignatureCheck::Pending).await)
 }
 
 #[get("/get_last_published_value_and_time")]
@@ -278,13 +416,7 @@ pub async fn get_last_published_value_and_time(
 
 use serde::de::DeserializeOwned;
 
-async fn deserialize_payload_to_vec<T>(
-    mut payload: web::Payload,
-    max_size: usize,
-) -> Result<Vec<T>, Error>
-where
-    T: DeserializeOwned,
-{
+async fn read_payload(mut payload: web::Payload, max_size: usize) -> Result<web::BytesMut, Error> {
     let mut body = web::BytesMut::new();
     while let Some(chunk) = payload.next().await {
         let chunk = chunk?;
@@ -294,6 +426,17 @@ where
         }
         body.extend_from_slice(&chunk);
     }
+    Ok(body)
+}
+
+async fn deserialize_payload_to_vec<T>(
+    payload: web::Payload,
+    max_size: usize,
+) -> Result<Vec<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let body = read_payload(payload, max_size).await?;
 
     // body is loaded, now we can deserialize serde-json
     // let obj = serde_json::from_slice::<MyObj>(&body)?;
@@ -304,30 +447,141 @@ where
     Ok(vec_t)
 }
 
+/// Verifies the signatures of a batch of reports together, on the blocking thread pool. If the
+/// batch does not verify, the signatures are checked one by one to find the invalid ones.
+/// Reports of unknown or suspended reporters, signed with a scheme that is not accepted, or with
+/// values that can not be signed, are left to `process_report` to reject.
+async fn verify_batch_signatures(
+    sequencer_state: &SequencerState,
+    data_feeds: &[DataFeedPayload],
+) -> Result<Vec<SignatureCheck>, Error> {
+    let mut signature_checks = vec![SignatureCheck::Pending; data_feeds.len()];
+    let mut to_verify = Vec::new();
+    {
+        let report_signing = &sequencer_state.report_signing;
+        let reporters = sequencer_state.reporters.read().await;
+        for (index, data_feed) in data_feeds.iter().enumerate() {
+            let metadata = &data_feed.payload_metadata;
+            if !report_signing.accepts(metadata.signing_scheme) {
+                continue;
+            }
+            let Some(reporter) = reporters.get(&metadata.reporter_id) else {
+                continue;
+            };
+            let reporter = reporter.read().await;
+            if reporter.suspended {
+                continue;
+            }
+            let Ok(message) = report_signing_message(
+                metadata.signing_scheme,
+                &report_signing.deployment_id,
+                &metadata.feed_id,
+                metadata.timestamp,
+                &data_feed.result,
+            ) else {
+                continue;
+            };
+            to_verify.push((index, reporter.pub_key, metadata.signature.sig, message));
+        }
+    }
+    if to_verify.is_empty() {
+        return Ok(signature_checks);
+    }
+
+    let verified = web::block(move || {
+        let pub_keys: Vec<&PublicKey> = to_verify.iter().map(|(_, pk, _, _)| pk).collect();
+        let signatures: Vec<&Signature> = to_verify.iter().map(|(_, _, sig, _)| sig).collect();
+        let messages: Vec<&[u8]> = to_verify
+            .iter()
+            .map(|(_, _, _, msg)| msg.as_slice())
+            .collect();
+        if verify_signatures_batch(&pub_keys, &signatures, &messages) {
+            return to_verify
+                .iter()
+                .map(|(index, _, _, _)| (*index, true))
+                .collect::<Vec<_>>();
+        }
+        debug!("Batch signature verification failed; verifying signatures one by one");
+        to_verify
+            .iter()
+            .map(|(index, pk, sig, msg)| (*index, verify_signature(pk, sig, msg)))
+            .collect()
+    })
+    .await?;
+
+    for (index, valid) in verified {
+        signature_checks[index] = if valid {
+            SignatureCheck::Valid
+        } else {
+            SignatureCheck::Invalid
+        };
+    }
+    Ok(signature_checks)
+}
+
+/// Whether the body is in the binary encoding of `reports_encoding` rather than JSON.
+fn is_encoded_reports_batch(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .is_some_and(|mime| mime.trim() == REPORTS_BATCH_CONTENT_TYPE)
+}
+
 #[post("/post_reports_batch")]
 pub async fn post_reports_batch(
+    req: HttpRequest,
     payload: web::Payload,
     sequencer_state: web::Data<SequencerState>,
 ) -> Result<HttpResponse, Error> {
+    if !allow_reports_request(&req, &sequencer_state).await {
+        return Ok(HttpResponse::TooManyRequests().body("Rate limit exceeded"));
+    }
     let max_size = get_max_buffer_size(&*sequencer_state.sequencer_config.read().await);
 
     let span = info_span!("post_reports_batch");
     let _guard = span.enter();
 
-    let data_feeds: Vec<DataFeedPayload> =
-        deserialize_payload_to_vec::<DataFeedPayload>(payload, max_size).await?;
+    let data_feeds: Vec<DataFeedPayload> = if is_encoded_reports_batch(&req) {
+        let body = read_payload(payload, max_size).await?;
+        decode_reports_batch(&body)
+            .map_err(|e| ErrorBadRequest(format!("Invalid reports batch: {e}")))?
+    } else {
+        deserialize_payload_to_vec::<DataFeedPayload>(payload, max_size).await?
+    };
     info!("Received batches {}", data_feeds.len());
 
+    if let Some(reports_limiter) = &sequencer_state.reports_limiter {
+        if !reports_limiter.allow_batch(data_feeds.len()) {
+            // Not attributed to a reporter, as the signatures of the batch are not verified yet.
+            sequencer_state
+                .reporter_metrics
+                .read()
+                .await
+                .oversized_batches
+                .inc();
+            return Ok(HttpResponse::TooManyRequests().body(format!(
+                "Batch of {} reports exceeds max_batch_len",
+                data_feeds.len()
+            )));
+        }
+    }
+
+    let signature_checks = verify_batch_signatures(&sequencer_state, &data_feeds).await?;
     let mut errors_in_batch = Vec::new();
-    for data_feed in data_feeds {
-        let res = process_report(&sequencer_state, data_feed).await;
+    let mut rate_limited = false;
+    for (data_feed, signature_check) in data_feeds.into_iter().zip(signature_checks) {
+        let res = process_report(&sequencer_state, data_feed, signature_check).await;
         if res.status() != StatusCode::OK || res.error().is_some() {
+            rate_limited |= res.status() == StatusCode::TOO_MANY_REQUESTS;
             errors_in_batch.push(format!("{res:?}"));
         }
     }
 
     if errors_in_batch.is_empty() {
         Ok(HttpResponse::Ok().into())
+    } else if rate_limited {
+        Ok(HttpResponse::TooManyRequests().body(format!("{errors_in_batch:?}")))
     } else {
         Ok(HttpResponse::BadRequest().body(format!("{errors_in_batch:?}")))
     }
@@ -376,7 +630,14 @@ pub async fn post_aggregated_consensus_vote(
             }
         };
 
-        let signer_address = reporter.read().await.address;
+        let (signer_address, suspended) = {
+            let reporter = reporter.read().await;
+            (reporter.address, reporter.suspended)
+        };
+        if suspended {
+            warn!("Suspended Reporter sending aggregation batch signature {body:?}!");
+            return Ok(HttpResponse::Forbidden().body("Reporter is suspended".to_string()));
+        }
         let call_data_with_signatures = sequencer_state
             .batches_awaiting_consensus
             .read()
@@ -499,12 +760,12 @@ pub async fn register_feed(
     // get valid id
     // update data feed registry
     let voting_start_system_time = UNIX_EPOCH + Duration::from_millis(voting_start_time_ms as u64);
-    let report_interval_ms = voting_end_time_ms - voting_start_time_ms;
+    let voting_end_system_time = UNIX_EPOCH + Duration::from_millis(voting_end_time_ms as u64);
     let new_feed_metadata = FeedMetaData::new_oneshot(
         name.clone(),
-        report_interval_ms as u64,
         quorum_percentage,
         voting_start_system_time,
+        voting_end_system_time,
     );
     let voting_start_timestamp = match Utc
         .timestamp_millis_opt(voting_start_time_ms as i64)
@@ -574,7 +835,7 @@ pub async fn register_feed(
     if let Err(err) = tokio::task::Builder::new()
         .name(format!("manual_feed_processor_{feed_id}").as_str())
         .spawn_local(async move {
-            feed_slots_processor
+            let result = feed_slots_processor
                 .start_loop(
                     &sequencer_state,
                     &registered_feed_metadata,
@@ -583,7 +844,10 @@ pub async fn register_feed(
                     cmd_recv,
                     Some(cmd_send),
                 )
-                .await
+                .await;
+            // The feed is finalized, its storage can be reused by other event feeds.
+            deregister_oneshot_feed(&sequencer_state, feed_id).await;
+            result
         })
     {
         error!("Failed to spawn manual processor for feed {feed_id} due to {err}!");
@@ -595,24 +859,181 @@ pub async fn register_feed(
     Ok(HttpResponse::Ok().json(response))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedUpdateProofResponse {
+    /// SSZ serialized header of the block, hex encoded.
+    pub block_header: String,
+    pub feed_id: u32,
+    /// The value as posted to the contract, hex encoded.
+    pub feed_data: String,
+    pub leaf_index: usize,
+    /// Hex encoded sibling nodes from the leaf up to `feed_updates_merkle_root`.
+    pub branch: Vec<String>,
+}
+
+#[get("/get_feed_update_proof/{block_height}/{feed_id}")]
+pub async fn get_feed_update_proof(
+    path: web::Path<(u64, u32)>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let (block_height, feed_id) = path.into_inner();
+    let proven = sequencer_state
+        .blockchain_db
+        .read()
+        .await
+        .prove_feed_update(block_height, feed_id)
+        .map_err(|e| ErrorInternalServerError(e.to_string()))?;
+    let Some((mut header, update, proof)) = proven else {
+        return Err(ErrorNotFound(format!(
+            "No update of feed {feed_id} in block {block_height}"
+        )));
+    };
+    let block_header = header
+        .serialize()
+        .map_err(|e| ErrorInternalServerError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(FeedUpdateProofResponse {
+        block_header: hex::encode(block_header),
+        feed_id,
+        feed_data: hex::encode(&update.feed_data[..]),
+        leaf_index: proof.index,
+        branch: proof.branch.iter().map(hex::encode).collect(),
+    }))
+}
+
+/// Interval at which idle update streams get a comment, so that proxies keep them open.
+const STREAM_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Deserialize)]
+pub struct StreamPublishedUpdatesQuery {
+    /// Comma separated ids of the feeds to stream, all feeds if missing.
+    pub feed_ids: Option<String>,
+    /// Block height to resume from, if no `Last-Event-ID` header is sent.
+    pub since_block_height: Option<u64>,
+    #[serde(default)]
+    pub include_transactions: bool,
+}
+
+fn parse_events_filter(query: &StreamPublishedUpdatesQuery) -> Result<EventsFilter, Error> {
+    let feed_ids = match &query.feed_ids {
+        Some(feed_ids) => Some(
+            feed_ids
+                .split(',')
+                .map(|id| id.trim().parse::<u32>())
+                .collect::<Result<HashSet<u32>, _>>()
+                .map_err(|e| ErrorBadRequest(format!("Invalid feed_ids: {e}")))?,
+        ),
+        None => None,
+    };
+    Ok(EventsFilter {
+        feed_ids,
+        include_transactions: query.include_transactions,
+    })
+}
+
+fn sse_frame(event: &SequencedEvent) -> web::Bytes {
+    let data = serde_json::to_string(&event.event).unwrap_or_else(|e| {
+        error!("Could not serialize published event {event:?}: {e}");
+        "{}".to_string()
+    });
+    web::Bytes::from(format!(
+        "id: {}\nevent: {}\ndata: {data}\n\n",
+        event.sequence,
+        event.event.name()
+    ))
+}
+
+/// Server-sent events stream of the aggregated feed updates and, if requested, of the
+/// transactions publishing them. Events are identified by their sequence number, so clients
+/// reconnecting with `Last-Event-ID` get the events they missed, as long as they are still
+/// buffered.
+#[get("/stream_published_updates")]
+pub async fn stream_published_updates(
+    req: HttpRequest,
+    query: web::Query<StreamPublishedUpdatesQuery>,
+    sequencer_state: web::Data<SequencerState>,
+) -> Result<HttpResponse, Error> {
+    let filter = parse_events_filter(&query)?;
+    let resume_from = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse().ok())
+        .map(ResumeFrom::Sequence)
+        .or(query.since_block_height.map(ResumeFrom::BlockHeight));
+    let (missed, recv) = sequencer_state.published_updates.subscribe(resume_from);
+    info!(
+        "New published updates stream, {} subscribers",
+        sequencer_state.published_updates.subscribers_count()
+    );
+
+    let missed: VecDeque<SequencedEvent> = missed.into();
+    let events = futures::stream::unfold(
+        (missed, recv, filter, sequencer_state),
+        |(mut missed, mut recv, filter, sequencer_state)| async move {
+            loop {
+                if let Some(event) = missed.pop_front() {
+                    if filter.matches(&event.event) {
+                        let frame = sse_frame(&event);
+                        return Some((Ok(frame), (missed, recv, filter, sequencer_state)));
+                    }
+                    continue;
+                }
+                let event = match tokio::time::timeout(STREAM_KEEP_ALIVE_INTERVAL, recv.recv())
+                    .await
+                {
+                    Ok(Ok(event)) => event,
+                    Ok(Err(RecvError::Lagged(skipped))) => {
+                        // Closing lets the client resume from the last event it got.
+                        warn!("Published updates stream lagged by {skipped} events; closing it");
+                        return None;
+                    }
+                    Ok(Err(RecvError::Closed)) => return None,
+                    Err(_) if sequencer_state.shutdown.is_requested() => return None,
+                    Err(_) => {
+                        let frame = web::Bytes::from_static(b": keep-alive\n\n");
+                        return Some((Ok(frame), (missed, recv, filter, sequencer_state)));
+                    }
+                };
+                if filter.matches(&event.event) {
+                    let frame = sse_frame(&event);
+                    return Some((
+                        Ok::<_, Error>(frame),
+                        (missed, recv, filter, sequencer_state),
+                    ));
+                }
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events))
+}
+
 pub fn add_main_services(cfg: &mut ServiceConfig) {
     cfg.service(post_report)
         .service(post_reports_batch)
         .service(get_last_published_value_and_time)
-        .service(post_aggregated_consensus_vote);
+        .service(post_aggregated_consensus_vote)
+        .service(get_feed_update_proof)
+        .service(stream_published_updates);
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use crate::feeds::feed_workers::prepare_app_workers;
+    use crate::feeds::published_updates_stream::PublishedEvent;
     use crate::http_handlers::admin::deploy;
-    use crate::providers::provider::init_shared_rpc_providers;
+    use crate::providers::provider::{init_shared_rpc_providers, new_provider_metrics};
     use actix_web::{test, App};
     use alloy::node_bindings::Anvil;
     use alloy::primitives::Address;
     use blocksense_config::AllFeedsConfig;
     use blocksense_config::{get_test_config_with_no_providers, test_feed_config};
+    use blocksense_feed_registry::types::ReportSigningScheme;
 
     use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
     use blocksense_config::{get_test_config_with_single_provider, SequencerConfig};
@@ -637,8 +1058,9 @@ pub mod tests {
         let feeds_config = AllFeedsConfig {
             feeds: vec![feed_1_config],
         };
+        let provider_metrics = new_provider_metrics(metrics_prefix);
         let providers =
-            init_shared_rpc_providers(&sequencer_config, metrics_prefix, &feeds_config).await;
+            init_shared_rpc_providers(&sequencer_config, &provider_metrics, &feeds_config).await;
         let (vote_send, mut _vote_recv) = mpsc::unbounded_channel();
         let (
             feeds_management_cmd_to_block_creator_send,
@@ -648,18 +1070,22 @@ pub mod tests {
             mpsc::unbounded_channel();
         let (aggregate_batch_sig_send, _aggregate_batch_sig_recv) = mpsc::unbounded_channel();
 
-        let sequencer_state = web::Data::new(SequencerState::new(
-            feeds_config,
-            providers,
-            log_handle,
-            &sequencer_config,
-            metrics_prefix,
-            None,
-            vote_send,
-            feeds_management_cmd_to_block_creator_send,
-            feeds_slots_manager_cmd_send,
-            aggregate_batch_sig_send,
-        ));
+        let sequencer_state = web::Data::new(
+            SequencerState::new(
+                feeds_config,
+                providers,
+                provider_metrics,
+                log_handle,
+                &sequencer_config,
+                metrics_prefix,
+                None,
+                vote_send,
+                feeds_management_cmd_to_block_creator_send,
+                feeds_slots_manager_cmd_send,
+                aggregate_batch_sig_send,
+            )
+            .unwrap(),
+        );
 
         let app = test::init_service(
             App::new()
@@ -677,7 +1103,14 @@ pub mod tests {
         const SECRET_KEY: &str = "536d1f9d97166eba5ff0efb8cc8dbeb856fb13d2d126ed1efc761e9955014003";
         const REPORT_VAL: f64 = 80000.8;
         let result = Ok(FeedType::Numerical(REPORT_VAL));
-        let signature = generate_signature(SECRET_KEY, FEED_ID, timestamp, &result);
+        let signature = generate_signature(
+            SECRET_KEY,
+            ReportSigningScheme::V0,
+            "",
+            FEED_ID,
+            timestamp,
+            &result,
+        );
 
         let payload = DataFeedPayload {
             payload_metadata: PayloadMetaData {
@@ -687,6 +1120,7 @@ pub mod tests {
                 signature: JsonSerializableSignature {
                     sig: signature.unwrap(),
                 },
+                signing_scheme: ReportSigningScheme::V0,
             },
             result,
         };
@@ -876,7 +1310,14 @@ pub mod tests {
         const SECRET_KEY: &str = "536d1f9d97166eba5ff0efb8cc8dbeb856fb13d2d126ed1efc761e9955014003";
         const REPORT_VAL: f64 = 80000.8;
         let result = Ok(FeedType::Numerical(REPORT_VAL));
-        let signature = generate_signature(SECRET_KEY, FEED_ID, timestamp, &result);
+        let signature = generate_signature(
+            SECRET_KEY,
+            ReportSigningScheme::V0,
+            "",
+            FEED_ID,
+            timestamp,
+            &result,
+        );
 
         let payload = DataFeedPayload {
             payload_metadata: PayloadMetaData {
@@ -886,6 +1327,7 @@ pub mod tests {
                 signature: JsonSerializableSignature {
                     sig: signature.unwrap(),
                 },
+                signing_scheme: ReportSigningScheme::V0,
             },
             result,
         };
@@ -1260,4 +1702,423 @@ pub mod tests {
         );
         assert!(last_values[0].error.is_none())
     }
+
+    #[actix_web::test]
+    async fn test_get_feed_update_proof() {
+        use blocksense_blockchain_data_model::proofs::{verify_feed_update_proof, FeedUpdateProof};
+        use blocksense_blockchain_data_model::{AssetFeedUpdate, BlockHeader};
+
+        let sequencer_config = get_test_config_with_no_providers();
+        let feeds_config = AllFeedsConfig { feeds: vec![] };
+        let (
+            sequencer_state,
+            _vote_recv,
+            _feeds_management_cmd_to_block_creator_recv,
+            _feeds_slots_manager_cmd_recv,
+            _aggregate_batch_sig_recv,
+        ) = create_sequencer_state_from_sequencer_config(
+            sequencer_config,
+            "test_get_feed_update_proof",
+            feeds_config,
+        )
+        .await;
+        {
+            let mut blockchain_db = sequencer_state.blockchain_db.write().await;
+            let updates = vec![
+                AssetFeedUpdate::new(1, vec![1; 32]).unwrap(),
+                AssetFeedUpdate::new(7, vec![7; 32]).unwrap(),
+            ];
+            let (header, feed_actions, feed_updates) = blockchain_db
+                .create_new_block(1, 3, vec![], vec![], vec![], updates)
+                .unwrap();
+            blockchain_db
+                .add_next_block(header, feed_actions, feed_updates)
+                .unwrap();
+        }
+
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_main_services),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/get_feed_update_proof/3/7")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let response: FeedUpdateProofResponse = test::read_body_json(resp).await;
+
+        let header =
+            BlockHeader::deserialize(&hex::decode(&response.block_header).unwrap()).unwrap();
+        let update =
+            AssetFeedUpdate::new(response.feed_id, hex::decode(&response.feed_data).unwrap())
+                .unwrap();
+        let proof = FeedUpdateProof {
+            index: response.leaf_index,
+            branch: response
+                .branch
+                .iter()
+                .map(|node| hex::decode(node).unwrap().try_into().unwrap())
+                .collect(),
+        };
+        assert_eq!(update.feed_data.to_vec(), vec![7; 32]);
+        assert!(verify_feed_update_proof(&header, &update, &proof).unwrap());
+
+        for uri in ["/get_feed_update_proof/3/2", "/get_feed_update_proof/4/7"] {
+            let req = test::TestRequest::get().uri(uri).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 404);
+        }
+    }
+
+    const REPORTER_SECRET_KEY: &str =
+        "536d1f9d97166eba5ff0efb8cc8dbeb856fb13d2d126ed1efc761e9955014003";
+
+    /// Creates the sequencer state of `sequencer_config` with reporter 0 added to it, whose
+    /// key is `REPORTER_SECRET_KEY`.
+    async fn state_with_reporter(
+        mut sequencer_config: SequencerConfig,
+        metrics_prefix: &str,
+        feeds_config: AllFeedsConfig,
+    ) -> web::Data<SequencerState> {
+        use blocksense_config::Reporter;
+
+        sequencer_config.reporters.push(Reporter {
+            id: 0,
+            pub_key: "ea30af86b930d539c55677b05b4a5dad9fce1f758ba09d152d19a7d6940f8d8a8a8fb9f90d38a19e988d721cddaee4567d2e".to_string(),
+            address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            suspended: false,
+        });
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            sequencer_config,
+            metrics_prefix,
+            feeds_config,
+        )
+        .await;
+        sequencer_state
+    }
+
+    /// A report of reporter 0 for the current time, signed with `signing_scheme`.
+    fn signed_report(
+        signing_scheme: ReportSigningScheme,
+        deployment_id: &str,
+        feed_id: u32,
+        value: f64,
+    ) -> DataFeedPayload {
+        let timestamp = current_unix_time();
+        let result = Ok(FeedType::Numerical(value));
+        let signature = generate_signature(
+            REPORTER_SECRET_KEY,
+            signing_scheme,
+            deployment_id,
+            &feed_id.to_string(),
+            timestamp,
+            &result,
+        )
+        .unwrap();
+        DataFeedPayload {
+            payload_metadata: PayloadMetaData {
+                reporter_id: 0,
+                feed_id: feed_id.to_string(),
+                timestamp,
+                signature: JsonSerializableSignature { sig: signature },
+                signing_scheme,
+            },
+            result,
+        }
+    }
+
+    #[actix_web::test]
+    async fn post_report_over_reports_limits_fails_with_429() {
+        use blocksense_config::{RateLimit, ReportsLimits};
+        use std::net::SocketAddr;
+
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.reports_limits = Some(ReportsLimits {
+            per_reporter: Some(RateLimit {
+                per_sec: 0.001,
+                burst: 3,
+            }),
+            per_ip: Some(RateLimit {
+                per_sec: 0.001,
+                burst: 5,
+            }),
+            max_batch_len: Some(2),
+            max_revotes_per_slot: Some(1),
+        });
+        let sequencer_state = state_with_reporter(
+            sequencer_config,
+            "post_report_over_reports_limits_fails_with_429",
+            AllFeedsConfig {
+                feeds: vec![test_feed_config(1, 0)],
+            },
+        )
+        .await;
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_main_services),
+        )
+        .await;
+
+        let report = |value: f64| signed_report(ReportSigningScheme::V0, "", 1, value);
+        let ip: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+        let post_report = |value: f64| {
+            test::TestRequest::post()
+                .uri("/post_report")
+                .peer_addr(ip)
+                .set_payload(serde_json::to_string(&report(value)).unwrap())
+                .to_request()
+        };
+
+        // A vote and a revote are accepted, the next revote exceeds max_revotes_per_slot.
+        for (value, status) in [(1.0, 200), (2.0, 200), (3.0, 429)] {
+            let resp = test::call_service(&app, post_report(value)).await;
+            assert_eq!(resp.status(), status);
+        }
+        // The reporter used up its burst of 3 reports, and then the IP its burst of 5 requests.
+        for value in [4.0, 5.0, 6.0] {
+            let resp = test::call_service(&app, post_report(value)).await;
+            assert_eq!(resp.status(), 429);
+        }
+
+        let req = test::TestRequest::post()
+            .uri("/post_reports_batch")
+            .peer_addr("10.0.0.2:40000".parse().unwrap())
+            .set_payload(serde_json::to_string(&[report(7.0), report(8.0), report(9.0)]).unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 429);
+
+        let reporter_metrics = sequencer_state.reporter_metrics.read().await;
+        assert_eq!(
+            reporter_metrics
+                .revotes_over_limit_per_feed
+                .with_label_values(&["0", "1"])
+                .get(),
+            1
+        );
+        assert_eq!(
+            reporter_metrics
+                .rate_limited_reports
+                .with_label_values(&["0"])
+                .get(),
+            2
+        );
+        assert_eq!(reporter_metrics.rate_limited_ip_requests.get(), 1);
+        assert_eq!(reporter_metrics.oversized_batches.get(), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_verify_batch_signatures() {
+        let sequencer_state = state_with_reporter(
+            get_test_config_with_no_providers(),
+            "test_verify_batch_signatures",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+
+        let mut reports: Vec<DataFeedPayload> = (1..=50)
+            .map(|feed_id| signed_report(ReportSigningScheme::V0, "", feed_id, 1.5))
+            .collect();
+        let checks = verify_batch_signatures(&sequencer_state, &reports)
+            .await
+            .unwrap();
+        assert!(checks.iter().all(|check| *check == SignatureCheck::Valid));
+
+        // A value that was not signed, and a report of an unknown reporter.
+        reports[7].result = Ok(FeedType::Numerical(2.5));
+        reports[9].payload_metadata.reporter_id = 5;
+        let checks = verify_batch_signatures(&sequencer_state, &reports)
+            .await
+            .unwrap();
+        assert_eq!(checks[7], SignatureCheck::Invalid);
+        assert_eq!(checks[9], SignatureCheck::Pending);
+        assert_eq!(
+            checks
+                .iter()
+                .filter(|check| **check == SignatureCheck::Valid)
+                .count(),
+            48
+        );
+    }
+
+    #[actix_web::test]
+    async fn post_encoded_reports_batch() {
+        use blocksense_feed_registry::reports_encoding::encode_reports_batch;
+
+        let sequencer_state = state_with_reporter(
+            get_test_config_with_no_providers(),
+            "post_encoded_reports_batch",
+            AllFeedsConfig {
+                feeds: vec![test_feed_config(1, 0)],
+            },
+        )
+        .await;
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_main_services),
+        )
+        .await;
+
+        let report = signed_report(ReportSigningScheme::V0, "", 1, 80000.8);
+        let post_batch = |body: Vec<u8>| {
+            test::TestRequest::post()
+                .uri("/post_reports_batch")
+                .insert_header((header::CONTENT_TYPE, REPORTS_BATCH_CONTENT_TYPE))
+                .set_payload(body)
+                .to_request()
+        };
+
+        let encoded = encode_reports_batch(&[report]).unwrap();
+        let resp = test::call_service(&app, post_batch(encoded.clone())).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            sequencer_state.reports.read().await.revotes(1, 0).await,
+            Some(0)
+        );
+
+        let resp =
+            test::call_service(&app, post_batch(encoded[..encoded.len() / 2].to_vec())).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn post_report_with_signing_scheme() {
+        use blocksense_config::ReportSigning;
+
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.report_signing = Some(ReportSigning {
+            deployment_id: "test-deployment".to_string(),
+            accepted_schemes: vec![ReportSigningScheme::V1],
+        });
+        let sequencer_state = state_with_reporter(
+            sequencer_config,
+            "post_report_with_signing_scheme",
+            AllFeedsConfig {
+                feeds: vec![test_feed_config(1, 0)],
+            },
+        )
+        .await;
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_main_services),
+        )
+        .await;
+
+        let post_report = |signing_scheme: ReportSigningScheme, deployment_id: &str| {
+            let report = signed_report(signing_scheme, deployment_id, 1, 80000.8);
+            test::TestRequest::post()
+                .uri("/post_report")
+                .set_payload(serde_json::to_string(&report).unwrap())
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, post_report(ReportSigningScheme::V0, "")).await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(
+            &app,
+            post_report(ReportSigningScheme::V1, "other-deployment"),
+        )
+        .await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(
+            &app,
+            post_report(ReportSigningScheme::V1, "test-deployment"),
+        )
+        .await;
+        assert_eq!(resp.status(), 200);
+
+        let reporter_metrics = sequencer_state.reporter_metrics.read().await;
+        assert_eq!(
+            reporter_metrics
+                .unaccepted_signing_scheme
+                .with_label_values(&["0"])
+                .get(),
+            1
+        );
+        assert_eq!(
+            reporter_metrics
+                .non_valid_signature
+                .with_label_values(&["0"])
+                .get(),
+            1
+        );
+    }
+
+    async fn next_sse_frame<B>(body: &mut std::pin::Pin<&mut B>) -> String
+    where
+        B: actix_web::body::MessageBody,
+        B::Error: std::fmt::Debug,
+    {
+        let frame = futures::future::poll_fn(|cx| body.as_mut().poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        String::from_utf8(frame.to_vec()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_stream_published_updates() {
+        use blocksense_data_feeds::feeds_processing::VotedFeedUpdate;
+
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_stream_published_updates",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+        let update = |feed_id: u32| VotedFeedUpdate {
+            feed_id,
+            value: FeedType::Numerical(feed_id as f64),
+            end_slot_timestamp: 0,
+        };
+        let published_updates = &sequencer_state.published_updates;
+        published_updates.publish_feed_updates(1, &[update(1), update(2)]);
+        published_updates.publish_feed_updates(2, &[update(1)]);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(sequencer_state.clone())
+                .configure(add_main_services),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/stream_published_updates?feed_ids=x")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri("/stream_published_updates?feed_ids=1&include_transactions=true")
+            .insert_header(("Last-Event-ID", "1"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let mut body = std::pin::pin!(resp.into_body());
+
+        // The update of feed 1 in block 2, the third event, was missed.
+        let frame = next_sse_frame(&mut body).await;
+        assert!(frame.starts_with("id: 3\nevent: feed_update\n"));
+        assert!(frame.contains("\"feed_id\":1"));
+
+        published_updates.publish_feed_updates(3, &[update(2), update(1)]);
+        published_updates.publish(PublishedEvent::Transaction {
+            block_height: 3,
+            network: "ETH".to_string(),
+            tx_hash: "0x01".to_string(),
+            feed_ids: vec![1, 2],
+        });
+        let frame = next_sse_frame(&mut body).await;
+        assert!(frame.starts_with("id: 5\nevent: feed_update\n"));
+        assert!(frame.contains("\"feed_id\":1"));
+        let frame = next_sse_frame(&mut body).await;
+        assert!(frame.starts_with("id: 6\nevent: transaction\n"));
+        assert!(frame.contains("\"tx_hash\":\"0x01\""));
+    }
 }
@@ -1,4 +1,5 @@
 pub mod admin;
+pub mod admin_auth;
 pub mod data_feeds;
 
 const MAX_SIZE: usize = 524_288; // max payload size is 512kb
@@ -1,9 +1,11 @@
 pub mod aggregate_batch_consensus_processor;
 pub mod block_creator;
 pub mod blocks_reader;
+pub mod chain_verifier;
 pub mod feeds;
 pub mod http_handlers;
 pub mod metrics_collector;
 pub mod providers;
 pub mod reporters;
 pub mod sequencer_state;
+pub mod shutdown;
@@ -15,6 +15,7 @@ use std::{collections::HashMap, collections::HashSet, mem, sync::Arc};
 use tokio::{sync::Mutex, sync::RwLock, time::Duration};
 
 use crate::{
+    feeds::published_updates_stream::PublishedEvent,
     providers::provider::{
         parse_eth_address, ProviderStatus, RpcProvider, SharedRpcProviders,
         EVENT_FEED_CONTRACT_NAME, PRICE_FEED_CONTRACT_NAME,
@@ -74,10 +75,16 @@ fn legacy_serialize_updates(
             }
         };
 
-        let (key, val) = update.encode(
+        let (key, val) = match update.encode(
             digits_in_fraction as usize,
             update.end_slot_timestamp as u64,
-        );
+        ) {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                error!("Skipping update in legacy batch to network `{net}`: {e}");
+                continue;
+            }
+        };
 
         num_reported_feeds += 1;
         result += to_hex_string(key, None).as_str();
@@ -123,7 +130,7 @@ pub async fn get_serialized_updates_for_network(
     debug!("Acquired a read lock on provider config for `{net}`");
     filter_allowed_feeds(net, updates, &provider_settings.allow_feeds);
     provider.peg_stable_coins_to_value(updates);
-    provider.apply_publish_criteria(updates);
+    provider.apply_publish_criteria(updates, &*feeds_config.read().await);
 
     // Don’t post to Smart Contract if we have 0 updates
     if updates.updates.is_empty() {
@@ -187,6 +194,8 @@ pub async fn get_serialized_updates_for_network(
     Ok(serialized_updates)
 }
 
+/// Returns the status of the transaction, the ids of the feeds it updates and its hash once
+/// it is confirmed.
 #[allow(clippy::too_many_arguments)]
 pub async fn eth_batch_send_to_contract(
     net: String,
@@ -198,7 +207,7 @@ pub async fn eth_batch_send_to_contract(
     transaction_retry_timeout_secs: u64,
     transaction_retries_count_before_give_up: u64,
     retry_fee_increment_fraction: f64,
-) -> Result<(String, Vec<u32>)> {
+) -> Result<(String, Vec<u32>, Option<String>)> {
     let mut feeds_rounds = HashMap::new();
     let serialized_updates = get_serialized_updates_for_network(
         net.as_str(),
@@ -212,7 +221,11 @@ pub async fn eth_batch_send_to_contract(
 
     if updates.updates.is_empty() {
         info!("Network `{net}` posting to smart contract skipped because it received 0 updates");
-        return Ok((format!("No updates to send for network {net}"), Vec::new()));
+        return Ok((
+            format!("No updates to send for network {net}"),
+            Vec::new(),
+            None,
+        ));
     }
 
     debug!(
@@ -437,7 +450,7 @@ pub async fn eth_batch_send_to_contract(
         debug!("matched receipt_result");
 
         if timed_out_count > transaction_retries_count_before_give_up {
-            return Ok(("timeout".to_string(), feeds_to_update_ids));
+            return Ok(("timeout".to_string(), feeds_to_update_ids, None));
         }
     }
 
@@ -474,7 +487,11 @@ pub async fn eth_batch_send_to_contract(
     drop(provider);
     debug!("Released a read/write lock on provider state for network `{net}`");
 
-    Ok((receipt.status().to_string(), feeds_to_update_ids))
+    Ok((
+        receipt.status().to_string(),
+        feeds_to_update_ids,
+        Some(tx_hash.to_string()),
+    ))
 }
 
 pub async fn eth_batch_send_to_all_contracts(
@@ -487,6 +504,7 @@ pub async fn eth_batch_send_to_all_contracts(
     debug!("updates: {:?}", updates.updates);
 
     let collected_futures = FuturesUnordered::new();
+    let block_height = updates.block_height;
 
     // drop all the locks as soon as we are done using the data
     {
@@ -585,10 +603,20 @@ pub async fn eth_batch_send_to_all_contracts(
     for v in result {
         match v {
             Ok((result, net, provider)) => match result.await {
-                Ok((status, updated_feeds)) => {
+                Ok((status, updated_feeds, tx_hash)) => {
                     all_results += &format!("result from network {net}: Ok -> status: {status}");
                     if status == "true" {
                         all_results += &format!(", updated_feeds: {updated_feeds:?}");
+                        if let Some(tx_hash) = tx_hash {
+                            sequencer_state.published_updates.publish(
+                                PublishedEvent::Transaction {
+                                    block_height,
+                                    network: net.clone(),
+                                    tx_hash,
+                                    feed_ids: updated_feeds.clone(),
+                                },
+                            );
+                        }
                         increment_feeds_round_metrics(
                             &updated_feeds,
                             Some(sequencer_state.feeds_metrics.clone()),
@@ -721,7 +749,9 @@ async fn increment_feeds_round_metrics(
 mod tests {
     use super::*;
 
-    use crate::providers::provider::{init_shared_rpc_providers, MULTICALL_CONTRACT_NAME};
+    use crate::providers::provider::{
+        init_shared_rpc_providers, new_provider_metrics, MULTICALL_CONTRACT_NAME,
+    };
     use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
     use alloy::rpc::types::eth::TransactionInput;
     use alloy::{
@@ -737,6 +767,7 @@ mod tests {
     use blocksense_data_feeds::feeds_processing::VotedFeedUpdate;
     use blocksense_feed_registry::registry::HistoryEntry;
     use blocksense_feed_registry::types::Repeatability::Oneshot;
+    use blocksense_registry::config::NumericalEncoding;
     use blocksense_utils::test_env::get_test_private_key_path;
     use regex::Regex;
     use ringbuf::traits::Consumer;
@@ -779,7 +810,7 @@ mod tests {
         // give some time for cleanup env variables
         let providers = init_shared_rpc_providers(
             &cfg,
-            Some("test_deploy_contract_returns_valid_address_"),
+            &new_provider_metrics(Some("test_deploy_contract_returns_valid_address_")),
             &feeds_config,
         )
         .await;
@@ -833,7 +864,7 @@ mod tests {
         };
         let providers = init_shared_rpc_providers(
             &cfg,
-            Some("test_eth_batch_send_to_oneshot_contract_"),
+            &new_provider_metrics(Some("test_eth_batch_send_to_oneshot_contract_")),
             &feeds_config,
         )
         .await;
@@ -896,6 +927,7 @@ mod tests {
             end_slot_timestamp,
             blocksense_feed_registry::types::FeedType::Numerical(0.0f64),
             18,
+            NumericalEncoding::Unsigned,
         )
         .unwrap();
         let updates_oneshot = BatchedAggegratesToSend {
@@ -1040,6 +1072,7 @@ mod tests {
                 end_of_timeslot,
                 FeedType::Text("".to_string()),
                 18,
+                NumericalEncoding::Unsigned,
             )
             .unwrap()],
         };
@@ -1507,7 +1540,7 @@ mod tests {
         };
         let providers = init_shared_rpc_providers(
             &sequencer_config,
-            Some("peg_stable_coin_updates_"),
+            &new_provider_metrics(Some("peg_stable_coin_updates_")),
             &feeds_config,
         )
         .await;
@@ -1528,7 +1561,7 @@ mod tests {
         assert_eq!(updates.updates[3].value, FeedType::Numerical(1.101f64));
         assert_eq!(updates.updates[4].value, FeedType::Numerical(1.0f64));
 
-        provider.apply_publish_criteria(&mut updates);
+        provider.apply_publish_criteria(&mut updates, &HashMap::from([(feed.id, feed.clone())]));
         assert_eq!(updates.updates.len(), 3);
         assert_eq!(updates.updates[2].value, FeedType::Numerical(1.101f64));
     }
@@ -1561,7 +1594,7 @@ mod tests {
         };
         let providers = init_shared_rpc_providers(
             &sequencer_config,
-            Some("peg_stable_coin_updates_disabled"),
+            &new_provider_metrics(Some("peg_stable_coin_updates_disabled")),
             &feeds_config,
         )
         .await;
@@ -1582,7 +1615,7 @@ mod tests {
         assert_eq!(updates.updates[3].value, FeedType::Numerical(1.101f64));
         assert_eq!(updates.updates[4].value, FeedType::Numerical(0.991f64));
 
-        provider.apply_publish_criteria(&mut updates);
+        provider.apply_publish_criteria(&mut updates, &HashMap::from([(feed.id, feed.clone())]));
         assert_eq!(updates.updates.len(), 5);
         assert_eq!(updates.updates[3].value, FeedType::Numerical(1.101f64));
     }
@@ -1,3 +1,4 @@
 pub mod eth_send_utils;
 pub mod multicall;
 pub mod provider;
+pub mod providers_management;
@@ -23,9 +23,11 @@ use blocksense_config::{AllFeedsConfig, PublishCriteria, SequencerConfig};
 use blocksense_data_feeds::feeds_processing::{
     BatchedAggegratesToSend, PublishedFeedUpdate, PublishedFeedUpdateError, VotedFeedUpdate,
 };
+use blocksense_feed_registry::market_hours::MarketCalendars;
 use blocksense_feed_registry::registry::{FeedAggregateHistory, HistoryEntry};
 use blocksense_feed_registry::types::FeedType;
 use blocksense_metrics::{metrics::ProviderMetrics, process_provider_getter};
+use blocksense_registry::config::{FeedConfig, NumericalEncoding};
 use eyre::{eyre, Result};
 use paste::paste;
 use ringbuf::traits::{Consumer, Observer, RingBuffer};
@@ -84,7 +86,8 @@ pub struct RpcProvider {
     pub impersonated_anvil_account: Option<Address>,
     pub history: FeedAggregateHistory,
     pub publishing_criteria: HashMap<u32, PublishCriteria>,
-    pub feeds_variants: HashMap<u32, (FeedType, usize)>,
+    pub feeds_variants: HashMap<u32, (FeedType, usize, NumericalEncoding)>,
+    pub market_calendars: Arc<MarketCalendars>,
     pub contracts: Vec<Contract>,
     pub rpc_url: Url,
     pub round_counters: RoundCounters,
@@ -100,53 +103,41 @@ pub enum ProviderStatus {
 
 pub type SharedRpcProviders = Arc<RwLock<HashMap<String, Arc<Mutex<RpcProvider>>>>>;
 
+/// Metrics shared by all providers, including the ones added at runtime. They can be
+/// registered only once per prefix, so they are created once at startup.
+pub fn new_provider_metrics(prefix: Option<&str>) -> Arc<RwLock<ProviderMetrics>> {
+    Arc::new(RwLock::new(
+        ProviderMetrics::new(prefix.unwrap_or("")).expect("Failed to allocate ProviderMetrics"),
+    ))
+}
+
 pub async fn init_shared_rpc_providers(
     conf: &SequencerConfig,
-    prefix: Option<&str>,
+    provider_metrics: &Arc<RwLock<ProviderMetrics>>,
     feeds_config: &AllFeedsConfig,
 ) -> SharedRpcProviders {
-    let prefix = prefix.unwrap_or("");
     Arc::new(RwLock::new(
-        get_rpc_providers(conf, prefix, feeds_config).await,
+        get_rpc_providers(conf, provider_metrics, feeds_config).await,
     ))
 }
 
 async fn get_rpc_providers(
     conf: &SequencerConfig,
-    prefix: &str,
+    provider_metrics: &Arc<RwLock<ProviderMetrics>>,
     feeds_config: &AllFeedsConfig,
 ) -> HashMap<String, Arc<Mutex<RpcProvider>>> {
     let mut providers: HashMap<String, Arc<Mutex<RpcProvider>>> = HashMap::new();
-
-    let provider_metrics = Arc::new(RwLock::new(
-        ProviderMetrics::new(prefix).expect("Failed to allocate ProviderMetrics"),
-    ));
+    let market_calendars = Arc::new(MarketCalendars::new(&conf.market_calendars));
 
     for (net, p) in &conf.providers {
-        let rpc_url: Url = p
-            .url
-            .parse()
-            .unwrap_or_else(|_| panic!("Not a valid url provided for {net}!"));
-        let priv_key_path = &p.private_key_path;
-        let priv_key = fs::read_to_string(priv_key_path.clone()).unwrap_or_else(|_| {
-            panic!(
-                "Failed to read private key for {} from {}",
-                net, priv_key_path
-            )
-        });
-        let signer: PrivateKeySigner = priv_key
-            .trim()
-            .parse()
-            .unwrap_or_else(|_| panic!("Incorrect private key specified {}.", priv_key));
-
-        let rpc_provider = RpcProvider::new(
+        let rpc_provider = create_rpc_provider(
             net.as_str(),
-            rpc_url,
-            &signer,
             p,
-            &provider_metrics,
+            provider_metrics,
             feeds_config,
-        );
+            &market_calendars,
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
         rpc_provider
             .log_if_contract_exists(PRICE_FEED_CONTRACT_NAME)
             .await;
@@ -168,6 +159,37 @@ async fn get_rpc_providers(
     providers
 }
 
+/// Builds the provider of network `net` from its config, without contacting the network.
+pub fn create_rpc_provider(
+    net: &str,
+    p: &blocksense_config::Provider,
+    provider_metrics: &Arc<RwLock<ProviderMetrics>>,
+    feeds_config: &AllFeedsConfig,
+    market_calendars: &Arc<MarketCalendars>,
+) -> Result<RpcProvider> {
+    let rpc_url: Url = p
+        .url
+        .parse()
+        .map_err(|_| eyre!("Not a valid url provided for {net}!"))?;
+    let priv_key_path = &p.private_key_path;
+    let priv_key = fs::read_to_string(priv_key_path)
+        .map_err(|_| eyre!("Failed to read private key for {net} from {priv_key_path}"))?;
+    let signer: PrivateKeySigner = priv_key
+        .trim()
+        .parse()
+        .map_err(|_| eyre!("Incorrect private key specified {priv_key}."))?;
+
+    Ok(RpcProvider::new(
+        net,
+        rpc_url,
+        &signer,
+        p,
+        provider_metrics,
+        feeds_config,
+        market_calendars,
+    ))
+}
+
 impl RpcProvider {
     pub fn new(
         network: &str,
@@ -176,6 +198,7 @@ impl RpcProvider {
         p: &blocksense_config::Provider,
         provider_metrics: &Arc<tokio::sync::RwLock<ProviderMetrics>>,
         feeds_config: &AllFeedsConfig,
+        market_calendars: &Arc<MarketCalendars>,
     ) -> RpcProvider {
         let provider = ProviderBuilder::new()
             .wallet(EthereumWallet::from(signer.clone()))
@@ -187,13 +210,19 @@ impl RpcProvider {
             .and_then(|x| parse_eth_address(x.as_str()));
         let (history, publishing_criteria) = RpcProvider::prepare_history(p);
         let contracts = RpcProvider::prepare_contracts(p);
-        let mut feeds_variants: HashMap<u32, (FeedType, usize)> = HashMap::new();
+        let mut feeds_variants: HashMap<u32, (FeedType, usize, NumericalEncoding)> = HashMap::new();
         for f in feeds_config.feeds.iter() {
             debug!("Registering feed for network; feed={f:?}; network={network}");
-            match FeedType::get_variant_from_string(f.value_type.as_str()) {
+            match FeedType::get_variant_from_config(f) {
                 Ok(variant) => {
-                    feeds_variants
-                        .insert(f.id, (variant, f.additional_feed_info.decimals as usize));
+                    feeds_variants.insert(
+                        f.id,
+                        (
+                            variant,
+                            f.additional_feed_info.decimals as usize,
+                            f.value_encoding,
+                        ),
+                    );
                 }
                 _ => {
                     error!("Unknown feed value variant = {}", f.value_type);
@@ -214,6 +243,7 @@ impl RpcProvider {
             history,
             publishing_criteria,
             feeds_variants,
+            market_calendars: market_calendars.clone(),
             contracts,
             rpc_url,
             round_counters: HashMap::new(),
@@ -303,7 +333,13 @@ impl RpcProvider {
         }
     }
 
-    pub fn apply_publish_criteria(&self, updates: &mut BatchedAggegratesToSend) {
+    /// Drops the updates that the publishing criteria skip. The market calendars are looked up
+    /// in `feeds_config`, so that they also apply to the feeds registered at runtime.
+    pub fn apply_publish_criteria(
+        &self,
+        updates: &mut BatchedAggegratesToSend,
+        feeds_config: &HashMap<u32, FeedConfig>,
+    ) {
         let mut res = updates
             .updates
             .iter()
@@ -311,7 +347,16 @@ impl RpcProvider {
                 self.publishing_criteria
                     .get(&update.feed_id)
                     .is_none_or(|criteria| {
-                        !update.should_skip(criteria, &self.history).should_skip()
+                        !update
+                            .should_skip_with_market_hours(
+                                criteria,
+                                &self.history,
+                                feeds_config
+                                    .get(&update.feed_id)
+                                    .and_then(|feed| self.market_calendars.for_feed(feed))
+                                    .as_deref(),
+                            )
+                            .should_skip()
                     })
             })
             .cloned()
@@ -389,14 +434,17 @@ impl RpcProvider {
             .collect();
         let mut digits: Vec<usize> = vec![];
         let mut variants: Vec<FeedType> = vec![];
+        let mut encodings: Vec<NumericalEncoding> = vec![];
         for feed_id in feed_ids.iter() {
-            let Some((variant, digits_in_fraction)) = self.feeds_variants.get(feed_id) else {
+            let Some((variant, digits_in_fraction, encoding)) = self.feeds_variants.get(feed_id)
+            else {
                 return Err(eyre!(
                     "Unknown variant and number of digits for feed with id = {feed_id}"
                 ));
             };
             digits.push(*digits_in_fraction);
             variants.push(variant.clone());
+            encodings.push(*encoding);
         }
 
         let aggregate_return = contract.aggregate(calldata).call().await?;
@@ -409,6 +457,7 @@ impl RpcProvider {
                     feed_ids[count],
                     variants[count].clone(),
                     digits[count],
+                    encodings[count],
                     &data.0,
                 )
             })
@@ -446,7 +495,8 @@ impl RpcProvider {
                 }
             })
             .collect();
-        let Some((variant, digits_in_fraction)) = self.feeds_variants.get(&feed_id) else {
+        let Some((variant, digits_in_fraction, encoding)) = self.feeds_variants.get(&feed_id)
+        else {
             return Err(eyre!(
                 "Unknown variant and number of digits for feed with id = {feed_id}"
             ));
@@ -462,6 +512,7 @@ impl RpcProvider {
                     updates[count],
                     variant.clone(),
                     *digits_in_fraction,
+                    *encoding,
                     &data.0,
                 )
             })
@@ -644,6 +695,32 @@ impl RpcProvider {
             Ok(0)
         }
     }
+
+    /// Reconciles the history of `feed_id` restored from disk with the on-chain state.
+    /// Entries newer than the latest update on chain were never published (or the contract
+    /// was redeployed), so they are dropped; updates published while the sequencer was down
+    /// are then fetched from the chain.
+    pub async fn reconcile_history_with_chain(
+        &mut self,
+        feed_id: u32,
+        limit_entries: u32,
+    ) -> Result<u32> {
+        let latest = self.get_latest_values(&[feed_id]).await?;
+        if let Some(latest) = latest.first() {
+            let num_updates = match latest {
+                Ok(latest) => latest.num_updates,
+                Err(latest) => latest.num_updates,
+            };
+            let discarded = self.history.discard_after(feed_id, num_updates);
+            if discarded > 0 {
+                warn!(
+                    "Discarded {discarded} restored history entries for feed id {feed_id} in network {} that are ahead of the chain",
+                    self.network
+                );
+            }
+        }
+        self.load_history_from_chain(feed_id, limit_entries).await
+    }
 }
 // pub fn print_type<T>(_: &T) {
 //     println!("{:?}", std::any::type_name::<T>());
@@ -659,7 +736,7 @@ mod tests {
     use alloy_primitives::address;
     use blocksense_utils::test_env::get_test_private_key_path;
 
-    use crate::providers::provider::get_rpc_providers;
+    use crate::providers::provider::{get_rpc_providers, new_provider_metrics};
     use alloy::consensus::Transaction;
     use alloy::providers::Provider as AlloyProvider;
     use blocksense_config::get_test_config_with_single_provider;
@@ -674,7 +751,12 @@ mod tests {
         let cfg =
             get_test_config_with_single_provider(network, key_path.as_path(), &anvil.endpoint());
         let feeds_config = AllFeedsConfig { feeds: vec![] };
-        let providers = get_rpc_providers(&cfg, "basic_test_provider_", &feeds_config).await;
+        let providers = get_rpc_providers(
+            &cfg,
+            &new_provider_metrics(Some("basic_test_provider_")),
+            &feeds_config,
+        )
+        .await;
         let provider = &providers.get(network).unwrap().lock().await.provider;
 
         let alice = anvil.addresses()[7];
@@ -718,7 +800,12 @@ mod tests {
             "http://localhost:8545",
         );
         let feeds_config = AllFeedsConfig { feeds: vec![] };
-        let providers = get_rpc_providers(&cfg, "test_get_wallet_success_", &feeds_config).await;
+        let providers = get_rpc_providers(
+            &cfg,
+            &new_provider_metrics(Some("test_get_wallet_success_")),
+            &feeds_config,
+        )
+        .await;
 
         // Call the function
         let wallet = &providers[network].lock().await.signer;
@@ -763,7 +850,7 @@ mod tests {
         // test
         let binding = init_shared_rpc_providers(
             &cfg,
-            Some("test_get_rpc_providers_returns_single_provider_"),
+            &new_provider_metrics(Some("test_get_rpc_providers_returns_single_provider_")),
             &feeds_config,
         )
         .await;
@@ -0,0 +1,449 @@
+//! Runtime changes to the set of networks the sequencer publishes to.
+//!
+//! A new `RpcProvider` is built from the given config, inherits the feeds' state of the
+//! provider it replaces, and is then swapped into `providers`, along with its config in
+//! `sequencer_config` and its `provider_status`, while holding the write lock of `providers`.
+//! Batches dispatched before the swap finish on the previous provider, after which what they
+//! published is carried over to the new one.
+
+use alloy::hex;
+use blocksense_config::{AllFeedsConfig, Provider, Validated};
+use blocksense_feed_registry::registry::HistoryEntry;
+use eyre::{eyre, Result};
+use ringbuf::traits::{Consumer, RingBuffer};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::providers::provider::{
+    create_rpc_provider, parse_eth_address, ProviderStatus, RpcProvider,
+};
+use crate::sequencer_state::SequencerState;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderChange {
+    pub provider: Provider,
+    /// Fills the history of the feeds with publishing criteria from the network's contract.
+    #[serde(default)]
+    pub load_history_from_chain: bool,
+}
+
+fn check_provider_config(network: &str, p: &Provider) -> Result<()> {
+    p.validate(network).map_err(|e| eyre!("{e}"))?;
+    let addresses = [
+        ("contract_address", &p.contract_address),
+        ("safe_address", &p.safe_address),
+        ("event_contract_address", &p.event_contract_address),
+        ("multicall_contract_address", &p.multicall_contract_address),
+        ("impersonated_anvil_account", &p.impersonated_anvil_account),
+    ];
+    for (name, address) in addresses {
+        if let Some(address) = address {
+            if parse_eth_address(address).is_none() {
+                eyre::bail!("{network}: {name} {address} is not a valid address");
+            }
+        }
+    }
+    let byte_codes = [
+        ("data_feed_store_byte_code", &p.data_feed_store_byte_code),
+        ("data_feed_sports_byte_code", &p.data_feed_sports_byte_code),
+    ];
+    for (name, byte_code) in byte_codes {
+        if let Some(byte_code) = byte_code {
+            if let Err(e) = hex::decode(byte_code) {
+                eyre::bail!("{network}: {name} is not a valid hex string: {e}");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn build_provider(
+    sequencer_state: &SequencerState,
+    network: &str,
+    p: &Provider,
+) -> Result<RpcProvider> {
+    check_provider_config(network, p)?;
+    let feeds_config = AllFeedsConfig {
+        feeds: sequencer_state
+            .active_feeds
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect(),
+    };
+    create_rpc_provider(
+        network,
+        p,
+        &sequencer_state.provider_metrics,
+        &feeds_config,
+        &sequencer_state.market_calendars,
+    )
+}
+
+/// Carries the history and round counters of the feeds still published with `new` over
+/// from `previous`.
+fn inherit_feeds_state(new: &mut RpcProvider, previous: &RpcProvider) {
+    for feed_id in new.publishing_criteria.keys() {
+        let (Some(previous_history), Some(history)) = (
+            previous.history.get(*feed_id),
+            new.history.get_mut(*feed_id),
+        ) else {
+            continue;
+        };
+        for entry in previous_history.iter() {
+            history.push_overwrite(entry.clone());
+        }
+    }
+    new.round_counters = previous.round_counters.clone();
+}
+
+/// Last update number in the history of every feed of `provider`.
+fn last_update_numbers(provider: &RpcProvider) -> HashMap<u32, u128> {
+    provider
+        .publishing_criteria
+        .keys()
+        .filter_map(|feed_id| Some((*feed_id, provider.history.last(*feed_id)?.update_number)))
+        .collect()
+}
+
+/// Carries over what was published with `previous` after `new` inherited its feeds' state,
+/// i.e. the history entries past the update numbers in `inherited`, and the round counters
+/// that moved since. Entries already published with `new` are kept after them.
+fn catch_up_feeds_state(
+    new: &mut RpcProvider,
+    previous: &RpcProvider,
+    inherited: &HashMap<u32, u128>,
+) {
+    for feed_id in new.publishing_criteria.keys() {
+        let (Some(previous_history), Some(history)) = (
+            previous.history.get(*feed_id),
+            new.history.get_mut(*feed_id),
+        ) else {
+            continue;
+        };
+        let is_new = |entry: &HistoryEntry| {
+            inherited
+                .get(feed_id)
+                .is_none_or(|last| entry.update_number > *last)
+        };
+        let missed: Vec<HistoryEntry> = previous_history
+            .iter()
+            .filter(|entry| is_new(entry))
+            .cloned()
+            .collect();
+        let Some(last_missed) = missed.last().map(|entry| entry.update_number) else {
+            continue;
+        };
+        let mut entries: Vec<HistoryEntry> = history.pop_iter().collect();
+        let own = entries.split_off(entries.iter().position(is_new).unwrap_or(entries.len()));
+        for entry in entries.into_iter().chain(missed) {
+            history.push_overwrite(entry);
+        }
+        for (update_number, mut entry) in (last_missed + 1..).zip(own) {
+            entry.update_number = update_number;
+            history.push_overwrite(entry);
+        }
+    }
+    for (feed_id, round) in &previous.round_counters {
+        let counter = new.round_counters.entry(*feed_id).or_insert(0);
+        *counter = (*counter).max(*round);
+    }
+}
+
+async fn load_provider_history(network: &str, provider: &Arc<Mutex<RpcProvider>>) {
+    let mut provider = provider.lock().await;
+    let mut feed_ids: Vec<u32> = provider.publishing_criteria.keys().copied().collect();
+    feed_ids.sort();
+    for feed_id in feed_ids {
+        let Some(capacity) = provider.get_history_capacity(feed_id) else {
+            continue;
+        };
+        match provider
+            .load_history_from_chain(feed_id, capacity as u32)
+            .await
+        {
+            Ok(count) => {
+                info!("Loaded {count} history entries of feed id {feed_id} from network {network}")
+            }
+            Err(e) => {
+                warn!("Could not load history of feed id {feed_id} from network {network}: {e}")
+            }
+        }
+    }
+}
+
+/// Adds a network, or reconfigures an existing one if `replace` is set.
+async fn set_provider(
+    sequencer_state: &SequencerState,
+    network: &str,
+    change: ProviderChange,
+    replace: bool,
+) -> Result<()> {
+    let mut rpc_provider = build_provider(sequencer_state, network, &change.provider).await?;
+    let previous = sequencer_state.providers.read().await.get(network).cloned();
+    let mut inherited = HashMap::new();
+    match (&previous, replace) {
+        (Some(_), false) => eyre::bail!("Network {network} already exists"),
+        (None, true) => eyre::bail!("No provider for network {network}"),
+        (Some(previous), true) => {
+            // Waits for the transactions in flight on the previous provider, without blocking
+            // the other networks.
+            let previous = previous.lock().await;
+            inherit_feeds_state(&mut rpc_provider, &previous);
+            inherited = last_update_numbers(&previous);
+        }
+        (None, false) => {}
+    }
+    let rpc_provider = {
+        let mut providers = sequencer_state.providers.write().await;
+        let unchanged = match (providers.get(network), &previous) {
+            (Some(current), Some(previous)) => Arc::ptr_eq(current, previous),
+            (None, None) => true,
+            _ => false,
+        };
+        if !unchanged {
+            eyre::bail!("Provider for network {network} was changed concurrently");
+        }
+        let rpc_provider = Arc::new(Mutex::new(rpc_provider));
+        providers.insert(network.to_string(), rpc_provider.clone());
+
+        let is_enabled = change.provider.is_enabled;
+        sequencer_state
+            .sequencer_config
+            .write()
+            .await
+            .providers
+            .insert(network.to_string(), change.provider);
+        sequencer_state.provider_status.write().await.insert(
+            network.to_string(),
+            if is_enabled {
+                ProviderStatus::AwaitingFirstUpdate
+            } else {
+                ProviderStatus::Disabled
+            },
+        );
+        rpc_provider
+    };
+    if let Some(previous) = &previous {
+        // Batches dispatched before the swap may have published with the previous provider
+        // since its state was inherited.
+        let previous = previous.lock().await;
+        catch_up_feeds_state(&mut *rpc_provider.lock().await, &previous, &inherited);
+    }
+
+    if change.load_history_from_chain {
+        load_provider_history(network, &rpc_provider).await;
+    }
+    info!(
+        "{} provider for network {network}",
+        if replace { "Reconfigured" } else { "Added" }
+    );
+    Ok(())
+}
+
+pub async fn add_provider(
+    sequencer_state: &SequencerState,
+    network: &str,
+    change: ProviderChange,
+) -> Result<()> {
+    set_provider(sequencer_state, network, change, false).await
+}
+
+pub async fn update_provider(
+    sequencer_state: &SequencerState,
+    network: &str,
+    change: ProviderChange,
+) -> Result<()> {
+    set_provider(sequencer_state, network, change, true).await
+}
+
+pub async fn remove_provider(sequencer_state: &SequencerState, network: &str) -> Result<()> {
+    let mut providers = sequencer_state.providers.write().await;
+    if providers.remove(network).is_none() {
+        eyre::bail!("No provider for network {network}");
+    }
+    sequencer_state
+        .sequencer_config
+        .write()
+        .await
+        .providers
+        .remove(network);
+    sequencer_state
+        .provider_status
+        .write()
+        .await
+        .remove(network);
+    info!("Removed provider for network {network}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use blocksense_config::{
+        get_test_config_with_single_provider, test_feed_config, PublishCriteria,
+    };
+    use blocksense_feed_registry::types::FeedType;
+    use blocksense_utils::test_env::get_test_private_key_path;
+
+    #[tokio::test]
+    async fn test_providers_management() {
+        let network = "ETH_test_providers_management";
+        let key_path = get_test_private_key_path();
+        let cfg = get_test_config_with_single_provider(
+            network,
+            key_path.as_path(),
+            "http://localhost:8545",
+        );
+        let provider_config = cfg.providers[network].clone();
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            cfg,
+            "test_providers_management",
+            AllFeedsConfig {
+                feeds: vec![test_feed_config(1, 0)],
+            },
+        )
+        .await;
+
+        let change = ProviderChange {
+            provider: provider_config.clone(),
+            load_history_from_chain: false,
+        };
+        assert!(add_provider(&sequencer_state, network, change.clone())
+            .await
+            .is_err());
+        add_provider(&sequencer_state, "ETH_added", change.clone())
+            .await
+            .unwrap();
+        assert!(sequencer_state.get_provider("ETH_added").await.is_some());
+        assert_eq!(
+            sequencer_state.provider_status.read().await["ETH_added"],
+            ProviderStatus::AwaitingFirstUpdate
+        );
+
+        sequencer_state
+            .get_provider(network)
+            .await
+            .unwrap()
+            .lock()
+            .await
+            .round_counters
+            .insert(1, 5);
+        let mut disabled = change.clone();
+        disabled.provider.is_enabled = false;
+        disabled.provider.transaction_gas_limit = 12345;
+        update_provider(&sequencer_state, network, disabled)
+            .await
+            .unwrap();
+        {
+            let provider = sequencer_state.get_provider(network).await.unwrap();
+            let provider = provider.lock().await;
+            assert_eq!(provider.transaction_gas_limit, 12345);
+            assert_eq!(provider.round_counters.get(&1), Some(&5));
+        }
+        assert_eq!(
+            sequencer_state.provider_status.read().await[network],
+            ProviderStatus::Disabled
+        );
+        assert!(!sequencer_state.sequencer_config.read().await.providers[network].is_enabled);
+
+        let mut invalid = change.clone();
+        invalid.provider.contract_address = Some("not an address".to_string());
+        assert!(update_provider(&sequencer_state, network, invalid)
+            .await
+            .is_err());
+        assert!(update_provider(&sequencer_state, "ETH_unknown", change)
+            .await
+            .is_err());
+
+        remove_provider(&sequencer_state, "ETH_added")
+            .await
+            .unwrap();
+        assert!(remove_provider(&sequencer_state, "ETH_added")
+            .await
+            .is_err());
+        assert!(sequencer_state.get_provider("ETH_added").await.is_none());
+        assert!(!sequencer_state
+            .sequencer_config
+            .read()
+            .await
+            .providers
+            .contains_key("ETH_added"));
+        assert!(!sequencer_state
+            .provider_status
+            .read()
+            .await
+            .contains_key("ETH_added"));
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_feeds_state() {
+        let network = "ETH_test_catch_up_feeds_state";
+        let key_path = get_test_private_key_path();
+        let mut cfg = get_test_config_with_single_provider(
+            network,
+            key_path.as_path(),
+            "http://localhost:8545",
+        );
+        cfg.providers
+            .get_mut(network)
+            .unwrap()
+            .publishing_criteria
+            .push(PublishCriteria {
+                feed_id: 1,
+                skip_publish_if_less_then_percentage: 0.0,
+                always_publish_heartbeat_ms: None,
+                peg_to_value: None,
+                peg_tolerance_percentage: 0.0,
+            });
+        let provider_config = cfg.providers[network].clone();
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            cfg,
+            "test_catch_up_feeds_state",
+            AllFeedsConfig {
+                feeds: vec![test_feed_config(1, 0)],
+            },
+        )
+        .await;
+
+        let previous = sequencer_state.get_provider(network).await.unwrap();
+        let mut previous = previous.lock().await;
+        previous.history.push_next(1, FeedType::Numerical(1.0), 1);
+        previous.round_counters.insert(1, 1);
+        let mut new = build_provider(&sequencer_state, network, &provider_config)
+            .await
+            .unwrap();
+        inherit_feeds_state(&mut new, &previous);
+        let inherited = last_update_numbers(&previous);
+
+        // Published with the previous provider by batches dispatched before the swap, and with
+        // the new provider right after it.
+        previous.history.push_next(1, FeedType::Numerical(2.0), 2);
+        previous.round_counters.insert(1, 3);
+        new.history.push_next(1, FeedType::Numerical(3.0), 3);
+        new.round_counters.insert(1, 2);
+
+        catch_up_feeds_state(&mut new, &previous, &inherited);
+        let history: Vec<(FeedType, u128)> = new
+            .history
+            .get(1)
+            .unwrap()
+            .iter()
+            .map(|entry| (entry.value.clone(), entry.update_number))
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                (FeedType::Numerical(1.0), 0),
+                (FeedType::Numerical(2.0), 1),
+                (FeedType::Numerical(3.0), 2),
+            ]
+        );
+        assert_eq!(new.round_counters.get(&1), Some(&3));
+    }
+}
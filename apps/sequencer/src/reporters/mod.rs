@@ -1 +1,3 @@
 pub mod reporter;
+pub mod reporters_management;
+pub mod reports_limiter;
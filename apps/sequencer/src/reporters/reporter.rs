@@ -2,6 +2,7 @@ use alloy::primitives::Address;
 use blocksense_config::SequencerConfig;
 use blocksense_crypto::{deserialize_public_key, PublicKey, MULTIFORMATS_BLS_PUBKYE_PREFIX};
 use blocksense_metrics::metrics::ReporterMetrics;
+use eyre::{eyre, Result};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -11,39 +12,76 @@ use tokio::sync::RwLock;
 pub struct Reporter {
     pub pub_key: PublicKey,
     pub address: Address,
+    pub suspended: bool,
     pub reporter_metrics: Arc<RwLock<ReporterMetrics>>,
 }
 
+impl Reporter {
+    pub fn from_config(
+        conf: &blocksense_config::Reporter,
+        reporter_metrics: Arc<RwLock<ReporterMetrics>>,
+    ) -> Result<Reporter> {
+        let (pub_key, address) = parse_reporter_keys(&conf.pub_key, &conf.address)?;
+        Ok(Reporter {
+            pub_key,
+            address,
+            suspended: conf.suspended,
+            reporter_metrics,
+        })
+    }
+}
+
 pub type SharedReporter = Arc<RwLock<Reporter>>;
 
 pub type Reporters = HashMap<u64, SharedReporter>;
 
 pub type SharedReporters = Arc<RwLock<Reporters>>;
 
-pub fn init_shared_reporters(conf: &SequencerConfig, prefix: Option<&str>) -> SharedReporters {
-    let prefix = prefix.unwrap_or("");
-    Arc::new(RwLock::new(init_reporters(conf, prefix)))
+/// Parses the BLS pub key, with its multiformats prefix, and the second round address of
+/// a reporter.
+pub fn parse_reporter_keys(pub_key: &str, address: &str) -> Result<(PublicKey, Address)> {
+    let pub_key = pub_key
+        .strip_prefix(MULTIFORMATS_BLS_PUBKYE_PREFIX)
+        .ok_or_else(|| eyre!("Multiformats key prefix error. Only BLS is currently supported."))?;
+    let pub_key =
+        deserialize_public_key(pub_key).map_err(|e| eyre!("Pub key format error: {e}"))?;
+    let address =
+        Address::from_str(address).map_err(|e| eyre!("Could not deserialize address: {e}"))?;
+    Ok((pub_key, address))
+}
+
+pub fn init_shared_reporters(
+    conf: &SequencerConfig,
+    reporter_metrics: &Arc<RwLock<ReporterMetrics>>,
+) -> SharedReporters {
+    Arc::new(RwLock::new(init_reporters(conf, reporter_metrics)))
 }
 
-fn init_reporters(conf: &SequencerConfig, prefix: &str) -> HashMap<u64, Arc<RwLock<Reporter>>> {
+fn init_reporters(
+    conf: &SequencerConfig,
+    reporter_metrics: &Arc<RwLock<ReporterMetrics>>,
+) -> HashMap<u64, Arc<RwLock<Reporter>>> {
     let mut reporters = HashMap::new();
-    let reporter_metrics = Arc::new(RwLock::new(
-        ReporterMetrics::new(prefix).expect("Failed to allocate ReporterMetrics."),
-    ));
     for r in &conf.reporters {
-        reporters.insert(
-            r.id.into(),
-            Arc::new(RwLock::new(Reporter {
-                pub_key: deserialize_public_key(
-                    r.pub_key
-                        .strip_prefix(MULTIFORMATS_BLS_PUBKYE_PREFIX)
-                        .expect("Multiformats key prefix error. Only BLS is currently supported."),
-                )
-                .expect("Pub key format error: "),
-                address: Address::from_str(&r.address).expect("Could not deserialize address!"),
-                reporter_metrics: reporter_metrics.clone(),
-            })),
-        );
+        let reporter = Reporter::from_config(r, reporter_metrics.clone())
+            .unwrap_or_else(|e| panic!("Invalid reporter id {}: {e}", r.id));
+        reporters.insert(r.id.into(), Arc::new(RwLock::new(reporter)));
     }
     reporters
 }
+
+/// Ids of the reporters that are not suspended.
+pub async fn active_reporter_ids(reporters: &SharedReporters) -> Vec<u64> {
+    let mut ids = Vec::new();
+    for (id, reporter) in reporters.read().await.iter() {
+        if !reporter.read().await.suspended {
+            ids.push(*id);
+        }
+    }
+    ids
+}
+
+/// Number of reporters whose votes count towards quorum.
+pub async fn count_active_reporters(reporters: &SharedReporters) -> usize {
+    active_reporter_ids(reporters).await.len()
+}
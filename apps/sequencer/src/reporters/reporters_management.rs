@@ -0,0 +1,265 @@
+//! Runtime changes to the set of reporters. Every change is applied to `reporters` and
+//! mirrored into `sequencer_config`, so that `/get_sequencer_config` reflects it. Reports
+//! already collected for the current slots are kept.
+
+use alloy::primitives::Address;
+use blocksense_config::{Reporter as ReporterConfig, Validated};
+use blocksense_crypto::PublicKey;
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::reporters::reporter::{parse_reporter_keys, Reporter, Reporters};
+use crate::sequencer_state::SequencerState;
+
+/// Keys a reporter signs with: its BLS pub key and its second round address.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReporterKeys {
+    pub pub_key: String,
+    pub address: String,
+}
+
+pub async fn add_reporter(sequencer_state: &SequencerState, conf: ReporterConfig) -> Result<()> {
+    let reporter_id = conf.id;
+    conf.validate(format!("Reporter id: {reporter_id}").as_str())
+        .map_err(|e| eyre!("{e}"))?;
+    let reporter = Reporter::from_config(&conf, sequencer_state.reporter_metrics.clone())?;
+    {
+        let mut reporters = sequencer_state.reporters.write().await;
+        if reporters.contains_key(&reporter_id.into()) {
+            eyre::bail!("Reporter with id {reporter_id} already exists");
+        }
+        check_keys_unused(
+            &reporters,
+            reporter_id,
+            &reporter.pub_key,
+            &reporter.address,
+        )
+        .await?;
+        reporters.insert(reporter_id.into(), Arc::new(RwLock::new(reporter)));
+    }
+    {
+        let mut sequencer_config = sequencer_state.sequencer_config.write().await;
+        sequencer_config.reporters.retain(|r| r.id != reporter_id);
+        sequencer_config.reporters.push(conf);
+    }
+    info!("Added reporter {reporter_id}");
+    Ok(())
+}
+
+pub async fn remove_reporter(sequencer_state: &SequencerState, reporter_id: u32) -> Result<()> {
+    if sequencer_state
+        .reporters
+        .write()
+        .await
+        .remove(&reporter_id.into())
+        .is_none()
+    {
+        eyre::bail!("No reporter with id {reporter_id}");
+    }
+    sequencer_state
+        .sequencer_config
+        .write()
+        .await
+        .reporters
+        .retain(|r| r.id != reporter_id);
+    info!("Removed reporter {reporter_id}");
+    Ok(())
+}
+
+/// Suspends or resumes a reporter. Suspended reporters keep their keys, but their votes are
+/// rejected and quorums are computed without them.
+pub async fn set_reporter_suspended(
+    sequencer_state: &SequencerState,
+    reporter_id: u32,
+    suspended: bool,
+) -> Result<()> {
+    let reporter = sequencer_state
+        .reporters
+        .read()
+        .await
+        .get(&reporter_id.into())
+        .cloned()
+        .ok_or_else(|| eyre!("No reporter with id {reporter_id}"))?;
+    {
+        let mut reporter = reporter.write().await;
+        if reporter.suspended == suspended {
+            let state = if suspended { "suspended" } else { "active" };
+            eyre::bail!("Reporter {reporter_id} is already {state}");
+        }
+        reporter.suspended = suspended;
+    }
+    update_reporter_config(sequencer_state, reporter_id, |conf| {
+        conf.suspended = suspended
+    })
+    .await;
+    info!("Reporter {reporter_id} suspended = {suspended}");
+    Ok(())
+}
+
+/// Replaces the keys of a reporter. Votes verified with the previous keys are kept.
+pub async fn rotate_reporter_keys(
+    sequencer_state: &SequencerState,
+    reporter_id: u32,
+    keys: ReporterKeys,
+) -> Result<()> {
+    let (pub_key, address) = parse_reporter_keys(&keys.pub_key, &keys.address)?;
+    {
+        // Held until the keys are replaced, so that concurrent changes can not take them too.
+        let reporters = sequencer_state.reporters.write().await;
+        let reporter = reporters
+            .get(&reporter_id.into())
+            .ok_or_else(|| eyre!("No reporter with id {reporter_id}"))?;
+        check_keys_unused(&reporters, reporter_id, &pub_key, &address).await?;
+        let mut reporter = reporter.write().await;
+        reporter.pub_key = pub_key;
+        reporter.address = address;
+    }
+    update_reporter_config(sequencer_state, reporter_id, |conf| {
+        conf.pub_key = keys.pub_key;
+        conf.address = keys.address;
+    })
+    .await;
+    info!("Rotated the keys of reporter {reporter_id}");
+    Ok(())
+}
+
+/// Fails if a reporter other than `reporter_id` already uses `pub_key` or `address`, since
+/// votes are attributed to reporters by their keys.
+async fn check_keys_unused(
+    reporters: &Reporters,
+    reporter_id: u32,
+    pub_key: &PublicKey,
+    address: &Address,
+) -> Result<()> {
+    for (id, reporter) in reporters {
+        if *id == u64::from(reporter_id) {
+            continue;
+        }
+        let reporter = reporter.read().await;
+        if reporter.pub_key == *pub_key {
+            eyre::bail!("Pub key is already used by reporter {id}");
+        }
+        if reporter.address == *address {
+            eyre::bail!("Address {address} is already used by reporter {id}");
+        }
+    }
+    Ok(())
+}
+
+async fn update_reporter_config(
+    sequencer_state: &SequencerState,
+    reporter_id: u32,
+    update: impl FnOnce(&mut ReporterConfig),
+) {
+    let mut sequencer_config = sequencer_state.sequencer_config.write().await;
+    if let Some(conf) = sequencer_config
+        .reporters
+        .iter_mut()
+        .find(|r| r.id == reporter_id)
+    {
+        update(conf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporters::reporter::count_active_reporters;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use blocksense_config::{get_test_config_with_no_providers, AllFeedsConfig};
+
+    const PUB_KEY_1: &str = "ea30b1533ef5638af7b70a036275642fc453ace97ed2c6b9d220fe1f59a24d61f481a777aa8a579f20e95a74cd4567ed36a3";
+    const PUB_KEY_2: &str = "ea30813e2f8cf968e27bad29167b41bce038a3ce9b7b368de05e5cf1af3de919eeba267b8706f55c356d5f71891eff116b98";
+
+    const ADDRESS_1: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const ADDRESS_2: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const ADDRESS_3: &str = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC";
+
+    fn reporter_config(id: u32, pub_key: &str, address: &str) -> ReporterConfig {
+        ReporterConfig {
+            id,
+            pub_key: pub_key.to_string(),
+            address: address.to_string(),
+            suspended: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reporters_management() {
+        let mut cfg = get_test_config_with_no_providers();
+        cfg.reporters.push(reporter_config(1, PUB_KEY_1, ADDRESS_1));
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            cfg,
+            "test_reporters_management",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+
+        // Neither the pub key nor the address of another reporter can be reused.
+        assert!(
+            add_reporter(&sequencer_state, reporter_config(2, PUB_KEY_1, ADDRESS_2))
+                .await
+                .is_err()
+        );
+        assert!(
+            add_reporter(&sequencer_state, reporter_config(2, PUB_KEY_2, ADDRESS_1))
+                .await
+                .is_err()
+        );
+        add_reporter(&sequencer_state, reporter_config(2, PUB_KEY_2, ADDRESS_2))
+            .await
+            .unwrap();
+        assert!(
+            add_reporter(&sequencer_state, reporter_config(2, PUB_KEY_2, ADDRESS_2))
+                .await
+                .is_err()
+        );
+        assert!(
+            add_reporter(&sequencer_state, reporter_config(3, "ea30abcd", ADDRESS_3))
+                .await
+                .is_err()
+        );
+        assert_eq!(count_active_reporters(&sequencer_state.reporters).await, 2);
+
+        set_reporter_suspended(&sequencer_state, 1, true)
+            .await
+            .unwrap();
+        assert!(set_reporter_suspended(&sequencer_state, 1, true)
+            .await
+            .is_err());
+        assert_eq!(count_active_reporters(&sequencer_state.reporters).await, 1);
+
+        let rotate = |pub_key: &str, address: &str| {
+            rotate_reporter_keys(
+                &sequencer_state,
+                2,
+                ReporterKeys {
+                    pub_key: pub_key.to_string(),
+                    address: address.to_string(),
+                },
+            )
+        };
+        assert!(rotate(PUB_KEY_1, ADDRESS_3).await.is_err());
+        assert!(rotate(PUB_KEY_2, ADDRESS_1).await.is_err());
+        rotate(PUB_KEY_2, ADDRESS_3).await.unwrap();
+
+        {
+            let config = sequencer_state.sequencer_config.read().await;
+            assert_eq!(config.reporters.len(), 2);
+            assert!(config.reporters[0].suspended);
+            assert_eq!(config.reporters[1].pub_key, PUB_KEY_2);
+            assert_eq!(config.reporters[1].address, ADDRESS_3);
+        }
+
+        remove_reporter(&sequencer_state, 1).await.unwrap();
+        assert!(remove_reporter(&sequencer_state, 1).await.is_err());
+        assert_eq!(count_active_reporters(&sequencer_state.reporters).await, 1);
+        assert_eq!(
+            sequencer_state.sequencer_config.read().await.reporters,
+            vec![reporter_config(2, PUB_KEY_2, ADDRESS_3)]
+        );
+    }
+}
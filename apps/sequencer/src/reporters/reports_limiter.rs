@@ -0,0 +1,212 @@
+//! Token bucket rate limiting of report ingestion, per reporter and per client IP address.
+
+use blocksense_config::{RateLimit, ReportsLimits};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Buckets are swept once this many keys are tracked, dropping the ones that are full again.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_sec).min(limit.burst as f64);
+        self.last_refill = now;
+    }
+}
+
+#[derive(Debug)]
+struct Buckets<K> {
+    buckets: HashMap<K, TokenBucket>,
+    /// Number of tracked keys at which the next sweep runs. It is at least twice the keys
+    /// left after the last sweep, so that sweeps take amortized constant time per new key.
+    sweep_at: usize,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    limit: RateLimit,
+    buckets: Mutex<Buckets<K>>,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(limit: RateLimit) -> RateLimiter<K> {
+        RateLimiter {
+            limit,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                sweep_at: MAX_TRACKED_KEYS,
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket of `key`. Returns false if it is empty.
+    pub fn try_acquire(&self, key: K) -> bool {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: K, now: Instant) -> bool {
+        let limit = &self.limit;
+        let mut guard = self.buckets.lock().expect("Poisoned rate limiter");
+        let Buckets { buckets, sweep_at } = &mut *guard;
+        if buckets.len() >= *sweep_at {
+            buckets.retain(|_, bucket| {
+                bucket.refill(limit, now);
+                bucket.tokens < limit.burst as f64
+            });
+            *sweep_at = MAX_TRACKED_KEYS.max(2 * buckets.len());
+        }
+        let bucket = buckets.entry(key).or_insert(TokenBucket {
+            tokens: limit.burst as f64,
+            last_refill: now,
+        });
+        bucket.refill(limit, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The `ReportsLimits` of the sequencer config, with the state of their rate limiters.
+#[derive(Debug)]
+pub struct ReportsLimiter {
+    pub limits: ReportsLimits,
+    per_reporter: Option<RateLimiter<u64>>,
+    per_ip: Option<RateLimiter<IpAddr>>,
+}
+
+impl ReportsLimiter {
+    pub fn new(limits: ReportsLimits) -> ReportsLimiter {
+        ReportsLimiter {
+            per_reporter: limits.per_reporter.map(RateLimiter::new),
+            per_ip: limits.per_ip.map(RateLimiter::new),
+            limits,
+        }
+    }
+
+    pub fn allow_report(&self, reporter_id: u64) -> bool {
+        self.per_reporter
+            .as_ref()
+            .is_none_or(|limiter| limiter.try_acquire(reporter_id))
+    }
+
+    pub fn allow_request(&self, ip: IpAddr) -> bool {
+        self.per_ip
+            .as_ref()
+            .is_none_or(|limiter| limiter.try_acquire(ip))
+    }
+
+    pub fn allow_batch(&self, batch_len: usize) -> bool {
+        self.limits
+            .max_batch_len
+            .is_none_or(|max_batch_len| batch_len <= max_batch_len)
+    }
+
+    pub fn allow_revote(&self, revotes: u32) -> bool {
+        self.limits
+            .max_revotes_per_slot
+            .is_none_or(|max_revotes| revotes < max_revotes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_token_bucket_refills_at_the_configured_rate() {
+        let limiter = RateLimiter::new(RateLimit {
+            per_sec: 2.0,
+            burst: 3,
+        });
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(1, start));
+        }
+        assert!(!limiter.try_acquire_at(1, start));
+        assert!(limiter.try_acquire_at(2, start));
+
+        let half_a_sec_later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at(1, half_a_sec_later));
+        assert!(!limiter.try_acquire_at(1, half_a_sec_later));
+
+        // The bucket does not grow over the burst while idle.
+        let much_later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(1, much_later));
+        }
+        assert!(!limiter.try_acquire_at(1, much_later));
+    }
+
+    #[test]
+    fn test_buckets_are_swept_when_the_tracked_keys_double() {
+        let limiter = RateLimiter::new(RateLimit {
+            per_sec: 1.0,
+            burst: 1,
+        });
+        let start = Instant::now();
+        let tracked = |limiter: &RateLimiter<usize>| {
+            let buckets = limiter.buckets.lock().unwrap();
+            (buckets.buckets.len(), buckets.sweep_at)
+        };
+        for key in 0..MAX_TRACKED_KEYS {
+            assert!(limiter.try_acquire_at(key, start));
+        }
+        assert_eq!(tracked(&limiter), (MAX_TRACKED_KEYS, MAX_TRACKED_KEYS));
+
+        // None of the buckets is full again, so the sweep keeps them all and the next one
+        // waits until twice as many keys are tracked.
+        assert!(limiter.try_acquire_at(MAX_TRACKED_KEYS, start));
+        assert_eq!(
+            tracked(&limiter),
+            (MAX_TRACKED_KEYS + 1, 2 * MAX_TRACKED_KEYS)
+        );
+        assert!(limiter.try_acquire_at(MAX_TRACKED_KEYS + 1, start));
+        assert_eq!(tracked(&limiter).1, 2 * MAX_TRACKED_KEYS);
+
+        // Once refilled, the buckets of the idle keys are dropped.
+        for key in MAX_TRACKED_KEYS + 2..2 * MAX_TRACKED_KEYS {
+            assert!(limiter.try_acquire_at(key, start));
+        }
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.try_acquire_at(2 * MAX_TRACKED_KEYS, later));
+        assert_eq!(tracked(&limiter), (1, MAX_TRACKED_KEYS));
+    }
+
+    #[test]
+    fn test_reports_limiter() {
+        let limiter = ReportsLimiter::new(ReportsLimits {
+            per_reporter: None,
+            per_ip: Some(RateLimit {
+                per_sec: 1.0,
+                burst: 1,
+            }),
+            max_batch_len: Some(2),
+            max_revotes_per_slot: Some(1),
+        });
+        assert!(limiter.allow_report(1));
+        assert!(limiter.allow_report(1));
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        assert!(limiter.allow_request(ip));
+        assert!(!limiter.allow_request(ip));
+        assert!(limiter.allow_batch(2));
+        assert!(!limiter.allow_batch(3));
+        assert!(limiter.allow_revote(0));
+        assert!(!limiter.allow_revote(1));
+    }
+}
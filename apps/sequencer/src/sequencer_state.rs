@@ -1,52 +1,82 @@
 use crate::feeds::consensus_second_round_manager::AggregationBatchConsensus;
 use crate::feeds::feed_allocator::{init_concurrent_allocator, ConcurrentAllocator};
+use crate::feeds::published_updates_stream::PublishedUpdatesStream;
+use crate::http_handlers::admin_auth::AdminTokens;
 use crate::providers::provider::ProviderStatus;
 use crate::providers::provider::SharedRpcProviders;
-use crate::providers::provider::{init_shared_rpc_providers, RpcProvider};
+use crate::providers::provider::{init_shared_rpc_providers, new_provider_metrics, RpcProvider};
 use crate::reporters::reporter::init_shared_reporters;
 use crate::reporters::reporter::SharedReporters;
+use crate::reporters::reports_limiter::ReportsLimiter;
+use crate::shutdown::ShutdownCoordinator;
+use blocksense_blockchain_data_model::block_store::{BlockStore, FileBlockStore};
 use blocksense_blockchain_data_model::in_mem_db::InMemDb;
-use blocksense_config::{AllFeedsConfig, SequencerConfig};
+use blocksense_config::{
+    AllFeedsConfig, BlockSigning, BlockStoreConfig, ReportSigning, SequencerConfig,
+};
+use blocksense_crypto::{
+    deserialize_priv_key, deserialize_public_key, PublicKey, SecretKey,
+    MULTIFORMATS_BLS_PUBKYE_PREFIX,
+};
 use blocksense_data_feeds::feeds_processing::VotedFeedUpdateWithProof;
 use blocksense_feed_registry::feed_registration_cmds::FeedsManagementCmds;
+use blocksense_feed_registry::market_hours::MarketCalendars;
 use blocksense_feed_registry::registry::new_feeds_meta_data_reg_from_config;
 use blocksense_feed_registry::registry::{
     AllFeedsReports, FeedAggregateHistory, FeedMetaDataRegistry,
 };
+use blocksense_feed_registry::reputation::ReporterReputations;
 use blocksense_gnosis_safe::data_types::ReporterResponse;
 use blocksense_gnosis_safe::utils::SignatureWithAddress;
-use blocksense_metrics::metrics::FeedsMetrics;
+use blocksense_metrics::metrics::{AdminMetrics, FeedsMetrics, ProviderMetrics, ReporterMetrics};
 use blocksense_registry::config::FeedConfig;
 use blocksense_utils::logging::{init_shared_logging_handle, SharedLoggingHandle};
 use eyre::eyre;
 use rdkafka::producer::FutureProducer;
 use rdkafka::ClientConfig;
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tracing::warn;
 
 pub struct SequencerState {
     pub registry: Arc<RwLock<FeedMetaDataRegistry>>,
     pub reports: Arc<RwLock<AllFeedsReports>>,
     pub providers: SharedRpcProviders,
+    pub provider_metrics: Arc<RwLock<ProviderMetrics>>,
     pub log_handle: SharedLoggingHandle,
     pub reporters: SharedReporters,
+    pub reporter_metrics: Arc<RwLock<ReporterMetrics>>,
     pub feed_id_allocator: Arc<RwLock<Option<ConcurrentAllocator>>>,
     pub aggregated_votes_to_block_creator_send: UnboundedSender<VotedFeedUpdateWithProof>,
     pub feeds_metrics: Arc<RwLock<FeedsMetrics>>,
     pub active_feeds: Arc<RwLock<HashMap<u32, FeedConfig>>>,
+    /// Feeds of the feeds config file as last applied, at startup or by a reload.
+    pub applied_feeds_config: Arc<Mutex<HashMap<u32, FeedConfig>>>,
     pub sequencer_config: Arc<RwLock<SequencerConfig>>,
     pub feed_aggregate_history: Arc<RwLock<FeedAggregateHistory>>,
+    pub market_calendars: Arc<MarketCalendars>,
+    pub reporter_reputation: Arc<RwLock<ReporterReputations>>,
     pub feeds_management_cmd_to_block_creator_send: UnboundedSender<FeedsManagementCmds>,
     pub feeds_slots_manager_cmd_send: UnboundedSender<FeedsManagementCmds>,
-    pub blockchain_db: Arc<RwLock<InMemDb>>,
+    pub blockchain_db: Arc<RwLock<Box<dyn BlockStore>>>,
+    pub block_signing_key: Option<SecretKey>,
+    pub sequencer_pub_keys: HashMap<u64, PublicKey>,
     pub kafka_endpoint: Option<FutureProducer>,
     pub provider_status: Arc<RwLock<HashMap<String, ProviderStatus>>>,
     pub batches_awaiting_consensus: Arc<RwLock<AggregationBatchConsensus>>,
     pub aggregate_batch_sig_send: UnboundedSender<(ReporterResponse, SignatureWithAddress)>,
+    pub shutdown: ShutdownCoordinator,
+    pub admin_tokens: Option<AdminTokens>,
+    pub admin_metrics: Arc<RwLock<AdminMetrics>>,
+    pub published_updates: PublishedUpdatesStream,
+    pub reports_limiter: Option<ReportsLimiter>,
+    pub report_signing: ReportSigning,
     // pub voting_recv_channel: Arc<RwLock<mpsc::UnboundedReceiver<(String, String)>>>,
 }
 
@@ -55,6 +85,7 @@ impl SequencerState {
     pub fn new(
         feeds_config: AllFeedsConfig,
         providers: SharedRpcProviders,
+        provider_metrics: Arc<RwLock<ProviderMetrics>>,
         log_handle: SharedLoggingHandle,
         sequencer_config: &SequencerConfig,
         metrics_prefix: Option<&str>,
@@ -63,7 +94,7 @@ impl SequencerState {
         feeds_management_cmd_to_block_creator_send: UnboundedSender<FeedsManagementCmds>,
         feeds_slots_manager_cmd_send: UnboundedSender<FeedsManagementCmds>,
         aggregate_batch_sig_send: UnboundedSender<(ReporterResponse, SignatureWithAddress)>,
-    ) -> SequencerState {
+    ) -> Result<SequencerState, eyre::Error> {
         let provider_status: HashMap<String, ProviderStatus> = sequencer_config
             .providers
             .iter()
@@ -81,14 +112,34 @@ impl SequencerState {
         for feed in &feeds_config.feeds {
             history.register_feed(feed.id, 100);
         }
-        SequencerState {
+        let market_calendars = MarketCalendars::new(&sequencer_config.market_calendars);
+        for feed in &feeds_config.feeds {
+            if let Err(e) = market_calendars.validate_feed(feed) {
+                return Err(eyre!("Invalid feeds config: {e}"));
+            }
+        }
+        let blockchain_db = open_block_store(&sequencer_config.block_store)?;
+        let (block_signing_key, sequencer_pub_keys) =
+            load_block_signing_keys(sequencer_config.block_signing.as_ref())?;
+        let reporter_metrics = Arc::new(RwLock::new(
+            ReporterMetrics::new(metrics_prefix.unwrap_or(""))
+                .expect("Failed to allocate ReporterMetrics."),
+        ));
+        let applied_feeds_config = feeds_config
+            .feeds
+            .iter()
+            .map(|feed| (feed.id, feed.clone()))
+            .collect();
+        Ok(SequencerState {
             registry: Arc::new(RwLock::new(new_feeds_meta_data_reg_from_config(
                 &feeds_config,
             ))),
             reports: Arc::new(RwLock::new(AllFeedsReports::new())),
             providers,
+            provider_metrics,
             log_handle,
-            reporters: init_shared_reporters(sequencer_config, metrics_prefix),
+            reporters: init_shared_reporters(sequencer_config, &reporter_metrics),
+            reporter_metrics,
             feed_id_allocator: Arc::new(RwLock::new(feed_id_allocator)),
             aggregated_votes_to_block_creator_send,
             feeds_metrics: Arc::new(RwLock::new(
@@ -102,11 +153,18 @@ impl SequencerState {
                     .map(|feed| (feed.id, feed))
                     .collect(),
             )),
+            applied_feeds_config: Arc::new(Mutex::new(applied_feeds_config)),
             sequencer_config: Arc::new(RwLock::new(sequencer_config.clone())),
             feed_aggregate_history: Arc::new(RwLock::new(history)),
+            market_calendars: Arc::new(market_calendars),
+            reporter_reputation: Arc::new(RwLock::new(ReporterReputations::new(
+                sequencer_config.reporter_reputation.clone(),
+            ))),
             feeds_management_cmd_to_block_creator_send,
             feeds_slots_manager_cmd_send,
-            blockchain_db: Arc::new(RwLock::new(InMemDb::new())),
+            blockchain_db: Arc::new(RwLock::new(blockchain_db)),
+            block_signing_key,
+            sequencer_pub_keys,
             kafka_endpoint: sequencer_config
                 .kafka_report_endpoint
                 .url
@@ -118,7 +176,19 @@ impl SequencerState {
             provider_status,
             batches_awaiting_consensus: Arc::new(RwLock::new(AggregationBatchConsensus::new())),
             aggregate_batch_sig_send,
-        }
+            shutdown: ShutdownCoordinator::new(),
+            admin_tokens: AdminTokens::load(sequencer_config.admin_auth.as_ref()),
+            admin_metrics: Arc::new(RwLock::new(
+                AdminMetrics::new(metrics_prefix.unwrap_or(""))
+                    .expect("Failed to allocate AdminMetrics"),
+            )),
+            published_updates: PublishedUpdatesStream::new(),
+            reports_limiter: sequencer_config
+                .reports_limits
+                .clone()
+                .map(ReportsLimiter::new),
+            report_signing: sequencer_config.report_signing.clone().unwrap_or_default(),
+        })
     }
 
     pub async fn get_provider(&self, network: &str) -> Option<Arc<Mutex<RpcProvider>>> {
@@ -138,6 +208,51 @@ impl SequencerState {
     }
 }
 
+fn open_block_store(config: &BlockStoreConfig) -> Result<Box<dyn BlockStore>, eyre::Error> {
+    Ok(match config {
+        BlockStoreConfig::InMemory => Box::new(InMemDb::new()),
+        BlockStoreConfig::File { path } => Box::new(
+            FileBlockStore::open(Path::new(path))
+                .map_err(|e| eyre!("Could not open block store {path}: {e:#}"))?,
+        ),
+    })
+}
+
+fn load_block_signing_keys(
+    config: Option<&BlockSigning>,
+) -> Result<(Option<SecretKey>, HashMap<u64, PublicKey>), eyre::Error> {
+    let Some(config) = config else {
+        warn!("No block signing configured, streamed blocks will be neither signed nor verified!");
+        return Ok((None, HashMap::new()));
+    };
+    let priv_key = fs::read_to_string(&config.private_key_path).map_err(|e| {
+        eyre!(
+            "Failed to read block signing key from {}: {e}",
+            config.private_key_path
+        )
+    })?;
+    let priv_key = deserialize_priv_key(priv_key.trim())
+        .map_err(|e| eyre!("Block signing key format error: {e}"))?;
+    let pub_keys = config
+        .sequencer_pub_keys
+        .iter()
+        .map(|(sequencer_id, pub_key)| {
+            let pub_key = pub_key
+                .strip_prefix(MULTIFORMATS_BLS_PUBKYE_PREFIX)
+                .ok_or_else(|| {
+                    eyre!(
+                        "Multiformats key prefix error for sequencer {sequencer_id}. Only BLS is currently supported."
+                    )
+                })?;
+            let pub_key = deserialize_public_key(pub_key).map_err(|e| {
+                eyre!("Pub key format error for sequencer {sequencer_id}: {e}")
+            })?;
+            Ok((*sequencer_id, pub_key))
+        })
+        .collect::<Result<HashMap<_, _>, eyre::Error>>()?;
+    Ok((Some(priv_key), pub_keys))
+}
+
 // Exclusively take config structure to init sequencer_state
 pub async fn create_sequencer_state_from_sequencer_config(
     sequencer_config: SequencerConfig,
@@ -151,8 +266,9 @@ pub async fn create_sequencer_state_from_sequencer_config(
     UnboundedReceiver<(ReporterResponse, SignatureWithAddress)>, // aggregate_batch_sig_recv
 ) {
     let log_handle = init_shared_logging_handle("INFO", false);
+    let provider_metrics = new_provider_metrics(Some(metrics_prefix));
     let providers =
-        init_shared_rpc_providers(&sequencer_config, Some(metrics_prefix), &feeds_config).await;
+        init_shared_rpc_providers(&sequencer_config, &provider_metrics, &feeds_config).await;
 
     let (vote_send, vote_recv) = mpsc::unbounded_channel();
     let (feeds_management_cmd_to_block_creator_send, feeds_management_cmd_to_block_creator_recv) =
@@ -164,6 +280,7 @@ pub async fn create_sequencer_state_from_sequencer_config(
     let sequencer_state = SequencerState::new(
         feeds_config.clone(),
         providers,
+        provider_metrics,
         log_handle,
         &sequencer_config,
         Some(metrics_prefix),
@@ -172,7 +289,8 @@ pub async fn create_sequencer_state_from_sequencer_config(
         feeds_management_cmd_to_block_creator_send,
         feeds_slots_manager_cmd_send,
         aggregate_batch_sig_send,
-    );
+    )
+    .expect("Could not create sequencer state");
 
     (
         actix_web::web::Data::new(sequencer_state),
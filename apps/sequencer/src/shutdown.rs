@@ -0,0 +1,243 @@
+use actix_web::dev::ServerHandle;
+use actix_web::web::Data;
+use rdkafka::producer::Producer;
+use rdkafka::util::Timeout;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Notify};
+use tokio::time::{sleep, timeout_at, Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::feeds::history_persistence::persist_feed_histories;
+use crate::sequencer_state::SequencerState;
+
+const DRAIN_POLL_PERIOD: Duration = Duration::from_millis(100);
+
+/// Coordinates the graceful shutdown of the sequencer's workers.
+///
+/// Once a shutdown is requested, reports are no longer accepted, the block creator emits
+/// the block of the current slot and stops, and the votes result sender stops after it has
+/// dispatched the batches of that block. Transactions still being sent are tracked through
+/// `InFlightGuard`s. The HTTP servers are stopped once all of this is drained.
+pub struct ShutdownCoordinator {
+    requested: watch::Sender<bool>,
+    votes_sender_stopped: Notify,
+    in_flight_txs: Arc<AtomicUsize>,
+    servers: Mutex<Vec<ServerHandle>>,
+}
+
+/// Marks a transaction as in flight until dropped.
+pub struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> ShutdownCoordinator {
+        ShutdownCoordinator {
+            requested: watch::Sender::new(false),
+            votes_sender_stopped: Notify::new(),
+            in_flight_txs: Arc::new(AtomicUsize::new(0)),
+            servers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn request(&self) {
+        self.requested.send_replace(true);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.requested.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.requested.subscribe()
+    }
+
+    pub fn notify_votes_sender_stopped(&self) {
+        self.votes_sender_stopped.notify_one();
+    }
+
+    pub fn track_in_flight_tx(&self) -> InFlightGuard {
+        self.in_flight_txs.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.in_flight_txs.clone())
+    }
+
+    pub fn in_flight_txs(&self) -> usize {
+        self.in_flight_txs.load(Ordering::SeqCst)
+    }
+
+    /// Registers an HTTP server, which should be built with `disable_signals`, to be stopped
+    /// at the end of the shutdown.
+    pub fn register_server(&self, server: ServerHandle) {
+        self.servers
+            .lock()
+            .expect("Servers lock poisoned")
+            .push(server);
+    }
+
+    async fn stop_servers(&self) {
+        let servers = std::mem::take(&mut *self.servers.lock().expect("Servers lock poisoned"));
+        for server in servers {
+            server.stop(true).await;
+        }
+    }
+
+    /// Waits until the batches of the last block are dispatched and all tracked transactions
+    /// and pending second round consensus batches are done, or until `deadline`.
+    async fn drain(&self, sequencer_state: &SequencerState, deadline: Instant) -> bool {
+        if timeout_at(deadline, self.votes_sender_stopped.notified())
+            .await
+            .is_err()
+        {
+            warn!("Timed out waiting for the last block to be dispatched");
+            return false;
+        }
+        loop {
+            let in_flight_txs = self.in_flight_txs();
+            let pending_batches = sequencer_state
+                .batches_awaiting_consensus
+                .read()
+                .await
+                .in_progress_batches
+                .len();
+            if in_flight_txs == 0 && pending_batches == 0 {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                warn!("Timed out with {in_flight_txs} transactions in flight and {pending_batches} batches awaiting consensus");
+                return false;
+            }
+            sleep(DRAIN_POLL_PERIOD).await;
+        }
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves on Ctrl-C or SIGTERM.
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut sigterm =
+            signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler!");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Ctrl-C detected"),
+            _ = sigterm.recv() => info!("SIGTERM detected"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await.unwrap();
+        info!("Ctrl-C detected");
+    }
+}
+
+/// Stops accepting reports, waits a bounded time for the current block and the
+/// transactions in flight, then flushes Kafka and persists the sequencer's state.
+pub async fn graceful_shutdown(sequencer_state: &Data<SequencerState>) {
+    let (timeout_secs, history_persistence) = {
+        let config = sequencer_state.sequencer_config.read().await;
+        (
+            config.shutdown_timeout_secs,
+            config.history_persistence.clone(),
+        )
+    };
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    info!("Shutting down gracefully, waiting up to {timeout_secs}s for in-flight work...");
+    sequencer_state.shutdown.request();
+
+    if sequencer_state
+        .shutdown
+        .drain(sequencer_state, deadline)
+        .await
+    {
+        info!("Drained all in-flight blocks and transactions");
+    }
+    sequencer_state.shutdown.stop_servers().await;
+
+    if let Some(kafka_endpoint) = &sequencer_state.kafka_endpoint {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if let Err(e) = kafka_endpoint.flush(Timeout::After(remaining.max(Duration::from_secs(1))))
+        {
+            error!("Failed to flush Kafka records: {e}");
+        }
+    }
+
+    if let Some(history_persistence) = &history_persistence {
+        persist_feed_histories(sequencer_state, history_persistence).await;
+    }
+    info!("Shutdown complete");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer_state::create_sequencer_state_from_sequencer_config;
+    use actix_web::{App, HttpServer};
+    use blocksense_config::{get_test_config_with_no_providers, AllFeedsConfig};
+
+    #[test]
+    fn test_in_flight_guards() {
+        let shutdown = ShutdownCoordinator::new();
+        assert!(!shutdown.is_requested());
+        let receiver = shutdown.subscribe();
+
+        let first = shutdown.track_in_flight_tx();
+        let second = shutdown.track_in_flight_tx();
+        assert_eq!(shutdown.in_flight_txs(), 2);
+        drop(first);
+        assert_eq!(shutdown.in_flight_txs(), 1);
+
+        shutdown.request();
+        assert!(shutdown.is_requested());
+        assert!(*receiver.borrow());
+        drop(second);
+        assert_eq!(shutdown.in_flight_txs(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_graceful_shutdown_drains_before_stopping_servers() {
+        let (sequencer_state, _, _, _, _) = create_sequencer_state_from_sequencer_config(
+            get_test_config_with_no_providers(),
+            "test_graceful_shutdown_drains_before_stopping_servers",
+            AllFeedsConfig { feeds: vec![] },
+        )
+        .await;
+        let server = HttpServer::new(App::new)
+            .workers(1)
+            .disable_signals()
+            .bind(("127.0.0.1", 0))
+            .unwrap()
+            .run();
+        sequencer_state.shutdown.register_server(server.handle());
+        let server = actix_web::rt::spawn(server);
+
+        let in_flight_tx = sequencer_state.shutdown.track_in_flight_tx();
+        let shutdown = actix_web::rt::spawn({
+            let sequencer_state = sequencer_state.clone();
+            async move { graceful_shutdown(&sequencer_state).await }
+        });
+
+        sleep(DRAIN_POLL_PERIOD * 3).await;
+        assert!(sequencer_state.shutdown.is_requested());
+        assert!(!shutdown.is_finished());
+
+        sequencer_state.shutdown.notify_votes_sender_stopped();
+        sleep(DRAIN_POLL_PERIOD * 3).await;
+        assert!(!shutdown.is_finished());
+        assert!(!server.is_finished());
+
+        drop(in_flight_tx);
+        shutdown.await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -15,7 +15,9 @@ use blocksense_config::SequencerConfig;
 use blocksense_crypto::JsonSerializableSignature;
 use blocksense_data_feeds::generate_signature::generate_signature;
 use blocksense_feed_registry::registry::await_time;
-use blocksense_feed_registry::types::{DataFeedPayload, FeedType, PayloadMetaData};
+use blocksense_feed_registry::types::{
+    DataFeedPayload, FeedType, PayloadMetaData, ReportSigningScheme,
+};
 use curl::easy::Handler;
 use curl::easy::WriteError;
 use curl::easy::{Easy, Easy2};
@@ -405,7 +407,15 @@ async fn main() -> Result<()> {
 
         let result = Ok(FeedType::Numerical(REPORT_VAL));
         let (id, key) = REPORTERS_INFO[0];
-        let signature = generate_signature(key, FEED_ID, timestamp, &result).unwrap();
+        let signature = generate_signature(
+            key,
+            ReportSigningScheme::V0,
+            "",
+            FEED_ID,
+            timestamp,
+            &result,
+        )
+        .unwrap();
 
         let payload = DataFeedPayload {
             payload_metadata: PayloadMetaData {
@@ -413,6 +423,7 @@ async fn main() -> Result<()> {
                 feed_id: FEED_ID.to_string(),
                 timestamp,
                 signature: JsonSerializableSignature { sig: signature },
+                signing_scheme: ReportSigningScheme::V0,
             },
             result,
         };
@@ -463,8 +474,17 @@ async fn main() -> Result<()> {
                     feed_id: FEED_ID.to_string(),
                     timestamp,
                     signature: JsonSerializableSignature {
-                        sig: generate_signature(key, FEED_ID, timestamp, &result).unwrap(),
+                        sig: generate_signature(
+                            key,
+                            ReportSigningScheme::V0,
+                            "",
+                            FEED_ID,
+                            timestamp,
+                            &result,
+                        )
+                        .unwrap(),
                     },
+                    signing_scheme: ReportSigningScheme::V0,
                 },
                 result,
             })
@@ -505,6 +525,8 @@ async fn main() -> Result<()> {
                     signature: JsonSerializableSignature {
                         sig: generate_signature(
                             key,
+                            ReportSigningScheme::V0,
+                            "",
                             FEED_ID,
                             timestamp,
                             // This will cause a corrupted signature on the second iteration,
@@ -513,6 +535,7 @@ async fn main() -> Result<()> {
                         )
                         .unwrap(),
                     },
+                    signing_scheme: ReportSigningScheme::V0,
                 },
                 result: Ok(FeedType::Numerical(CORRECT_AND_WRONG_VALS[i])),
             })
@@ -51,7 +51,11 @@ use blocksense_data_feeds::{
 };
 use blocksense_feed_registry::{
     registry::SlotTimeTracker,
-    types::{DataFeedPayload, FeedError, FeedType, PayloadMetaData, Repeatability},
+    reports_encoding::{encode_reports_batch, REPORTS_BATCH_CONTENT_TYPE},
+    types::{
+        DataFeedPayload, Decimal, FeedError, FeedType, PayloadMetaData, Repeatability,
+        ReportSigningScheme, StructField,
+    },
 };
 use blocksense_feeds_processing::utils::validate;
 use blocksense_metrics::{
@@ -103,6 +107,8 @@ pub struct OracleTrigger {
     secret_key: String,
     second_consensus_secret_key: String,
     reporter_id: u64,
+    json_reports: bool,
+    deployment_id: Option<String>,
     queue_components: HashMap<String, Component>,
 }
 
@@ -123,6 +129,11 @@ struct TriggerMetadata {
     secret_key: Option<String>,
     second_consensus_secret_key: Option<String>,
     reporter_id: Option<u64>,
+    /// Sends report batches as JSON instead of the binary encoding, e.g. for debugging.
+    json_reports: Option<bool>,
+    /// Deployment the reports are signed for with `ReportSigningScheme::V1`. Reports are
+    /// signed with the legacy `V0` scheme if it is not set.
+    deployment_id: Option<String>,
 }
 
 #[derive(Clone, Eq, Debug, Default, Deserialize, Serialize)]
@@ -222,6 +233,11 @@ impl TriggerExecutor for OracleTrigger {
             .second_consensus_secret_key
             .expect("Second consensus secret key is not provided");
         let reporter_id = metadata.reporter_id.expect("Reporter ID is not provided");
+        let json_reports = metadata.json_reports.unwrap_or(false);
+        let deployment_id = metadata.deployment_id;
+        if deployment_id.is_none() {
+            tracing::warn!("No deployment_id configured, signing reports with the legacy scheme");
+        }
         // TODO(adikov) There is a specific case in which one reporter receives task to report multiple
         // data feeds which are gathered from one wasm component. For example -
         // USD/BTC and USD/ETH. In that case we need to optimize calling the component once and
@@ -259,6 +275,8 @@ impl TriggerExecutor for OracleTrigger {
             secret_key,
             second_consensus_secret_key,
             reporter_id,
+            json_reports,
+            deployment_id,
             queue_components,
         })
     }
@@ -360,6 +378,8 @@ impl TriggerExecutor for OracleTrigger {
             &self.secret_key,
             &self.second_consensus_secret_key,
             self.reporter_id,
+            self.json_reports,
+            self.deployment_id.clone(),
         );
         loops.append(&mut manager);
 
@@ -680,6 +700,8 @@ impl OracleTrigger {
         secret_key: &str,
         second_consensus_secret_key: &str,
         reporter_id: u64,
+        json_reports: bool,
+        deployment_id: Option<String>,
     ) -> Vec<JoinHandle<TerminationReason>> {
         let process_payload_future = Self::process_payload(
             payload_rx,
@@ -687,6 +709,8 @@ impl OracleTrigger {
             sequencer_post_batch_url.to_owned(),
             secret_key.to_owned(),
             reporter_id,
+            json_reports,
+            deployment_id,
         );
 
         let process_aggregated_consensus_future = Self::process_aggregated_consensus(
@@ -710,8 +734,14 @@ impl OracleTrigger {
         sequencer_url: Url,
         secret_key: String,
         reporter_id: u64,
+        json_reports: bool,
+        deployment_id: Option<String>,
     ) -> TerminationReason {
         tracing::trace!("Task sender to sequencer started");
+        let (signing_scheme, deployment_id) = match deployment_id {
+            Some(deployment_id) => (ReportSigningScheme::V1, deployment_id),
+            None => (ReportSigningScheme::V0, String::new()),
+        };
         while let Some((_component_id, payload)) = rx.recv().await {
             tracing::trace!(
                 "Sender to sequencer received payload of size {}",
@@ -723,6 +753,27 @@ impl OracleTrigger {
                 let result = match value {
                     oracle::DataFeedResultValue::Numerical(value) => Ok(FeedType::Numerical(value)),
                     oracle::DataFeedResultValue::Text(value) => Ok(FeedType::Text(value)),
+                    oracle::DataFeedResultValue::Decimal(value) => {
+                        decimal_from_wit(&value).map(FeedType::Decimal)
+                    }
+                    oracle::DataFeedResultValue::Struct(fields) => fields
+                        .into_iter()
+                        .map(|field| {
+                            let value = match field.value {
+                                oracle::StructFieldValue::Numerical(value) => {
+                                    FeedType::Numerical(value)
+                                }
+                                oracle::StructFieldValue::Decimal(value) => {
+                                    FeedType::Decimal(decimal_from_wit(&value)?)
+                                }
+                            };
+                            Ok(StructField {
+                                name: field.name,
+                                value,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(FeedType::Struct),
                     oracle::DataFeedResultValue::Error(error_string) => {
                         Err(FeedError::APIError(error_string))
                     }
@@ -733,8 +784,20 @@ impl OracleTrigger {
                     }
                 };
 
-                let signature =
-                    generate_signature(&secret_key, id.as_str(), timestamp, &result).unwrap();
+                let signature = match generate_signature(
+                    &secret_key,
+                    signing_scheme,
+                    &deployment_id,
+                    id.as_str(),
+                    timestamp,
+                    &result,
+                ) {
+                    Ok(signature) => signature,
+                    Err(e) => {
+                        tracing::warn!("Not reporting feed id {id}: {e}");
+                        continue;
+                    }
+                };
 
                 batch_payload.push(DataFeedPayload {
                     payload_metadata: PayloadMetaData {
@@ -742,6 +805,7 @@ impl OracleTrigger {
                         feed_id: id,
                         timestamp,
                         signature: JsonSerializableSignature { sig: signature },
+                        signing_scheme,
                     },
                     result,
                 });
@@ -754,13 +818,24 @@ impl OracleTrigger {
                 sequencer_url.clone(),
                 batch_payload.len()
             );
+            let encoded_batch = if json_reports {
+                None
+            } else {
+                encode_reports_batch(&batch_payload)
+                    .inspect_err(|e| {
+                        tracing::warn!("Sending batch as JSON, could not encode it: {e}")
+                    })
+                    .ok()
+            };
             let client = reqwest::Client::new();
-            match client
-                .post(sequencer_url.clone())
-                .json(&batch_payload)
-                .send()
-                .await
-            {
+            let request = client.post(sequencer_url.clone());
+            let request = match encoded_batch {
+                Some(encoded_batch) => request
+                    .header(reqwest::header::CONTENT_TYPE, REPORTS_BATCH_CONTENT_TYPE)
+                    .body(encoded_batch),
+                None => request.json(&batch_payload),
+            };
+            match request.send().await {
                 Ok(res) => {
                     let status = res.status();
                     let contents = res.text().await.unwrap();
@@ -1032,6 +1107,16 @@ impl OutboundWasiHttpHandler for HttpRuntimeData {
     }
 }
 
+fn decimal_from_wit(value: &oracle::Decimal) -> Result<Decimal, FeedError> {
+    match value.mantissa.parse::<i128>() {
+        Ok(mantissa) => Ok(Decimal::new(mantissa, value.scale)),
+        Err(e) => Err(FeedError::APIError(format!(
+            "Invalid decimal mantissa {}: {e}",
+            value.mantissa
+        ))),
+    }
+}
+
 fn update_latest_votes(
     latest_votes: &mut HashMap<u32, VotedFeedUpdate>,
     batch: Vec<DataFeedPayload>,
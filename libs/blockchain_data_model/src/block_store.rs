@@ -0,0 +1,586 @@
+use crate::proofs::{prove_feed_update, FeedUpdateProof};
+use crate::{
+    in_mem_db::InMemDb, AssetFeedUpdate, BlockFeedConfig, BlockHeader, BlockStructField,
+    FeedActions, FeedUpdates, HashType, DATA_CHUNK_SIZE, MAX_ASSET_FEED_UPDATES_IN_BLOCK,
+    MAX_FEED_DATA_SIZE, MAX_FEED_ID_TO_DELETE_IN_BLOCK, MAX_NEW_FEEDS_IN_BLOCK,
+    MAX_UPDATED_FEEDS_IN_BLOCK,
+};
+use anyhow::{Context, Result};
+use blocksense_utils::constants::MAX_STRUCT_FIELDS;
+use blocksense_utils::time::current_unix_time;
+use hex_literal::hex;
+use ssz_rs::prelude::List;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+pub const GENESIS_HASH: HashType =
+    hex!("ec59d3d7860eadc9207b6ccf7c897b23b6b8e82d3d4b80212dfebc15a6b16b17");
+
+/// Start of every `FileBlockStore` file, followed by its format version as a big endian `u32`.
+const BLOCK_STORE_MAGIC: &[u8; 4] = b"BSBS";
+/// Version of the layout of the blocks in a `FileBlockStore`, bumped whenever the layout of
+/// the stored blocks changes. Files of other versions are refused rather than misread.
+pub const BLOCK_STORE_FORMAT_VERSION: u32 = 1;
+const BLOCK_STORE_HEADER_LEN: usize = BLOCK_STORE_MAGIC.len() + 4;
+
+/// Checks that the feed actions are the ones committed to in the block header.
+pub fn check_feed_actions(header: &BlockHeader, add_remove_feeds: &FeedActions) -> Result<()> {
+    let add_remove_feeds_merkle_root =
+        InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut add_remove_feeds.clone())?)?;
+    if header.add_remove_feeds_merkle_root != add_remove_feeds_merkle_root {
+        anyhow::bail!("Feed actions do not match the merkle root in the block header!");
+    }
+    Ok(())
+}
+
+/// Storage for the chain of blocks built by the sequencers.
+pub trait BlockStore: Send + Sync {
+    fn get_latest_block_height(&self) -> u64;
+
+    fn get_block_header_by_height(&self, block_height: u64) -> Option<&BlockHeader>;
+
+    fn get_block_header_by_hash(&self, header_hash: HashType) -> Option<&BlockHeader>;
+
+    /// Feed actions by their merkle root, as referred by `add_remove_feeds_merkle_root`.
+    fn get_feed_actions(&self, merkle_root: &HashType) -> Option<&FeedActions>;
+
+    /// Feed updates by their merkle root, as referred by `feed_updates_merkle_root`.
+    fn get_feed_updates(&self, merkle_root: &HashType) -> Option<&FeedUpdates>;
+
+    /// Appends a block to the tip of the chain, after it passes `check_next_block`.
+    fn add_next_block(
+        &mut self,
+        header: BlockHeader,
+        add_remove_feeds: FeedActions,
+        feed_updates: FeedUpdates,
+    ) -> Result<()>;
+
+    /// Returns the header of the block at `block_height` along with the update of `feed_id`
+    /// in it and the proof of its inclusion, or `None` if there is no such update.
+    fn prove_feed_update(
+        &self,
+        block_height: u64,
+        feed_id: u32,
+    ) -> Result<Option<(BlockHeader, AssetFeedUpdate, FeedUpdateProof)>> {
+        let Some(header) = self.get_block_header_by_height(block_height) else {
+            return Ok(None);
+        };
+        let feed_updates = self
+            .get_feed_updates(&header.feed_updates_merkle_root)
+            .context("Missing feed updates of block in blockchain!")?;
+        Ok(prove_feed_update(feed_updates, feed_id)?
+            .map(|(update, proof)| (header.clone(), update, proof)))
+    }
+
+    /// Checks that the block can be appended to the tip of the chain.
+    fn check_next_block(
+        &self,
+        header: &BlockHeader,
+        add_remove_feeds: &FeedActions,
+        feed_updates: &FeedUpdates,
+    ) -> Result<()> {
+        let latest_block_height = self.get_latest_block_height();
+        let block_height = header.block_height;
+        if block_height <= latest_block_height {
+            anyhow::bail!(
+                "Block height not as expected, got {}, expected higher than {}!",
+                block_height,
+                latest_block_height
+            );
+        }
+
+        if latest_block_height == 0 && header.prev_block_hash != GENESIS_HASH {
+            anyhow::bail!("First block does not refer genesis in blockchain!");
+        }
+
+        if latest_block_height != 0 {
+            let mut latest_block_header = self
+                .get_block_header_by_height(latest_block_height)
+                .context("Missing header of latest block in blockchain!")?
+                .clone();
+            let latest_block_header_hash =
+                InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut latest_block_header)?)?;
+            if header.prev_block_hash != latest_block_header_hash {
+                anyhow::bail!("New block does not refer latest in blockchain!");
+            }
+        }
+
+        check_feed_actions(header, add_remove_feeds)?;
+
+        let feed_updates_merkle_root =
+            InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut feed_updates.clone())?)?;
+        if header.feed_updates_merkle_root != feed_updates_merkle_root {
+            anyhow::bail!("Feed updates do not match the merkle root in the block header!");
+        }
+
+        Ok(())
+    }
+
+    fn create_new_block(
+        &self,
+        sequencer_id: u64,
+        new_block_height: u64,
+        new_feeds_in_block: Vec<BlockFeedConfig>,
+        feed_ids_to_delete_in_block: Vec<u32>,
+        updated_feeds_in_block: Vec<BlockFeedConfig>,
+        feed_updates_in_block: Vec<AssetFeedUpdate>,
+    ) -> Result<(BlockHeader, FeedActions, FeedUpdates)> {
+        // Populate new and to be removed feeds in block:
+        let mut add_remove_feeds = FeedActions::default();
+
+        if new_feeds_in_block.len() > MAX_NEW_FEEDS_IN_BLOCK {
+            error!("Trying to insert in block more newly registered feeds {} than supported {}. All above supported limit will be dropped!", new_feeds_in_block.len(), MAX_NEW_FEEDS_IN_BLOCK)
+        }
+
+        let mut iter = new_feeds_in_block.into_iter();
+        for i in 0..add_remove_feeds.new_feeds.len() {
+            if let Some(feed) = iter.next() {
+                add_remove_feeds.new_feeds[i] = Some(feed);
+            } else {
+                add_remove_feeds.new_feeds[i] = None;
+            }
+        }
+
+        if feed_ids_to_delete_in_block.len() > MAX_FEED_ID_TO_DELETE_IN_BLOCK {
+            error!("Trying to insert in block more to be deleted feeds {} than supported {}. All above supported limit will be dropped!", feed_ids_to_delete_in_block.len(), MAX_FEED_ID_TO_DELETE_IN_BLOCK)
+        }
+
+        let mut iter = feed_ids_to_delete_in_block.into_iter();
+        for i in 0..add_remove_feeds.feed_ids_to_rm.len() {
+            if let Some(feed_id) = iter.next() {
+                add_remove_feeds.feed_ids_to_rm[i] = Some(feed_id);
+            } else {
+                add_remove_feeds.feed_ids_to_rm[i] = None;
+            }
+        }
+
+        if updated_feeds_in_block.len() > MAX_UPDATED_FEEDS_IN_BLOCK {
+            error!("Trying to insert in block more updated feeds {} than supported {}. All above supported limit will be dropped!", updated_feeds_in_block.len(), MAX_UPDATED_FEEDS_IN_BLOCK)
+        }
+
+        let mut iter = updated_feeds_in_block.into_iter();
+        for updated_feed in add_remove_feeds.updated_feeds.iter_mut() {
+            *updated_feed = iter.next();
+        }
+
+        if feed_updates_in_block.len() > MAX_ASSET_FEED_UPDATES_IN_BLOCK {
+            error!("Trying to insert in block more feed updates {} than supported {}. All above supported limit will be dropped!", feed_updates_in_block.len(), MAX_ASSET_FEED_UPDATES_IN_BLOCK)
+        }
+
+        let mut feed_updates = FeedUpdates {
+            block_height: new_block_height,
+            ..Default::default()
+        };
+        let mut iter = feed_updates_in_block.into_iter();
+        for chunk in feed_updates.updates.iter_mut() {
+            for update in chunk.iter_mut() {
+                *update = iter.next();
+            }
+        }
+
+        let mut block_header = BlockHeader::default();
+        let latest_height = self.get_latest_block_height();
+        block_header.timestamp = current_unix_time() as u64;
+        block_header.block_height = new_block_height;
+        block_header.issuer_id = sequencer_id;
+        add_remove_feeds.block_height = new_block_height;
+        if latest_height == 0 {
+            block_header.prev_block_hash = GENESIS_HASH;
+        } else {
+            let mut latest_header = self
+                .get_block_header_by_height(latest_height)
+                .context("Missing header of latest block in blockchain!")?
+                .clone();
+            let latest_hash = InMemDb::calc_merkle_root(&mut latest_header)?;
+            block_header.prev_block_hash = InMemDb::node_to_hash(latest_hash)?;
+        }
+
+        let add_remove_feeds_merkle_root = InMemDb::calc_merkle_root(&mut add_remove_feeds)?;
+        block_header.add_remove_feeds_merkle_root =
+            InMemDb::node_to_hash(add_remove_feeds_merkle_root)?;
+
+        let feed_updates_merkle_root = InMemDb::calc_merkle_root(&mut feed_updates)?;
+        block_header.feed_updates_merkle_root = InMemDb::node_to_hash(feed_updates_merkle_root)?;
+
+        Ok((block_header, add_remove_feeds, feed_updates))
+    }
+}
+
+/// Keeps the chain in memory like `InMemDb`, and also appends every block to a file, from
+/// which the chain is rebuilt on startup. The file starts with `BLOCK_STORE_MAGIC` and the
+/// format version. Each block is stored as its SSZ serialized header, feed actions and feed
+/// updates, each prefixed by its length as a big endian `u32`.
+pub struct FileBlockStore {
+    path: PathBuf,
+    file: File,
+    blocks: InMemDb,
+}
+
+impl FileBlockStore {
+    /// Opens the chain stored at `path`, creating an empty one if the file does not exist.
+    /// Fails if the file is of another format version. Every stored block is validated to
+    /// extend the one before it. An incomplete block at the end of the file, left by a crash
+    /// mid-write, is dropped. Trailing bytes that do not fit in a block mean that the file is
+    /// corrupted, and are refused rather than dropped.
+    pub fn open(path: &Path) -> Result<FileBlockStore> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("Could not open block store {path:?}"))?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+
+        let header = block_store_header();
+        if contents.len() < BLOCK_STORE_HEADER_LEN && header.starts_with(&contents) {
+            // A new file, or one whose header was cut short by a crash.
+            file.set_len(0)?;
+            file.write_all(&header)?;
+            file.sync_data()?;
+            contents = header.clone();
+        }
+        if !contents.starts_with(BLOCK_STORE_MAGIC) || contents.len() < BLOCK_STORE_HEADER_LEN {
+            anyhow::bail!(
+                "{path:?} is not a block store of format version {BLOCK_STORE_FORMAT_VERSION}, it may have been written by an older sequencer"
+            );
+        }
+        if contents[..BLOCK_STORE_HEADER_LEN] != header {
+            let version = u32::from_be_bytes(
+                contents[BLOCK_STORE_MAGIC.len()..BLOCK_STORE_HEADER_LEN]
+                    .try_into()
+                    .expect("version is 4 bytes"),
+            );
+            anyhow::bail!(
+                "Block store {path:?} is of format version {version}, expected {BLOCK_STORE_FORMAT_VERSION}"
+            );
+        }
+
+        let mut blocks = InMemDb::new();
+        let mut offset = BLOCK_STORE_HEADER_LEN;
+        let mut count = 0;
+        while let Some((header, add_remove_feeds, feed_updates, len)) =
+            decode_block(&contents[offset..])?
+        {
+            blocks
+                .add_next_block(header, add_remove_feeds, feed_updates)
+                .with_context(|| format!("Invalid block at offset {offset} in {path:?}"))?;
+            offset += len;
+            count += 1;
+        }
+        if offset < contents.len() {
+            let tail_len = contents.len() - offset;
+            if tail_len > max_encoded_block_len()? {
+                anyhow::bail!(
+                    "Block store {path:?} is corrupted, it ends with {tail_len} bytes at offset {offset} which are not a block"
+                );
+            }
+            warn!("Dropping {tail_len} bytes of an incomplete block at the end of {path:?}");
+            file.set_len(offset as u64)?;
+        }
+        info!(
+            "Loaded {count} blocks from {path:?}, latest block height is {}",
+            blocks.get_latest_block_height()
+        );
+
+        Ok(FileBlockStore {
+            path: path.to_path_buf(),
+            file,
+            blocks,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl BlockStore for FileBlockStore {
+    fn get_latest_block_height(&self) -> u64 {
+        self.blocks.get_latest_block_height()
+    }
+
+    fn get_block_header_by_height(&self, block_height: u64) -> Option<&BlockHeader> {
+        self.blocks.get_block_header_by_height(block_height)
+    }
+
+    fn get_block_header_by_hash(&self, header_hash: HashType) -> Option<&BlockHeader> {
+        self.blocks.get_block_header_by_hash(header_hash)
+    }
+
+    fn get_feed_actions(&self, merkle_root: &HashType) -> Option<&FeedActions> {
+        self.blocks.get_feed_actions(merkle_root)
+    }
+
+    fn get_feed_updates(&self, merkle_root: &HashType) -> Option<&FeedUpdates> {
+        self.blocks.get_feed_updates(merkle_root)
+    }
+
+    fn add_next_block(
+        &mut self,
+        header: BlockHeader,
+        add_remove_feeds: FeedActions,
+        feed_updates: FeedUpdates,
+    ) -> Result<()> {
+        self.blocks
+            .check_next_block(&header, &add_remove_feeds, &feed_updates)?;
+
+        // The block is only added to memory once it is durably stored.
+        let encoded = encode_block(&header, &add_remove_feeds, &feed_updates)?;
+        let len = self.file.metadata()?.len();
+        if let Err(e) = self
+            .file
+            .write_all(&encoded)
+            .and_then(|_| self.file.sync_data())
+        {
+            // Drop what was written of the block, so that the file still ends with a whole block.
+            self.file
+                .set_len(len)
+                .with_context(|| format!("Could not drop partially written block: {e}"))?;
+            return Err(e.into());
+        }
+
+        self.blocks
+            .add_next_block(header, add_remove_feeds, feed_updates)
+    }
+}
+
+fn block_store_header() -> Vec<u8> {
+    let mut header = BLOCK_STORE_MAGIC.to_vec();
+    header.extend(BLOCK_STORE_FORMAT_VERSION.to_be_bytes());
+    header
+}
+
+fn encode_block(
+    header: &BlockHeader,
+    add_remove_feeds: &FeedActions,
+    feed_updates: &FeedUpdates,
+) -> Result<Vec<u8>> {
+    let mut encoded = Vec::new();
+    for part in [
+        header.clone().serialize()?,
+        add_remove_feeds.clone().serialize()?,
+        feed_updates.clone().serialize()?,
+    ] {
+        encoded.extend((part.len() as u32).to_be_bytes());
+        encoded.extend(part);
+    }
+    Ok(encoded)
+}
+
+/// Length of the largest block that can be stored, which bounds what a crash mid-write can leave
+/// at the end of the file.
+fn max_encoded_block_len() -> Result<usize> {
+    let struct_fields = List::try_from(vec![BlockStructField::default(); MAX_STRUCT_FIELDS])
+        .map_err(|_| anyhow::anyhow!("Could not create struct fields"))?;
+    let feed_config = BlockFeedConfig {
+        resources: [Some(Default::default()); DATA_CHUNK_SIZE],
+        always_publish_heartbeat_ms: Some(0),
+        struct_fields,
+        ..Default::default()
+    };
+    let add_remove_feeds = FeedActions {
+        block_height: 0,
+        new_feeds: std::array::from_fn(|_| Some(feed_config.clone())),
+        feed_ids_to_rm: [Some(0); MAX_FEED_ID_TO_DELETE_IN_BLOCK],
+        updated_feeds: std::array::from_fn(|_| Some(feed_config.clone())),
+    };
+    let update = AssetFeedUpdate::new(0, vec![0; MAX_FEED_DATA_SIZE])?;
+    let feed_updates = FeedUpdates {
+        block_height: 0,
+        updates: std::array::from_fn(|_| std::array::from_fn(|_| Some(update.clone()))),
+    };
+    Ok(encode_block(&BlockHeader::default(), &add_remove_feeds, &feed_updates)?.len())
+}
+
+/// Splits a length prefixed part from the front of `bytes`, or returns `None` if `bytes`
+/// ends before the part does.
+fn split_part(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len_bytes: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    let part = bytes.get(4..4 + len)?;
+    Some((part, &bytes[4 + len..]))
+}
+
+/// Decodes the block at the front of `bytes`, returning it along with the number of bytes
+/// it takes, or `None` if `bytes` ends before the block does.
+#[allow(clippy::type_complexity)]
+fn decode_block(bytes: &[u8]) -> Result<Option<(BlockHeader, FeedActions, FeedUpdates, usize)>> {
+    let Some((header, rest)) = split_part(bytes) else {
+        return Ok(None);
+    };
+    let Some((add_remove_feeds, rest)) = split_part(rest) else {
+        return Ok(None);
+    };
+    let Some((feed_updates, rest)) = split_part(rest) else {
+        return Ok(None);
+    };
+    Ok(Some((
+        BlockHeader::deserialize(header)?,
+        FeedActions::deserialize(add_remove_feeds)?,
+        FeedUpdates::deserialize(feed_updates)?,
+        bytes.len() - rest.len(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_store_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "block_store_{name}_{}/blocks.bin",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn add_blocks(store: &mut dyn BlockStore, heights: &[u64]) {
+        for height in heights {
+            let update = AssetFeedUpdate::new(*height as u32, vec![*height as u8; 32]).unwrap();
+            let (header, add_remove_feeds, feed_updates) = store
+                .create_new_block(
+                    1,
+                    *height,
+                    vec![],
+                    vec![*height as u32],
+                    vec![],
+                    vec![update],
+                )
+                .unwrap();
+            store
+                .add_next_block(header, add_remove_feeds, feed_updates)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_file_block_store_survives_reopen() {
+        let path = test_store_path("reopen");
+        let mut store = FileBlockStore::open(&path).unwrap();
+        add_blocks(&mut store, &[1, 2, 5]);
+        let tip = store.get_block_header_by_height(5).unwrap().clone();
+        drop(store);
+
+        let mut store = FileBlockStore::open(&path).unwrap();
+        assert_eq!(store.get_latest_block_height(), 5);
+        assert_eq!(store.get_block_header_by_height(5), Some(&tip));
+        assert!(store.get_block_header_by_height(3).is_none());
+        let add_remove_feeds = store
+            .get_feed_actions(&tip.add_remove_feeds_merkle_root)
+            .unwrap();
+        assert_eq!(add_remove_feeds.feed_ids_to_rm[0], Some(5));
+        let feed_updates: Vec<&AssetFeedUpdate> = store
+            .get_feed_updates(&tip.feed_updates_merkle_root)
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(feed_updates.len(), 1);
+        assert_eq!(feed_updates[0].feed_id(), 5);
+        assert_eq!(feed_updates[0].feed_data.to_vec(), vec![5u8; 32]);
+
+        // New blocks keep extending the restored chain.
+        add_blocks(&mut store, &[6]);
+        let (header, add_remove_feeds, feed_updates) = store
+            .create_new_block(1, 6, vec![], vec![], vec![], vec![])
+            .unwrap();
+        assert!(store
+            .add_next_block(header, add_remove_feeds, feed_updates)
+            .is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_block_store_rejects_mismatching_feed_updates() {
+        let mut store = InMemDb::new();
+        let update = AssetFeedUpdate::new(1, vec![1; 32]).unwrap();
+        let (header, add_remove_feeds, mut feed_updates) = store
+            .create_new_block(1, 1, vec![], vec![], vec![], vec![update])
+            .unwrap();
+        feed_updates.updates[0][0] = Some(AssetFeedUpdate::new(1, vec![2; 32]).unwrap());
+        assert!(store
+            .add_next_block(header, add_remove_feeds, feed_updates)
+            .is_err());
+    }
+
+    #[test]
+    fn test_file_block_store_drops_incomplete_block() {
+        let path = test_store_path("incomplete");
+        let mut store = FileBlockStore::open(&path).unwrap();
+        add_blocks(&mut store, &[1, 2]);
+        drop(store);
+
+        let len = fs::metadata(&path).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(len - 3).unwrap();
+
+        let store = FileBlockStore::open(&path).unwrap();
+        assert_eq!(store.get_latest_block_height(), 1);
+        assert!(fs::metadata(&path).unwrap().len() < len - 3);
+        drop(store);
+
+        // More trailing bytes than any block takes are not left by a crash mid-write.
+        let len = fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&vec![0xff; max_encoded_block_len().unwrap() + 1])
+            .unwrap();
+        drop(file);
+        assert!(FileBlockStore::open(&path).is_err());
+        assert!(fs::metadata(&path).unwrap().len() > len);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_block_store_rejects_other_format_versions() {
+        let path = test_store_path("version");
+        let mut store = FileBlockStore::open(&path).unwrap();
+        add_blocks(&mut store, &[1]);
+        drop(store);
+        let contents = fs::read(&path).unwrap();
+        assert_eq!(contents[..BLOCK_STORE_HEADER_LEN], block_store_header());
+
+        let mut other_version = contents.clone();
+        other_version[BLOCK_STORE_HEADER_LEN - 1] += 1;
+        fs::write(&path, &other_version).unwrap();
+        assert!(FileBlockStore::open(&path).is_err());
+
+        // Files written before the format was versioned start with the first block.
+        fs::write(&path, &contents[BLOCK_STORE_HEADER_LEN..]).unwrap();
+        assert!(FileBlockStore::open(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_block_store_rejects_broken_chain() {
+        let path = test_store_path("broken");
+        let mut store = FileBlockStore::open(&path).unwrap();
+        add_blocks(&mut store, &[1]);
+        let (mut header, add_remove_feeds, feed_updates) = store
+            .create_new_block(1, 2, vec![], vec![], vec![], vec![])
+            .unwrap();
+        header.prev_block_hash = GENESIS_HASH;
+        assert!(store
+            .add_next_block(
+                header.clone(),
+                add_remove_feeds.clone(),
+                feed_updates.clone()
+            )
+            .is_err());
+
+        // Bypass the checks to simulate a tampered file.
+        store
+            .file
+            .write_all(&encode_block(&header, &add_remove_feeds, &feed_updates).unwrap())
+            .unwrap();
+        drop(store);
+
+        assert!(FileBlockStore::open(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,11 +1,14 @@
+pub mod block_store;
 pub mod in_mem_db;
+pub mod proofs;
 
 use anyhow::Result;
+use blocksense_utils::constants::MAX_STRUCT_FIELDS;
 use ssz_rs::prelude::*;
 
 pub const DATA_CHUNK_SIZE: usize = 32;
 pub const KEY_CHUNK_SIZE: usize = 32;
-type HashType = [u8; DATA_CHUNK_SIZE];
+pub type HashType = [u8; DATA_CHUNK_SIZE];
 pub type FeedIdChunk = [u8; KEY_CHUNK_SIZE];
 pub type DataChunk = [u8; DATA_CHUNK_SIZE];
 pub type Resources = [Option<DataChunk>; DATA_CHUNK_SIZE];
@@ -16,6 +19,15 @@ pub struct AssetPair {
     pub quote: DataChunk,
 }
 
+/// A field of the values of struct feeds. An empty `aggregation` stands for the aggregation of
+/// the feed.
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+pub struct BlockStructField {
+    pub name: DataChunk,
+    pub value_type: DataChunk,
+    pub aggregation: DataChunk,
+}
+
 #[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
 pub struct BlockFeedConfig {
     pub id: u32,
@@ -35,17 +47,69 @@ pub struct BlockFeedConfig {
     pub value_type: DataChunk,
     pub aggregate_type: DataChunk,
     pub stride: u16,
+    pub signed_values: bool,
+    pub struct_fields: List<BlockStructField, MAX_STRUCT_FIELDS>,
+    pub category: DataChunk,
+    /// Empty if the feed is not restricted to market hours.
+    pub market_hours: DataChunk,
 }
 
+/// A published feed value, encoded the way it is written to the feeds' contract.
 #[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
 pub struct AssetFeedUpdate {
-    id: FeedIdChunk,
-    feed_data: DataChunk,
+    /// The feed ID as a big endian number.
+    pub id: FeedIdChunk,
+    pub feed_data: List<u8, MAX_FEED_DATA_SIZE>,
+}
+
+impl AssetFeedUpdate {
+    pub fn new(feed_id: u32, feed_data: Vec<u8>) -> Result<AssetFeedUpdate> {
+        let mut id = FeedIdChunk::default();
+        id[KEY_CHUNK_SIZE - 4..].copy_from_slice(&feed_id.to_be_bytes());
+        let len = feed_data.len();
+        let feed_data = List::try_from(feed_data).map_err(|_| {
+            anyhow::anyhow!(
+                "Feed data of {len} bytes for feed {feed_id} exceeds the limit of {MAX_FEED_DATA_SIZE}"
+            )
+        })?;
+        Ok(AssetFeedUpdate { id, feed_data })
+    }
+
+    pub fn feed_id(&self) -> u32 {
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&self.id[KEY_CHUNK_SIZE - 4..]);
+        u32::from_be_bytes(id)
+    }
 }
 
 pub type FeedUpdatesChunk = [Option<AssetFeedUpdate>; 32];
 pub type FeedUpdatesInBlock = [FeedUpdatesChunk; 16];
 
+/// The feed values published with a block. The key under which they are stored is their
+/// Merkle root, which is in the block header.
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+pub struct FeedUpdates {
+    pub block_height: u64,
+    pub updates: FeedUpdatesInBlock,
+}
+
+impl FeedUpdates {
+    pub fn serialize(&mut self) -> Result<Vec<u8>> {
+        let result = ssz_rs::serialize(self)?;
+        Ok(result)
+    }
+
+    pub fn deserialize(serialized: &[u8]) -> Result<FeedUpdates> {
+        let result = ssz_rs::deserialize(serialized)?;
+        Ok(result)
+    }
+
+    /// All updates in the order they were added to the block.
+    pub fn iter(&self) -> impl Iterator<Item = &AssetFeedUpdate> {
+        self.updates.iter().flatten().flatten()
+    }
+}
+
 #[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
 pub struct BlockHeader {
     pub issuer_id: u64,
@@ -53,6 +117,7 @@ pub struct BlockHeader {
     pub timestamp: u64,
     pub prev_block_hash: HashType,
     pub add_remove_feeds_merkle_root: HashType,
+    pub feed_updates_merkle_root: HashType,
 }
 
 impl BlockHeader {
@@ -72,6 +137,8 @@ pub struct FeedActions {
     pub block_height: u64,
     pub new_feeds: [Option<BlockFeedConfig>; MAX_NEW_FEEDS_IN_BLOCK],
     pub feed_ids_to_rm: [Option<u32>; MAX_FEED_ID_TO_DELETE_IN_BLOCK],
+    /// New configs of registered feeds, which keep their history and round counters.
+    pub updated_feeds: [Option<BlockFeedConfig>; MAX_UPDATED_FEEDS_IN_BLOCK],
 }
 
 impl FeedActions {
@@ -91,3 +158,6 @@ pub const MAX_ASSET_FEED_UPDATES_IN_BLOCK: usize =
 
 pub const MAX_NEW_FEEDS_IN_BLOCK: usize = 32;
 pub const MAX_FEED_ID_TO_DELETE_IN_BLOCK: usize = 32;
+pub const MAX_UPDATED_FEEDS_IN_BLOCK: usize = 32;
+/// Enough for a feed with a stride of 10, e.g. a struct of 1024 numerical fields.
+pub const MAX_FEED_DATA_SIZE: usize = 32 * 1024;
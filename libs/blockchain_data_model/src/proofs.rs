@@ -0,0 +1,155 @@
+use crate::{in_mem_db::InMemDb, AssetFeedUpdate, BlockHeader, FeedUpdates, HashType};
+use anyhow::Result;
+use ssz_rs::prelude::*;
+
+const FEED_UPDATES_CHUNK_DEPTH: usize = 5;
+const FEED_UPDATES_IN_BLOCK_DEPTH: usize = 4;
+
+/// Number of nodes in the branch of a `FeedUpdateProof`: the levels of a chunk of updates,
+/// the levels of the chunks in a block, and the level of the fields of `FeedUpdates`.
+pub const FEED_UPDATE_PROOF_DEPTH: usize =
+    FEED_UPDATES_CHUNK_DEPTH + FEED_UPDATES_IN_BLOCK_DEPTH + 1;
+
+/// SSZ Merkle proof that an update is part of the `FeedUpdates` of a block, i.e. that it
+/// hashes up to the `feed_updates_merkle_root` in the block header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedUpdateProof {
+    /// Index of the update's leaf among the `2^FEED_UPDATE_PROOF_DEPTH` leaves of the tree.
+    pub index: usize,
+    /// Sibling nodes on the path from the leaf to the root, starting at the leaf.
+    pub branch: Vec<HashType>,
+}
+
+/// Two nodes, whose hash tree root is the hash of their concatenation.
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+struct NodePair {
+    left: HashType,
+    right: HashType,
+}
+
+fn hash_pair(left: &HashType, right: &HashType) -> Result<HashType> {
+    let mut pair = NodePair {
+        left: *left,
+        right: *right,
+    };
+    InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut pair)?)
+}
+
+fn hash_tree_root(obj: &(impl SimpleSerialize + Clone)) -> Result<HashType> {
+    InMemDb::node_to_hash(InMemDb::calc_merkle_root(&mut obj.clone())?)
+}
+
+/// Merkleizes `leaves`, whose count is a power of two, returning the branch of the leaf at
+/// `index` along with the root.
+fn merkle_branch(leaves: Vec<HashType>, mut index: usize) -> Result<(Vec<HashType>, HashType)> {
+    let mut branch = Vec::new();
+    let mut layer = leaves;
+    while layer.len() > 1 {
+        branch.push(layer[index ^ 1]);
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect::<Result<_>>()?;
+        index /= 2;
+    }
+    Ok((branch, layer[0]))
+}
+
+/// Builds the proof of the update of `feed_id` in `feed_updates`, or returns `None` if the
+/// block holds no update of that feed.
+pub fn prove_feed_update(
+    feed_updates: &FeedUpdates,
+    feed_id: u32,
+) -> Result<Option<(AssetFeedUpdate, FeedUpdateProof)>> {
+    let chunk_len = 1 << FEED_UPDATES_CHUNK_DEPTH;
+    let Some(position) = feed_updates
+        .updates
+        .iter()
+        .flatten()
+        .position(|u| u.as_ref().is_some_and(|u| u.feed_id() == feed_id))
+    else {
+        return Ok(None);
+    };
+    let (chunk_index, index_in_chunk) = (position / chunk_len, position % chunk_len);
+
+    let chunk = &feed_updates.updates[chunk_index];
+    let leaves = chunk.iter().map(hash_tree_root).collect::<Result<_>>()?;
+    let (mut branch, _) = merkle_branch(leaves, index_in_chunk)?;
+
+    let chunk_roots = feed_updates
+        .updates
+        .iter()
+        .map(hash_tree_root)
+        .collect::<Result<_>>()?;
+    let (chunks_branch, updates_root) = merkle_branch(chunk_roots, chunk_index)?;
+    branch.extend(chunks_branch);
+
+    // `updates` is the second field of `FeedUpdates`, after `block_height`.
+    branch.push(hash_tree_root(&feed_updates.block_height)?);
+    let root = hash_pair(&branch[branch.len() - 1], &updates_root)?;
+    if root != hash_tree_root(feed_updates)? {
+        anyhow::bail!("Merkle root of the proof does not match the one of the feed updates!");
+    }
+
+    let index = (1 << (FEED_UPDATE_PROOF_DEPTH - 1)) | position;
+    let update = chunk[index_in_chunk]
+        .clone()
+        .expect("The update was found at this position");
+    Ok(Some((update, FeedUpdateProof { index, branch })))
+}
+
+/// Checks that `update` is part of the block with `header`.
+pub fn verify_feed_update_proof(
+    header: &BlockHeader,
+    update: &AssetFeedUpdate,
+    proof: &FeedUpdateProof,
+) -> Result<bool> {
+    if proof.branch.len() != FEED_UPDATE_PROOF_DEPTH || proof.index >> FEED_UPDATE_PROOF_DEPTH != 0
+    {
+        return Ok(false);
+    }
+    let mut node = hash_tree_root(&Some(update.clone()))?;
+    let mut index = proof.index;
+    for sibling in &proof.branch {
+        node = if index & 1 == 0 {
+            hash_pair(&node, sibling)?
+        } else {
+            hash_pair(sibling, &node)?
+        };
+        index >>= 1;
+    }
+    Ok(node == header.feed_updates_merkle_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_store::BlockStore;
+
+    #[test]
+    fn test_feed_update_proof() {
+        let store = InMemDb::new();
+        let updates = (0..40)
+            .map(|feed_id| AssetFeedUpdate::new(feed_id, vec![feed_id as u8; 32]).unwrap())
+            .collect();
+        let (header, _, feed_updates) = store
+            .create_new_block(1, 1, vec![], vec![], vec![], updates)
+            .unwrap();
+
+        for feed_id in [0, 5, 31, 32, 39] {
+            let (update, proof) = prove_feed_update(&feed_updates, feed_id).unwrap().unwrap();
+            assert_eq!(update.feed_id(), feed_id);
+            assert_eq!(proof.branch.len(), FEED_UPDATE_PROOF_DEPTH);
+            assert!(verify_feed_update_proof(&header, &update, &proof).unwrap());
+
+            let tampered = AssetFeedUpdate::new(feed_id, vec![255; 32]).unwrap();
+            assert!(!verify_feed_update_proof(&header, &tampered, &proof).unwrap());
+
+            let mut wrong_index = proof.clone();
+            wrong_index.index ^= 1;
+            assert!(!verify_feed_update_proof(&header, &update, &wrong_index).unwrap());
+        }
+
+        assert!(prove_feed_update(&feed_updates, 40).unwrap().is_none());
+    }
+}
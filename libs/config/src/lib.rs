@@ -1,13 +1,19 @@
+use blocksense_crypto::{deserialize_public_key, MULTIFORMATS_BLS_PUBKYE_PREFIX};
 use blocksense_registry::config::{
-    CompatibilityInfo, FeedConfig, FeedQuorum, FeedSchedule, PriceFeedInfo,
+    CompatibilityInfo, FeedConfig, FeedQuorum, FeedSchedule, NumericalEncoding, PriceFeedInfo,
+    ReportSigningScheme,
 };
 use blocksense_utils::constants::{
-    FEEDS_CONFIG_DIR, FEEDS_CONFIG_FILE, SEQUENCER_CONFIG_DIR, SEQUENCER_CONFIG_FILE,
+    FEEDS_CONFIG_DIR, FEEDS_CONFIG_FILE, MAX_STRUCT_FIELDS, SEQUENCER_CONFIG_DIR,
+    SEQUENCER_CONFIG_FILE,
 };
 use blocksense_utils::{get_config_file_path, read_file};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
 use hex::decode;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
 use std::{collections::HashMap, fmt::Debug};
 use std::{collections::HashSet, time::UNIX_EPOCH};
@@ -25,6 +31,49 @@ pub struct ChainlinkCompatibility {
     pub quote: String,
 }
 
+/// Trim percentage used when a feed is configured with plain `trimmed_mean`.
+pub const DEFAULT_TRIM_PERCENTAGE: f64 = 10.0;
+
+/// Aggregation named by the `aggregation` of a feed's quorum or of a struct field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Median,
+    Majority,
+    Average,
+    /// Mean of the votes left after dropping the given percentage from each end.
+    TrimmedMean(f64),
+    WeightedMedian,
+    InterquartileMean,
+}
+
+impl FromStr for Aggregation {
+    type Err = anyhow::Error;
+
+    /// The trimmed mean accepts an optional trim percentage, e.g. `trimmed_mean:20`.
+    fn from_str(aggregation: &str) -> anyhow::Result<Self> {
+        match aggregation {
+            "median" => Ok(Self::Median),
+            "majority" => Ok(Self::Majority),
+            "average" => Ok(Self::Average),
+            "trimmed_mean" => Ok(Self::TrimmedMean(DEFAULT_TRIM_PERCENTAGE)),
+            "weighted_median" => Ok(Self::WeightedMedian),
+            "interquartile_mean" => Ok(Self::InterquartileMean),
+            _ => {
+                let Some(trim) = aggregation.strip_prefix("trimmed_mean:") else {
+                    anyhow::bail!("Could not convert {aggregation} to a valid aggregator!");
+                };
+                let Ok(trim_percentage) = trim.parse::<f64>() else {
+                    anyhow::bail!("Invalid trim percentage in {aggregation}!");
+                };
+                if !(0.0..50.0).contains(&trim_percentage) {
+                    anyhow::bail!("Trim percentage in {aggregation} must be in the range [0, 50)!");
+                }
+                Ok(Self::TrimmedMean(trim_percentage))
+            }
+        }
+    }
+}
+
 pub trait Validated {
     fn validate(&self, context: &str) -> anyhow::Result<()>;
 }
@@ -82,10 +131,112 @@ impl Validated for FeedConfig {
                 );
             }
         };
+
+        if let Err(e) = self.quorum.aggregation.parse::<Aggregation>() {
+            anyhow::bail!(
+                "{}: aggregation for feed {} with id {} is invalid: {}",
+                context,
+                self.full_name,
+                self.id,
+                e
+            );
+        }
+
+        if self.value_type == "struct" {
+            validate_struct_fields(self, context)?;
+        } else if !self.struct_fields.is_empty() {
+            anyhow::bail!(
+                "{}: struct_fields for feed {} with id {} require value_type struct, not {}",
+                context,
+                self.full_name,
+                self.id,
+                self.value_type,
+            );
+        }
         Ok(())
     }
 }
 
+fn validate_struct_fields(feed: &FeedConfig, context: &str) -> anyhow::Result<()> {
+    if feed.struct_fields.is_empty() {
+        anyhow::bail!(
+            "{}: struct feed {} with id {} has no struct_fields",
+            context,
+            feed.full_name,
+            feed.id
+        );
+    }
+    if feed.struct_fields.len() > MAX_STRUCT_FIELDS {
+        anyhow::bail!(
+            "{}: struct feed {} with id {} has more than {} fields",
+            context,
+            feed.full_name,
+            feed.id,
+            MAX_STRUCT_FIELDS
+        );
+    }
+    let mut names = HashSet::new();
+    for field in &feed.struct_fields {
+        if !names.insert(field.name.as_str()) {
+            anyhow::bail!(
+                "{}: struct feed {} with id {} has duplicate field {}",
+                context,
+                feed.full_name,
+                feed.id,
+                field.name
+            );
+        }
+        if !matches!(field.value_type.as_str(), "numerical" | "decimal") {
+            anyhow::bail!(
+                "{}: field {} of struct feed {} with id {} must be numerical or decimal, not {}",
+                context,
+                field.name,
+                feed.full_name,
+                feed.id,
+                field.value_type
+            );
+        }
+        // Fields without an aggregation of their own use the feed's.
+        let aggregation = field
+            .aggregation
+            .as_deref()
+            .unwrap_or(&feed.quorum.aggregation);
+        match aggregation.parse::<Aggregation>() {
+            Ok(Aggregation::Majority) => anyhow::bail!(
+                "{}: aggregation {} can not be used for field {} of struct feed {} with id {}",
+                context,
+                aggregation,
+                field.name,
+                feed.full_name,
+                feed.id
+            ),
+            Ok(_) => {}
+            Err(e) => anyhow::bail!(
+                "{}: aggregation of field {} of struct feed {} with id {} is invalid: {}",
+                context,
+                field.name,
+                feed.full_name,
+                feed.id,
+                e
+            ),
+        }
+    }
+    // Every field takes one of the 2^stride 32 byte slots of the feed's storage.
+    let slots = 1u128.checked_shl(feed.stride as u32).unwrap_or(u128::MAX);
+    if feed.struct_fields.len() as u128 > slots {
+        anyhow::bail!(
+            "{}: struct feed {} with id {} has {} fields, but stride {} only fits {}",
+            context,
+            feed.full_name,
+            feed.id,
+            feed.struct_fields.len(),
+            feed.stride,
+            slots
+        );
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct FeedStrideAndDecimals {
     pub stride: u16,
@@ -121,8 +272,16 @@ pub struct AllFeedsConfig {
 
 impl Validated for AllFeedsConfig {
     fn validate(&self, context: &str) -> anyhow::Result<()> {
+        let mut feed_ids = HashSet::new();
         for feed in &self.feeds {
-            feed.validate(context)?
+            feed.validate(context)?;
+            if !feed_ids.insert(feed.id) {
+                anyhow::bail!(
+                    "{}: Feed id {} is configured more than once",
+                    context,
+                    feed.id
+                );
+            }
         }
 
         Ok(())
@@ -231,6 +390,9 @@ pub struct Reporter {
     pub id: u32,
     pub pub_key: String,
     pub address: String,
+    /// Votes of suspended reporters are rejected and they do not count towards quorum.
+    #[serde(default)]
+    pub suspended: bool,
 }
 
 impl Validated for Reporter {
@@ -254,11 +416,323 @@ pub struct BlockConfig {
     pub aggregation_consensus_discard_period_blocks: u64,
 }
 
+/// Location and frequency of the on-disk snapshots of the feeds' aggregate history.
+/// The snapshots are reloaded on startup, so that publishing criteria and anomaly
+/// detection keep their state across restarts.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistoryPersistence {
+    pub dir: String,
+    pub snapshot_interval_ms: u64,
+}
+
+impl Validated for HistoryPersistence {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.dir.is_empty() {
+            anyhow::bail!("{}: dir cannot be empty", context);
+        }
+        if self.snapshot_interval_ms == 0 {
+            anyhow::bail!("{}: snapshot_interval_ms cannot be set to 0", context);
+        }
+        Ok(())
+    }
+}
+
+/// Watching of the feeds config file for changes, which are applied to the active feeds
+/// without a restart.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FeedsConfigWatch {
+    pub poll_interval_ms: u64,
+}
+
+impl Validated for FeedsConfigWatch {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.poll_interval_ms == 0 {
+            anyhow::bail!("{}: poll_interval_ms cannot be set to 0", context);
+        }
+        Ok(())
+    }
+}
+
+/// Token bucket allowing `per_sec` requests per second on average, and bursts of up to
+/// `burst` requests.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_sec: f64,
+    pub burst: u32,
+}
+
+impl Validated for RateLimit {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if !self.per_sec.is_finite() || self.per_sec <= 0.0 {
+            anyhow::bail!("{}: per_sec must be a positive number", context);
+        }
+        if self.burst == 0 {
+            anyhow::bail!("{}: burst cannot be set to 0", context);
+        }
+        Ok(())
+    }
+}
+
+/// Limits on the reports accepted by `/post_report` and `/post_reports_batch`. Reports over
+/// any of them are rejected with 429.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ReportsLimits {
+    /// Reports accepted per reporter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_reporter: Option<RateLimit>,
+    /// Requests accepted per client IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_ip: Option<RateLimit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_len: Option<usize>,
+    /// Revotes accepted from a reporter for the same feed within a slot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_revotes_per_slot: Option<u32>,
+}
+
+impl Validated for ReportsLimits {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if let Some(per_reporter) = &self.per_reporter {
+            per_reporter.validate(format!("{}: per_reporter", context).as_str())?;
+        }
+        if let Some(per_ip) = &self.per_ip {
+            per_ip.validate(format!("{}: per_ip", context).as_str())?;
+        }
+        if self.max_batch_len == Some(0) {
+            anyhow::bail!("{}: max_batch_len cannot be set to 0", context);
+        }
+        Ok(())
+    }
+}
+
+/// Signing schemes of the reports accepted by the sequencer. Listing both the old and the new
+/// scheme lets reporters migrate one by one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReportSigning {
+    /// Identifies the deployment, e.g. by the chain id of its network, so that reports signed
+    /// for it are not valid for another.
+    pub deployment_id: String,
+    pub accepted_schemes: Vec<ReportSigningScheme>,
+}
+
+impl Default for ReportSigning {
+    /// Accepts only `V0`, which is what reporters sign without a deployment id.
+    fn default() -> Self {
+        ReportSigning {
+            deployment_id: String::new(),
+            accepted_schemes: vec![ReportSigningScheme::V0],
+        }
+    }
+}
+
+impl ReportSigning {
+    pub fn accepts(&self, scheme: ReportSigningScheme) -> bool {
+        self.accepted_schemes.contains(&scheme)
+    }
+}
+
+impl Validated for ReportSigning {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.accepted_schemes.is_empty() {
+            anyhow::bail!("{}: at least one scheme must be accepted", context);
+        }
+        if self.deployment_id.is_empty() && self.accepts(ReportSigningScheme::V1) {
+            anyhow::bail!("{}: deployment_id is needed by scheme v1", context);
+        }
+        Ok(())
+    }
+}
+
+/// Access granted to a token of the admin API. Every role can read the sequencer's state.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    /// Read-only access to state, configs and metrics.
+    Observer,
+    /// Management of feeds, reporters, networks and the log level.
+    Operator,
+    /// Deployment of contracts.
+    Deployer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AdminToken {
+    pub name: String,
+    /// File holding the bearer token.
+    pub token_path: String,
+    pub roles: Vec<AdminRole>,
+}
+
+/// Bearer token authentication of the admin API. Without it, the admin API is only
+/// protected by network isolation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AdminAuth {
+    pub tokens: Vec<AdminToken>,
+}
+
+impl Validated for AdminAuth {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.tokens.is_empty() {
+            anyhow::bail!("{}: at least one token must be configured", context);
+        }
+        let mut names = HashSet::new();
+        for token in &self.tokens {
+            if !names.insert(token.name.as_str()) {
+                anyhow::bail!("{}: duplicate token name {}", context, token.name);
+            }
+            if token.roles.is_empty() {
+                anyhow::bail!("{}: token {} has no roles", context, token.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// BLS identities of the sequencers. Every sequencer signs the Merkle root of the headers of
+/// the blocks it streams, and accepts streamed blocks only from the sequencers listed here.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BlockSigning {
+    /// Path to the hex encoded BLS secret key of this sequencer.
+    pub private_key_path: String,
+    /// Hex encoded BLS public keys of the sequencers by their `sequencer_id`.
+    pub sequencer_pub_keys: HashMap<u64, String>,
+}
+
+impl Validated for BlockSigning {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.private_key_path.is_empty() {
+            anyhow::bail!("{}: private_key_path cannot be empty", context);
+        }
+        if self.sequencer_pub_keys.is_empty() {
+            anyhow::bail!("{}: sequencer_pub_keys cannot be empty", context);
+        }
+        for (sequencer_id, pub_key) in &self.sequencer_pub_keys {
+            let Some(pub_key) = pub_key.strip_prefix(MULTIFORMATS_BLS_PUBKYE_PREFIX) else {
+                anyhow::bail!(
+                    "{}: Pub key of sequencer id {} is not a multiformats BLS key",
+                    context,
+                    sequencer_id
+                );
+            };
+            if let Err(e) = deserialize_public_key(pub_key) {
+                anyhow::bail!(
+                    "{}: Pub key of sequencer id {} is invalid: {}",
+                    context,
+                    sequencer_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the sequencer keeps the chain of blocks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockStoreConfig {
+    /// The chain is lost on restart.
+    #[default]
+    InMemory,
+    /// The chain is appended to a local file and reloaded on startup.
+    File { path: String },
+}
+
+impl Validated for BlockStoreConfig {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if let BlockStoreConfig::File { path } = self {
+            if path.is_empty() {
+                anyhow::bail!("{}: path cannot be empty", context);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A recurring trading session, in the local time of its market calendar. A session whose
+/// `close` is not after its `open` ends on the following day, e.g. FX trading from 17:00
+/// until 17:00.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TradingSession {
+    pub days: Vec<Weekday>,
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MarketCalendarConfig {
+    pub timezone: Tz,
+    pub sessions: Vec<TradingSession>,
+    /// Dates on which no session opens.
+    #[serde(default)]
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl Validated for MarketCalendarConfig {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.sessions.is_empty() {
+            anyhow::bail!("{}: at least one trading session is required", context);
+        }
+        if self.sessions.iter().any(|session| session.days.is_empty()) {
+            anyhow::bail!("{}: trading sessions must specify days", context);
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct KafkaReportEndpoint {
     pub url: Option<String>,
 }
 
+/// Scoring of the reporters by how close their votes are to the aggregated value, how often
+/// they vote and how often their votes arrive late. Recent observations weigh more.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ReporterReputationConfig {
+    /// Number of observations after which the influence of an observation on a score halves.
+    pub half_life: u32,
+    /// Deviation from the aggregated value at which a vote counts as fully inaccurate.
+    pub max_deviation_percentage: f64,
+    /// Weigh votes by the reputation of their reporters. Only aggregators that take weights
+    /// into account, like `weighted_median`, are affected.
+    pub use_as_aggregation_weights: bool,
+    /// Votes of reporters whose score drops below this floor are ignored until it recovers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jail_floor: Option<f64>,
+    /// Number of slots a reporter has to be scored on before it can be jailed.
+    pub min_observations: u64,
+}
+
+impl Default for ReporterReputationConfig {
+    fn default() -> Self {
+        ReporterReputationConfig {
+            half_life: 100,
+            max_deviation_percentage: 10.0,
+            use_as_aggregation_weights: false,
+            jail_floor: None,
+            min_observations: 100,
+        }
+    }
+}
+
+impl Validated for ReporterReputationConfig {
+    fn validate(&self, context: &str) -> anyhow::Result<()> {
+        if self.half_life == 0 {
+            anyhow::bail!("{}: half_life cannot be set to 0", context);
+        }
+        if self.max_deviation_percentage.is_nan() || self.max_deviation_percentage <= 0.0 {
+            anyhow::bail!("{}: max_deviation_percentage must be positive", context);
+        }
+        if let Some(jail_floor) = self.jail_floor {
+            if !(0.0..=1.0).contains(&jail_floor) {
+                anyhow::bail!("{}: jail_floor must be between 0 and 1", context);
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct SequencerConfig {
     pub sequencer_id: u64,
@@ -270,6 +744,34 @@ pub struct SequencerConfig {
     pub reporters: Vec<Reporter>,
     pub kafka_report_endpoint: KafkaReportEndpoint,
     pub http_input_buffer_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_persistence: Option<HistoryPersistence>,
+    /// Market calendars by name, extending or overriding the built-in ones. Feeds refer to
+    /// them through `market_hours` in their `additional_feed_info`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub market_calendars: HashMap<String, MarketCalendarConfig>,
+    #[serde(default)]
+    pub reporter_reputation: ReporterReputationConfig,
+    #[serde(default)]
+    pub block_store: BlockStoreConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_signing: Option<BlockSigning>,
+    /// Upper bound on how long a graceful shutdown waits for in-flight blocks, transactions
+    /// and Kafka records before the sequencer exits.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feeds_config_watch: Option<FeedsConfigWatch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_auth: Option<AdminAuth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reports_limits: Option<ReportsLimits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_signing: Option<ReportSigning>,
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    60
 }
 
 impl Validated for SequencerConfig {
@@ -291,6 +793,39 @@ impl Validated for SequencerConfig {
             reporter.validate(format!("{}: Reporter id: {}", context, reporter.id).as_str())?
         }
 
+        if let Some(history_persistence) = &self.history_persistence {
+            history_persistence.validate(format!("{}: history_persistence", context).as_str())?
+        }
+
+        for (name, calendar) in &self.market_calendars {
+            calendar.validate(format!("{}: Market calendar: {}", context, name).as_str())?
+        }
+
+        self.reporter_reputation
+            .validate(format!("{}: reporter_reputation", context).as_str())?;
+        self.block_store
+            .validate(format!("{}: block_store", context).as_str())?;
+
+        if let Some(block_signing) = &self.block_signing {
+            block_signing.validate(format!("{}: block_signing", context).as_str())?;
+        }
+
+        if let Some(feeds_config_watch) = &self.feeds_config_watch {
+            feeds_config_watch.validate(format!("{}: feeds_config_watch", context).as_str())?;
+        }
+
+        if let Some(admin_auth) = &self.admin_auth {
+            admin_auth.validate(format!("{}: admin_auth", context).as_str())?;
+        }
+
+        if let Some(reports_limits) = &self.reports_limits {
+            reports_limits.validate(format!("{}: reports_limits", context).as_str())?;
+        }
+
+        if let Some(report_signing) = &self.report_signing {
+            report_signing.validate(format!("{}: report_signing", context).as_str())?;
+        }
+
         Ok(())
     }
 }
@@ -330,8 +865,12 @@ pub fn get_sequencer_config() -> SequencerConfig {
         .expect("Could not get validated sequencer config")
 }
 
+pub fn get_feeds_config_file_path() -> PathBuf {
+    get_config_file_path(FEEDS_CONFIG_DIR, FEEDS_CONFIG_FILE)
+}
+
 pub fn get_feeds_config() -> AllFeedsConfig {
-    let feeds_config_file = get_config_file_path(FEEDS_CONFIG_DIR, FEEDS_CONFIG_FILE);
+    let feeds_config_file = get_feeds_config_file_path();
     get_validated_config::<AllFeedsConfig>(&feeds_config_file, "FeedsConfig")
         .expect("Could not get validated feeds config")
 }
@@ -350,6 +889,8 @@ pub fn test_feed_config(id: u32, stride: u16) -> FeedConfig {
         feed_type: "price-feed".to_owned(),
         oracle_id: "crypto-price-feeds".to_owned(),
         value_type: "numerical".to_owned(),
+        value_encoding: NumericalEncoding::Unsigned,
+        struct_fields: vec![],
         stride,
         quorum: FeedQuorum {
             percentage: 100.0,
@@ -433,6 +974,16 @@ pub fn get_test_config_with_no_providers() -> SequencerConfig {
         reporters: Vec::new(),
         kafka_report_endpoint: KafkaReportEndpoint { url: None },
         http_input_buffer_size: None,
+        history_persistence: None,
+        market_calendars: HashMap::new(),
+        reporter_reputation: ReporterReputationConfig::default(),
+        block_store: BlockStoreConfig::default(),
+        block_signing: None,
+        shutdown_timeout_secs: default_shutdown_timeout_secs(),
+        feeds_config_watch: None,
+        admin_auth: None,
+        reports_limits: None,
+        report_signing: None,
     }
 }
 
@@ -494,6 +1045,320 @@ mod tests {
         assert!(invalid_config_3.validate("").is_err());
     }
 
+    #[test]
+    fn parsing_market_calendars() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.market_calendars = serde_json::from_str(
+            r#"
+            {
+                "NYSE": {
+                    "timezone": "America/New_York",
+                    "sessions": [
+                        {"days": ["Mon", "Tue", "Wed", "Thu", "Fri"], "open": "09:30", "close": "16:00"}
+                    ],
+                    "holidays": ["2025-12-25"]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(sequencer_config.validate("").is_ok());
+
+        let nyse = &sequencer_config.market_calendars["NYSE"];
+        assert_eq!(nyse.timezone, chrono_tz::America::New_York);
+        assert_eq!(nyse.sessions[0].days.len(), 5);
+        assert_eq!(
+            nyse.sessions[0].open,
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap()
+        );
+        assert_eq!(
+            nyse.holidays,
+            vec![NaiveDate::from_ymd_opt(2025, 12, 25).unwrap()]
+        );
+
+        let mut invalid_config = sequencer_config.clone();
+        if let Some(nyse) = invalid_config.market_calendars.get_mut("NYSE") {
+            nyse.sessions[0].days.clear();
+        }
+        assert!(invalid_config.validate("").is_err());
+    }
+
+    #[test]
+    fn parsing_struct_feed_config() {
+        let mut feed = test_feed_config(1, 1);
+        feed.value_type = "struct".to_string();
+        feed.struct_fields = serde_json::from_str(
+            r#"
+            [
+                {"name": "bid", "value_type": "numerical"},
+                {"name": "ask", "value_type": "decimal", "aggregation": "average"}
+            ]"#,
+        )
+        .unwrap();
+        assert!(feed.validate("").is_ok());
+        assert_eq!(feed.struct_fields[0].aggregation, None);
+        assert_eq!(
+            feed.struct_fields[1].aggregation.as_deref(),
+            Some("average")
+        );
+
+        let mut too_small_stride = feed.clone();
+        too_small_stride.stride = 0;
+        assert!(too_small_stride.validate("").is_err());
+
+        let mut duplicate_field = feed.clone();
+        duplicate_field.struct_fields[1].name = "bid".to_string();
+        assert!(duplicate_field.validate("").is_err());
+
+        let mut text_field = feed.clone();
+        text_field.struct_fields[0].value_type = "text".to_string();
+        assert!(text_field.validate("").is_err());
+
+        let mut not_a_struct = feed.clone();
+        not_a_struct.value_type = "numerical".to_string();
+        assert!(not_a_struct.validate("").is_err());
+
+        let mut majority_field = feed.clone();
+        majority_field.struct_fields[1].aggregation = Some("majority".to_string());
+        assert!(majority_field.validate("").is_err());
+
+        let mut invalid_field_aggregation = feed.clone();
+        invalid_field_aggregation.struct_fields[1].aggregation = Some("mode".to_string());
+        assert!(invalid_field_aggregation.validate("").is_err());
+
+        // The feed's aggregation applies to the fields without their own.
+        let mut majority_default = feed.clone();
+        majority_default.quorum.aggregation = "majority".to_string();
+        assert!(majority_default.validate("").is_err());
+    }
+
+    #[test]
+    fn parsing_feed_aggregation() {
+        assert_eq!(
+            "trimmed_mean".parse::<Aggregation>().unwrap(),
+            Aggregation::TrimmedMean(DEFAULT_TRIM_PERCENTAGE)
+        );
+        assert_eq!(
+            "trimmed_mean:20".parse::<Aggregation>().unwrap(),
+            Aggregation::TrimmedMean(20.0)
+        );
+        for invalid in [
+            "trimmed_mean:50",
+            "trimmed_mean:60",
+            "trimmed_mean:abc",
+            "mode",
+        ] {
+            assert!(invalid.parse::<Aggregation>().is_err());
+        }
+
+        let mut feed = test_feed_config(1, 0);
+        assert!(feed.validate("").is_ok());
+        feed.quorum.aggregation = "trimmed_mean:60".to_string();
+        assert!(feed.validate("").is_err());
+    }
+
+    #[test]
+    fn parsing_reporter_reputation() {
+        let reputation: ReporterReputationConfig =
+            serde_json::from_str(r#"{"jail_floor": 0.3, "use_as_aggregation_weights": true}"#)
+                .unwrap();
+        assert_eq!(reputation.jail_floor, Some(0.3));
+        assert!(reputation.use_as_aggregation_weights);
+        assert_eq!(
+            reputation.half_life,
+            ReporterReputationConfig::default().half_life
+        );
+
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.reporter_reputation = reputation;
+        assert!(sequencer_config.validate("").is_ok());
+
+        let mut invalid_config = sequencer_config.clone();
+        invalid_config.reporter_reputation.jail_floor = Some(1.5);
+        assert!(invalid_config.validate("").is_err());
+
+        let mut invalid_config = sequencer_config.clone();
+        invalid_config.reporter_reputation.half_life = 0;
+        assert!(invalid_config.validate("").is_err());
+    }
+
+    #[test]
+    fn parsing_block_store_config() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        assert_eq!(sequencer_config.block_store, BlockStoreConfig::InMemory);
+
+        sequencer_config.block_store =
+            serde_json::from_str(r#"{"type": "file", "path": "/tmp/sequencer/blocks.bin"}"#)
+                .unwrap();
+        assert_eq!(
+            sequencer_config.block_store,
+            BlockStoreConfig::File {
+                path: "/tmp/sequencer/blocks.bin".to_string()
+            }
+        );
+        assert!(sequencer_config.validate("").is_ok());
+
+        sequencer_config.block_store = BlockStoreConfig::File {
+            path: String::new(),
+        };
+        assert!(sequencer_config.validate("").is_err());
+    }
+
+    #[test]
+    fn feeds_config_with_duplicate_feed_ids_fails_validation() {
+        let mut feeds_config = AllFeedsConfig {
+            feeds: vec![test_feed_config(1, 0), test_feed_config(2, 0)],
+        };
+        assert!(feeds_config.validate("").is_ok());
+
+        feeds_config.feeds.push(test_feed_config(1, 1));
+        assert!(feeds_config.validate("").is_err());
+    }
+
+    #[test]
+    fn sequencer_config_with_invalid_block_signing_pub_key_fails_validation() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.block_signing = Some(BlockSigning {
+            private_key_path: "/tmp/sequencer_key".to_string(),
+            sequencer_pub_keys: HashMap::from([(1, "ea30af86b930d539c55677b05b4a5dad9fce1f758ba09d152d19a7d6940f8d8a8a8fb9f90d38a19e988d721cddaee4567d2e".to_string())]),
+        });
+        assert!(sequencer_config.validate("").is_ok());
+
+        for invalid_pub_key in [
+            "not hex",
+            "ea30af",
+            "af86b930d539c55677b05b4a5dad9fce1f758ba09d152d19a7d6940f8d8a8a8fb9f90d38a19e988d721cddaee4567d2e",
+        ] {
+            let mut invalid_config = sequencer_config.clone();
+            if let Some(block_signing) = invalid_config.block_signing.as_mut() {
+                block_signing
+                    .sequencer_pub_keys
+                    .insert(2, invalid_pub_key.to_string());
+            }
+            assert!(invalid_config.validate("").is_err());
+        }
+
+        let mut invalid_config = sequencer_config.clone();
+        if let Some(block_signing) = invalid_config.block_signing.as_mut() {
+            block_signing.sequencer_pub_keys.clear();
+        }
+        assert!(invalid_config.validate("").is_err());
+    }
+
+    #[test]
+    fn sequencer_config_with_invalid_admin_auth_fails_validation() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        let token = AdminToken {
+            name: "ops".to_string(),
+            token_path: "/tmp/ops_token".to_string(),
+            roles: vec![AdminRole::Operator],
+        };
+        sequencer_config.admin_auth = Some(AdminAuth {
+            tokens: vec![token.clone()],
+        });
+        assert!(sequencer_config.validate("").is_ok());
+
+        let mut invalid_config = sequencer_config.clone();
+        if let Some(admin_auth) = invalid_config.admin_auth.as_mut() {
+            admin_auth.tokens.push(token.clone());
+        }
+        assert!(invalid_config.validate("").is_err());
+
+        let mut invalid_config = sequencer_config.clone();
+        if let Some(admin_auth) = invalid_config.admin_auth.as_mut() {
+            admin_auth.tokens[0].roles.clear();
+        }
+        assert!(invalid_config.validate("").is_err());
+    }
+
+    #[test]
+    fn sequencer_config_with_invalid_reports_limits_fails_validation() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        let limits = ReportsLimits {
+            per_reporter: Some(RateLimit {
+                per_sec: 10.0,
+                burst: 20,
+            }),
+            per_ip: None,
+            max_batch_len: Some(100),
+            max_revotes_per_slot: Some(0),
+        };
+        sequencer_config.reports_limits = Some(limits.clone());
+        assert!(sequencer_config.validate("").is_ok());
+
+        for invalid_limits in [
+            ReportsLimits {
+                per_ip: Some(RateLimit {
+                    per_sec: 0.0,
+                    burst: 1,
+                }),
+                ..limits.clone()
+            },
+            ReportsLimits {
+                per_reporter: Some(RateLimit {
+                    per_sec: 1.0,
+                    burst: 0,
+                }),
+                ..limits.clone()
+            },
+            ReportsLimits {
+                max_batch_len: Some(0),
+                ..limits.clone()
+            },
+        ] {
+            sequencer_config.reports_limits = Some(invalid_limits);
+            assert!(sequencer_config.validate("").is_err());
+        }
+    }
+
+    #[test]
+    fn sequencer_config_with_invalid_report_signing_fails_validation() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        let report_signing = ReportSigning {
+            deployment_id: "ethereum-sepolia".to_string(),
+            accepted_schemes: vec![ReportSigningScheme::V0, ReportSigningScheme::V1],
+        };
+        sequencer_config.report_signing = Some(report_signing.clone());
+        assert!(sequencer_config.validate("").is_ok());
+        sequencer_config.report_signing = Some(ReportSigning::default());
+        assert!(sequencer_config.validate("").is_ok());
+
+        for invalid_report_signing in [
+            ReportSigning {
+                accepted_schemes: vec![],
+                ..report_signing.clone()
+            },
+            ReportSigning {
+                deployment_id: String::new(),
+                ..report_signing.clone()
+            },
+        ] {
+            sequencer_config.report_signing = Some(invalid_report_signing);
+            assert!(sequencer_config.validate("").is_err());
+        }
+
+        let scheme: ReportSigningScheme = serde_json::from_str("\"v1\"").unwrap();
+        assert_eq!(
+            ReportSigningScheme::from_version(scheme.version()),
+            Some(ReportSigningScheme::V1)
+        );
+    }
+
+    #[test]
+    fn sequencer_config_with_zero_history_snapshot_interval_fails_validation() {
+        let mut sequencer_config = get_test_config_with_no_providers();
+        sequencer_config.history_persistence = Some(HistoryPersistence {
+            dir: "/tmp/sequencer_history".to_string(),
+            snapshot_interval_ms: 60000,
+        });
+        assert!(sequencer_config.validate("").is_ok());
+
+        let mut invalid_config = sequencer_config.clone();
+        if let Some(history_persistence) = invalid_config.history_persistence.as_mut() {
+            history_persistence.snapshot_interval_ms = 0;
+        }
+        assert!(invalid_config.validate("").is_err());
+    }
+
     #[test]
     fn parsing_provider_config_missing_publish_criteria() {
         let provider_a: Provider = serde_json::from_str(r#"
@@ -3,6 +3,7 @@ pub use blst::min_pk::SecretKey;
 pub use blst::min_pk::Signature;
 use blst::*;
 pub use hex::{decode, encode};
+use rand::Rng;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub const MULTIFORMATS_BLS_PUBKYE_PREFIX: &str = "ea30";
@@ -21,6 +22,26 @@ pub fn verify_signature(pk: &PublicKey, signature: &Signature, message: &[u8]) -
     signature.verify(true, message, &[], &[], pk, true) == BLST_ERROR::BLST_SUCCESS
 }
 
+/// Verifies that every `sigs[i]` is a signature of `msgs[i]` by `pks[i]`, with a single
+/// multi-pairing. Every signature is weighted by a random scalar, so that invalid signatures
+/// cannot cancel each other out. On failure it does not tell which signatures are invalid.
+pub fn verify_signatures_batch(pks: &[&PublicKey], sigs: &[&Signature], msgs: &[&[u8]]) -> bool {
+    if pks.is_empty() {
+        return sigs.is_empty() && msgs.is_empty();
+    }
+    let mut rng = rand::thread_rng();
+    let rands: Vec<blst_scalar> = pks
+        .iter()
+        .map(|_| {
+            let mut scalar = blst_scalar::default();
+            scalar.b[..8].copy_from_slice(&rng.gen_range(1..=u64::MAX).to_le_bytes());
+            scalar
+        })
+        .collect();
+    Signature::verify_multiple_aggregate_signatures(msgs, &[], pks, true, sigs, true, &rands, 64)
+        == BLST_ERROR::BLST_SUCCESS
+}
+
 pub fn serialize_public_key(pk: &PublicKey) -> String {
     encode(pk.to_bytes())
 }
@@ -62,3 +83,41 @@ impl<'de> Deserialize<'de> for JsonSerializableSignature {
         Ok(JsonSerializableSignature { sig })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_signatures_batch() {
+        let keys: Vec<(SecretKey, PublicKey)> = (0..4u8).map(|i| generate_keys(&[i; 35])).collect();
+        let msgs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 16]).collect();
+        let mut sigs: Vec<Signature> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|((sk, _), msg)| sign_message(sk, msg))
+            .collect();
+        let pks: Vec<&PublicKey> = keys.iter().map(|(_, pk)| pk).collect();
+        let msg_refs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+
+        assert!(verify_signatures_batch(
+            &pks,
+            &sigs.iter().collect::<Vec<_>>(),
+            &msg_refs
+        ));
+        assert!(verify_signatures_batch(&[], &[], &[]));
+
+        // Swapping two signatures keeps their aggregate, but not the weighted one.
+        sigs.swap(1, 2);
+        assert!(!verify_signatures_batch(
+            &pks,
+            &sigs.iter().collect::<Vec<_>>(),
+            &msg_refs
+        ));
+        assert!(!verify_signatures_batch(
+            &pks[..3],
+            &sigs.iter().collect::<Vec<_>>(),
+            &msg_refs
+        ));
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::anyhow;
 use blocksense_config::PublishCriteria;
 use blocksense_feed_registry::{
+    market_hours::MarketCalendar,
     registry::FeedAggregateHistory,
     types::{DataFeedPayload, FeedType, Timestamp},
 };
+use blocksense_registry::config::NumericalEncoding;
 use blocksense_utils::from_hex_string;
 use log::error;
 use serde::Deserialize;
@@ -36,6 +38,7 @@ pub enum DontSkipReason {
 pub enum DoSkipReason {
     TooSimilarTooSoon, // threshold not crossed and heartbeat not timed out
     NothingToPost,
+    AggregationFailed, // the votes could not be aggregated, e.g. a reporter sent NaN
 }
 
 #[derive(Debug, PartialEq)]
@@ -54,11 +57,19 @@ impl SkipDecision {
 }
 
 impl VotedFeedUpdate {
-    pub fn encode(&self, digits_in_fraction: usize, timestamp: u64) -> (Vec<u8>, Vec<u8>) {
-        (
-            self.feed_id.to_be_bytes().to_vec(),
-            naive_packing(&self.value, digits_in_fraction, timestamp),
-        )
+    pub fn encode(
+        &self,
+        digits_in_fraction: usize,
+        timestamp: u64,
+    ) -> Result<(Vec<u8>, Vec<u8>), anyhow::Error> {
+        let value = naive_packing(&self.value, digits_in_fraction, timestamp).map_err(|e| {
+            anyhow!(
+                "Could not encode value {:?} of feed {}: {e}",
+                self.value,
+                self.feed_id
+            )
+        })?;
+        Ok((self.feed_id.to_be_bytes().to_vec(), value))
     }
 
     pub fn new_decode(
@@ -67,13 +78,14 @@ impl VotedFeedUpdate {
         end_slot_timestamp: Timestamp,
         variant: FeedType, // variant is only a type placeholder.
         digits_in_fraction: usize,
+        encoding: NumericalEncoding,
     ) -> Result<VotedFeedUpdate, anyhow::Error> {
         let key_bytes = from_hex_string(key)?;
         let mut dst = [0u8; 4];
         dst.clone_from_slice(&key_bytes[0..4]);
         let feed_id = u32::from_be_bytes(dst);
         let value_bytes = from_hex_string(value)?;
-        let value = FeedType::from_bytes(value_bytes, variant, digits_in_fraction)
+        let value = FeedType::from_bytes(value_bytes, variant, digits_in_fraction, encoding)
             .map_err(|e| anyhow!("{e}"))?;
 
         Ok(VotedFeedUpdate {
@@ -88,48 +100,104 @@ impl VotedFeedUpdate {
         criteria: &PublishCriteria,
         history: &FeedAggregateHistory,
     ) -> SkipDecision {
-        if let FeedType::Numerical(candidate_value) = self.value {
-            let feed_id = self.feed_id;
-            let res = match history.last(feed_id) {
-                Some(last_published) => match last_published.value {
-                    FeedType::Numerical(last) => {
-                        // Note: a price can be negative,
-                        // e.g. there have been cases for electricity and crude oil prices
-                        // This is why we take absolute value
-                        let a = f64::abs(last);
-                        let diff = f64::abs(last - candidate_value);
-                        let has_heartbeat_timed_out = match criteria.always_publish_heartbeat_ms {
-                            Some(heartbeat) => {
-                                self.end_slot_timestamp
-                                    >= heartbeat + last_published.end_slot_timestamp
-                            }
-                            None => false,
-                        };
-                        let is_threshold_crossed =
-                            diff * 100.0f64 >= criteria.skip_publish_if_less_then_percentage * a;
-                        if is_threshold_crossed {
-                            SkipDecision::DontSkip(DontSkipReason::ThresholdCrossed)
-                        } else if has_heartbeat_timed_out {
-                            SkipDecision::DontSkip(DontSkipReason::HeartbeatTimedOut)
-                        } else {
-                            SkipDecision::DoSkip(DoSkipReason::TooSimilarTooSoon)
+        self.should_skip_with_market_hours(criteria, history, None)
+    }
+
+    /// Like `should_skip`, but the heartbeat only counts the time during which the feed's
+    /// market was open, so that it does not fire while the market is closed.
+    pub fn should_skip_with_market_hours(
+        &self,
+        criteria: &PublishCriteria,
+        history: &FeedAggregateHistory,
+        market_calendar: Option<&MarketCalendar>,
+    ) -> SkipDecision {
+        if !matches!(
+            self.value,
+            FeedType::Numerical(_) | FeedType::Decimal(_) | FeedType::Struct(_)
+        ) {
+            return SkipDecision::DontSkip(DontSkipReason::NonNumericalFeed);
+        }
+        let feed_id = self.feed_id;
+        match history.last(feed_id) {
+            Some(last_published) => {
+                let Some(is_threshold_crossed) = is_threshold_crossed(
+                    &last_published.value,
+                    &self.value,
+                    criteria.skip_publish_if_less_then_percentage,
+                ) else {
+                    error!("History for numerical feed with id {feed_id} contains a non-numerical update {:?}.", last_published.value);
+                    return SkipDecision::DontSkip(DontSkipReason::HistoryError);
+                };
+                let has_heartbeat_timed_out =
+                    match (criteria.always_publish_heartbeat_ms, market_calendar) {
+                        (Some(heartbeat), None) => {
+                            self.end_slot_timestamp >= heartbeat + last_published.end_slot_timestamp
                         }
-                    }
-                    _ => {
-                        error!("History for numerical feed with id {feed_id} contains a non-numerical update {:?}.", last_published.value);
-                        SkipDecision::DontSkip(DontSkipReason::HistoryError)
-                    }
-                },
-                None => SkipDecision::DontSkip(DontSkipReason::NoHistory),
-            };
-            res
-        } else {
-            SkipDecision::DontSkip(DontSkipReason::NonNumericalFeed)
+                        (Some(heartbeat), Some(calendar)) => {
+                            calendar.open_duration_ms(
+                                last_published.end_slot_timestamp,
+                                self.end_slot_timestamp,
+                            ) >= heartbeat
+                        }
+                        (None, _) => false,
+                    };
+                if is_threshold_crossed {
+                    SkipDecision::DontSkip(DontSkipReason::ThresholdCrossed)
+                } else if has_heartbeat_timed_out {
+                    SkipDecision::DontSkip(DontSkipReason::HeartbeatTimedOut)
+                } else {
+                    SkipDecision::DoSkip(DoSkipReason::TooSimilarTooSoon)
+                }
+            }
+            None => SkipDecision::DontSkip(DontSkipReason::NoHistory),
         }
     }
 }
 
-pub fn naive_packing(feed_result: &FeedType, digits_in_fraction: usize, timestamp: u64) -> Vec<u8> {
+/// Checks if `candidate` deviates from `last` by at least `percentage` percent.
+/// For struct values it is enough if a single field deviates that much.
+/// Returns `None` if the two values are not of the same numerical type.
+fn is_threshold_crossed(last: &FeedType, candidate: &FeedType, percentage: f64) -> Option<bool> {
+    // Note: a price can be negative,
+    // e.g. there have been cases for electricity and crude oil prices
+    // This is why we take absolute value
+    let (diff, a) = match (last, candidate) {
+        (FeedType::Numerical(last), FeedType::Numerical(candidate)) => {
+            (f64::abs(last - candidate), f64::abs(*last))
+        }
+        (FeedType::Decimal(last), FeedType::Decimal(candidate)) => {
+            // The difference is computed exactly, only the ratio to the
+            // threshold is evaluated in floating point.
+            let scale = last.scale.max(candidate.scale);
+            match (last.rescale(scale), candidate.rescale(scale)) {
+                (Some(last), Some(candidate)) => {
+                    let diff = last.mantissa.abs_diff(candidate.mantissa);
+                    (diff as f64, last.mantissa.unsigned_abs() as f64)
+                }
+                // Values this far apart can not be within the threshold.
+                _ => return Some(true),
+            }
+        }
+        (FeedType::Struct(last_fields), FeedType::Struct(candidate_fields)) => {
+            if !last.same_enum_type_as(candidate) {
+                return None;
+            }
+            let mut is_crossed = false;
+            for (last, candidate) in last_fields.iter().zip(candidate_fields) {
+                is_crossed |= is_threshold_crossed(&last.value, &candidate.value, percentage)?;
+            }
+            return Some(is_crossed);
+        }
+        _ => return None,
+    };
+    Some(diff * 100.0f64 >= percentage * a)
+}
+
+pub fn naive_packing(
+    feed_result: &FeedType,
+    digits_in_fraction: usize,
+    timestamp: u64,
+) -> Result<Vec<u8>, String> {
     //TODO: Return Bytes32 type
     feed_result.as_bytes(digits_in_fraction, timestamp)
 }
@@ -160,6 +228,7 @@ impl PublishedFeedUpdate {
         feed_id: u32,
         variant: FeedType,
         digits_in_fraction: usize,
+        encoding: NumericalEncoding,
         data: &[u8],
     ) -> Result<PublishedFeedUpdate, PublishedFeedUpdateError> {
         if data.len() != 64 {
@@ -172,7 +241,7 @@ impl PublishedFeedUpdate {
         let j2: [u8; 16] = data[48..64].try_into().expect("Impossible");
         let j3: [u8; 8] = data[24..32].try_into().expect("Impossible");
         let timestamp_u64 = u64::from_be_bytes(j3);
-        match FeedType::from_bytes(j1.to_vec(), variant, digits_in_fraction) {
+        match FeedType::from_bytes(j1.to_vec(), variant, digits_in_fraction, encoding) {
             Ok(latest) => Ok(PublishedFeedUpdate {
                 feed_id,
                 num_updates: u128::from_be_bytes(j2),
@@ -206,6 +275,7 @@ impl PublishedFeedUpdate {
         num_updates: u128,
         variant: FeedType,
         digits_in_fraction: usize,
+        encoding: NumericalEncoding,
         data: &[u8],
     ) -> Result<PublishedFeedUpdate, PublishedFeedUpdateError> {
         if data.len() != 32 {
@@ -225,7 +295,7 @@ impl PublishedFeedUpdate {
             ));
         }
         let j1: [u8; 32] = data[0..32].try_into().expect("Impossible");
-        match FeedType::from_bytes(j1.to_vec(), variant, digits_in_fraction) {
+        match FeedType::from_bytes(j1.to_vec(), variant, digits_in_fraction, encoding) {
             Ok(value) => Ok(PublishedFeedUpdate {
                 feed_id,
                 num_updates,
@@ -244,27 +314,176 @@ impl PublishedFeedUpdate {
 #[cfg(test)]
 mod tests {
     use blocksense_utils::to_hex_string;
+    use std::collections::HashMap;
     use std::time::SystemTime;
 
-    use blocksense_feed_registry::types::FeedType;
+    use blocksense_feed_registry::market_hours::MarketCalendars;
+    use blocksense_feed_registry::types::{Decimal, FeedType, StructField};
+    use blocksense_registry::config::NumericalEncoding::{Signed, Unsigned};
 
     use super::*;
 
     #[test]
     fn naive_packing_numerical_value() {
         let value = 42.42;
-        let bytes = naive_packing(&FeedType::Numerical(value), 18, 0);
+        let bytes = naive_packing(&FeedType::Numerical(value), 18, 0).unwrap();
 
-        let reversed = FeedType::from_bytes(bytes, FeedType::Numerical(0.0), 18).unwrap();
+        let reversed = FeedType::from_bytes(bytes, FeedType::Numerical(0.0), 18, Unsigned).unwrap();
 
         assert_eq!(value.to_string(), reversed.parse_to_string());
     }
 
+    #[test]
+    fn naive_packing_decimal_value() {
+        let value = FeedType::Decimal("1234567.123456789012345678".parse().unwrap());
+        let bytes = naive_packing(&value, 18, 0).unwrap();
+
+        let reversed =
+            FeedType::from_bytes(bytes, FeedType::Decimal(Decimal::new(0, 0)), 18, Unsigned);
+
+        assert_eq!(reversed.unwrap(), value);
+    }
+
+    #[test]
+    fn naive_packing_decimal_matches_numerical_layout() {
+        let decimal = naive_packing(&FeedType::Decimal(Decimal::new(4242, 2)), 18, 42).unwrap();
+        let numerical = naive_packing(&FeedType::Numerical(42.42), 18, 42).unwrap();
+        assert_eq!(decimal, numerical);
+
+        let negative = naive_packing(&FeedType::Decimal(Decimal::new(-1, 0)), 0, 42).unwrap();
+        assert_eq!(negative[..24], [0xff; 24]);
+        assert_eq!(negative[24..], 42u64.to_be_bytes());
+    }
+
+    #[test]
+    fn naive_packing_signed_numerical_value() {
+        for value in [-42.42, -0.000001, 0.5, -123456789.0] {
+            let bytes = naive_packing(&FeedType::Numerical(value), 18, 0).unwrap();
+
+            let reversed = FeedType::from_bytes(bytes, FeedType::Numerical(0.0), 18, Signed);
+
+            assert_eq!(reversed.unwrap(), FeedType::Numerical(value));
+        }
+    }
+
+    #[test]
+    fn naive_packing_signed_decimal_value() {
+        let value = FeedType::Decimal(Decimal::new(-1_234_567_890_123, 8));
+        let bytes = naive_packing(&value, 8, 0).unwrap();
+
+        let reversed = FeedType::from_bytes(
+            bytes.clone(),
+            FeedType::Decimal(Decimal::new(0, 0)),
+            8,
+            Signed,
+        );
+        assert_eq!(reversed.unwrap(), value);
+
+        // Decimals are read as two's complement whatever the encoding of the feed.
+        let unsigned =
+            FeedType::from_bytes(bytes, FeedType::Decimal(Decimal::new(0, 0)), 8, Unsigned);
+        assert_eq!(unsigned.unwrap(), value);
+        let too_large = [vec![0x80; 24], vec![0; 8]].concat();
+        let unsigned = FeedType::from_bytes(
+            too_large,
+            FeedType::Decimal(Decimal::new(0, 0)),
+            8,
+            Unsigned,
+        );
+        assert!(unsigned.is_err());
+    }
+
+    fn bid_ask(bid: f64, ask: Decimal) -> FeedType {
+        FeedType::Struct(vec![
+            StructField::new("bid", FeedType::Numerical(bid)),
+            StructField::new("ask", FeedType::Decimal(ask)),
+        ])
+    }
+
+    #[test]
+    fn naive_packing_struct_value() {
+        let value = bid_ask(-42.42, Decimal::new(4243, 2));
+        let bytes = naive_packing(&value, 8, 42).unwrap();
+
+        // Every field is laid out like a standalone numerical value.
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(
+            bytes[..32],
+            naive_packing(&FeedType::Numerical(-42.42), 8, 42).unwrap()
+        );
+        assert_eq!(
+            bytes[32..],
+            naive_packing(&FeedType::Decimal(Decimal::new(4243, 2)), 8, 42).unwrap()
+        );
+
+        let variant = bid_ask(0.0, Decimal::new(0, 0));
+        let reversed = FeedType::from_bytes(bytes.clone(), variant.clone(), 8, Signed).unwrap();
+        assert_eq!(reversed, bid_ask(-42.42, Decimal::new(4_243_000_000, 8)));
+        assert_eq!(
+            reversed.parse_to_string(),
+            "{bid: -42.42, ask: 42.43000000}"
+        );
+
+        assert!(FeedType::from_bytes(bytes[..32].to_vec(), variant, 8, Signed).is_err());
+    }
+
+    #[test]
+    fn voted_feed_update_should_skip_struct() {
+        let feed_id = 57;
+        let one_percent_threshold = PublishCriteria {
+            feed_id,
+            skip_publish_if_less_then_percentage: 1.0f64,
+            always_publish_heartbeat_ms: None,
+            peg_to_value: None,
+            peg_tolerance_percentage: 0.0f64,
+        };
+        let mut history = FeedAggregateHistory::new();
+        history.register_feed(feed_id, 100);
+        history.push_next(feed_id, bid_ask(100.0, Decimal::new(100, 0)), 1000);
+
+        let update = |value| VotedFeedUpdate {
+            feed_id,
+            value,
+            end_slot_timestamp: 2000,
+        };
+        assert_eq!(
+            update(bid_ask(100.5, Decimal::new(1005, 1)))
+                .should_skip(&one_percent_threshold, &history),
+            SkipDecision::DoSkip(DoSkipReason::TooSimilarTooSoon)
+        );
+        // A single field crossing the threshold is enough.
+        assert_eq!(
+            update(bid_ask(100.5, Decimal::new(102, 0)))
+                .should_skip(&one_percent_threshold, &history),
+            SkipDecision::DontSkip(DontSkipReason::ThresholdCrossed)
+        );
+        let other_fields =
+            FeedType::Struct(vec![StructField::new("mid", FeedType::Numerical(100.0))]);
+        assert_eq!(
+            update(other_fields).should_skip(&one_percent_threshold, &history),
+            SkipDecision::DontSkip(DontSkipReason::HistoryError)
+        );
+    }
+
+    #[test]
+    fn published_feed_update_signed_value() {
+        let mut data = naive_packing(&FeedType::Numerical(-3.25), 8, 1_700_000_000).unwrap();
+        data.extend(vec![0u8; 16]);
+        data.extend(7u128.to_be_bytes());
+
+        let update =
+            PublishedFeedUpdate::latest(5, FeedType::Numerical(0.0), 8, Signed, &data).unwrap();
+
+        assert_eq!(update.value, FeedType::Numerical(-3.25));
+        assert_eq!(update.num_updates, 7);
+        assert_eq!(update.published, 1_700_000_000);
+    }
+
     #[test]
     fn naive_packing_string_value() {
         let value = "blocksense"; // size is 10
         let feed_value = FeedType::Text(value.to_string());
-        let bytes = naive_packing(&feed_value, 18, 0);
+        let bytes = naive_packing(&feed_value, 18, 0).unwrap();
 
         let mut buf = [0; 10];
         buf.copy_from_slice(&bytes[..10]);
@@ -281,7 +500,7 @@ mod tests {
             value: FeedType::Numerical(142.0),
             end_slot_timestamp,
         };
-        let (encoded_key, encoded_value) = update.encode(18, 0);
+        let (encoded_key, encoded_value) = update.encode(18, 0).unwrap();
         assert_eq!("0000002a", to_hex_string(encoded_key, None));
         assert_eq!(
             "00000000000000000000000000000007b2a557a6d97800000000000000000000",
@@ -289,15 +508,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn naive_packing_rejects_values_that_can_not_be_encoded() {
+        for value in [f64::NAN, f64::INFINITY, 1e40] {
+            assert!(naive_packing(&FeedType::Numerical(value), 18, 0).is_err());
+        }
+        let too_large = FeedType::Decimal(Decimal::new(i128::MAX, 0));
+        assert!(naive_packing(&too_large, 30, 0).is_err());
+        let with_nan = bid_ask(f64::NAN, Decimal::new(1, 0));
+        assert!(naive_packing(&with_nan, 8, 0).is_err());
+
+        let update = VotedFeedUpdate {
+            feed_id: 42,
+            value: FeedType::Numerical(f64::NAN),
+            end_slot_timestamp: 0,
+        };
+        assert!(update.encode(18, 0).is_err());
+    }
+
     #[test]
     fn voted_feed_update_new_decode() {
         let end_slot_timestamp = 1_735_902_088_000_u128; // 3 Jan 2025 time of refactoring this test
                                                          // Send test votes
         let k1 = "ab000001";
         let v1 = "000000000000000000000000000010f0da2079987e1000000000000000000000";
-        let vote_1 =
-            VotedFeedUpdate::new_decode(k1, v1, end_slot_timestamp, FeedType::Numerical(0.0), 18)
-                .unwrap();
+        let vote_1 = VotedFeedUpdate::new_decode(
+            k1,
+            v1,
+            end_slot_timestamp,
+            FeedType::Numerical(0.0),
+            18,
+            Unsigned,
+        )
+        .unwrap();
         assert_eq!(vote_1.feed_id, 2868903937_u32);
         assert_eq!(vote_1.value, FeedType::Numerical(80000.8f64));
     }
@@ -432,6 +675,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn voted_feed_update_heartbeat_pauses_while_market_is_closed() {
+        let feed_id = 56;
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let nyse = calendars.get(Some("NYSE")).unwrap();
+        let hour_ms = 3_600_000_u128;
+        let heartbeat_every_hour = PublishCriteria {
+            feed_id,
+            skip_publish_if_less_then_percentage: 1000.0f64,
+            always_publish_heartbeat_ms: Some(hour_ms),
+            peg_to_value: None,
+            peg_tolerance_percentage: 0.0f64,
+        };
+        let mut history = FeedAggregateHistory::new();
+        history.register_feed(feed_id, 100);
+        // Last published 30 minutes before Friday's close, 2025-01-17 15:30 New York time.
+        let friday_1530 = 1_737_145_800_000_u128;
+        history.push_next(feed_id, FeedType::Numerical(1000.0), friday_1530);
+
+        // Monday 09:45 New York time: only 45 minutes of trading have passed.
+        let update = VotedFeedUpdate {
+            feed_id,
+            value: FeedType::Numerical(1000.0),
+            end_slot_timestamp: friday_1530 + 66 * hour_ms + hour_ms / 4,
+        };
+        assert_eq!(
+            update.should_skip(&heartbeat_every_hour, &history),
+            SkipDecision::DontSkip(DontSkipReason::HeartbeatTimedOut)
+        );
+        assert_eq!(
+            update.should_skip_with_market_hours(&heartbeat_every_hour, &history, Some(&nyse)),
+            SkipDecision::DoSkip(DoSkipReason::TooSimilarTooSoon)
+        );
+
+        // Monday 10:00 New York time
+        let update = VotedFeedUpdate {
+            feed_id,
+            value: FeedType::Numerical(1000.0),
+            end_slot_timestamp: friday_1530 + 66 * hour_ms + hour_ms / 2,
+        };
+        assert_eq!(
+            update.should_skip_with_market_hours(&heartbeat_every_hour, &history, Some(&nyse)),
+            SkipDecision::DontSkip(DontSkipReason::HeartbeatTimedOut)
+        );
+    }
+
     #[test]
     fn test_voted_feed_update() {
         let end_slot_timestamp = SystemTime::now()
@@ -443,7 +732,7 @@ mod tests {
             value: FeedType::Numerical(142.0),
             end_slot_timestamp,
         };
-        let (encoded_key, encoded_value) = update.encode(18, 0);
+        let (encoded_key, encoded_value) = update.encode(18, 0).unwrap();
         assert_eq!("0000002a", to_hex_string(encoded_key, None));
         assert_eq!(
             "00000000000000000000000000000007b2a557a6d97800000000000000000000",
@@ -453,9 +742,15 @@ mod tests {
         // Send test votes
         let k1 = "ab000001";
         let v1 = "000000000000000000000000000010f0da2079987e1000000000000000000000";
-        let vote_1 =
-            VotedFeedUpdate::new_decode(k1, v1, end_slot_timestamp, FeedType::Numerical(0.0), 18)
-                .unwrap();
+        let vote_1 = VotedFeedUpdate::new_decode(
+            k1,
+            v1,
+            end_slot_timestamp,
+            FeedType::Numerical(0.0),
+            18,
+            Unsigned,
+        )
+        .unwrap();
         assert_eq!(vote_1.feed_id, 2868903937_u32);
         assert_eq!(vote_1.value, FeedType::Numerical(80000.8f64));
     }
@@ -1,8 +1,113 @@
+use anyhow::{anyhow, bail};
 use blocksense_crypto::{deserialize_priv_key, sign_message, Signature};
-use blocksense_feed_registry::types::{FeedResult, Timestamp};
+use blocksense_feed_registry::types::{
+    FeedError, FeedResult, FeedType, ReportSigningScheme, Timestamp,
+};
+
+/// Prefix of the messages signed with `ReportSigningScheme::V1`, which keeps them apart from
+/// anything else signed with a reporter's key.
+pub const REPORT_SIGNING_DOMAIN: &[u8] = b"blocksense/report";
+
+const NUMERICAL: u8 = 0;
+const TEXT: u8 = 1;
+const BYTES: u8 = 2;
+const DECIMAL: u8 = 3;
+const STRUCT: u8 = 4;
+const API_ERROR: u8 = 0x80;
+const UNDEFINED_ERROR: u8 = 0x81;
+
+fn extend_with_len_prefixed(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend((bytes.len() as u32).to_be_bytes());
+    buffer.extend(bytes);
+}
+
+/// Type tag followed by the exact value, so that values of different types or scales never
+/// encode the same. Non-finite numbers are not signed.
+fn extend_with_value(buffer: &mut Vec<u8>, value: &FeedType) -> anyhow::Result<()> {
+    match value {
+        FeedType::Numerical(value) => {
+            if !value.is_finite() {
+                bail!("Can not sign non-finite value {value}");
+            }
+            buffer.push(NUMERICAL);
+            buffer.extend(value.to_bits().to_be_bytes());
+        }
+        FeedType::Text(value) => {
+            buffer.push(TEXT);
+            extend_with_len_prefixed(buffer, value.as_bytes());
+        }
+        FeedType::Bytes(value) => {
+            buffer.push(BYTES);
+            extend_with_len_prefixed(buffer, value);
+        }
+        FeedType::Decimal(value) => {
+            buffer.push(DECIMAL);
+            buffer.extend(value.mantissa.to_be_bytes());
+            buffer.push(value.scale);
+        }
+        FeedType::Struct(fields) => {
+            buffer.push(STRUCT);
+            buffer.extend((fields.len() as u32).to_be_bytes());
+            for field in fields {
+                extend_with_len_prefixed(buffer, field.name.as_bytes());
+                extend_with_value(buffer, &field.value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The bytes a reporter signs for a report. `deployment_id` is only part of the `V1` message.
+/// Fails for values that can not be signed, like NaN, or for `V0` values that do not fit in
+/// their on-chain encoding.
+pub fn report_signing_message(
+    scheme: ReportSigningScheme,
+    deployment_id: &str,
+    feed_id: &str,
+    timestamp: Timestamp,
+    feed_result: &FeedResult,
+) -> anyhow::Result<Vec<u8>> {
+    match scheme {
+        ReportSigningScheme::V0 => {
+            let mut byte_buffer: Vec<u8> = feed_id
+                .as_bytes()
+                .iter()
+                .copied()
+                .chain(timestamp.to_be_bytes().to_vec())
+                .collect();
+
+            if let Ok(result) = feed_result {
+                byte_buffer.extend(
+                    result
+                        .as_bytes(18, timestamp as u64)
+                        .map_err(|e| anyhow!("Can not sign value of feed {feed_id}: {e}"))?,
+                );
+            }
+            Ok(byte_buffer)
+        }
+        ReportSigningScheme::V1 => {
+            let mut byte_buffer = REPORT_SIGNING_DOMAIN.to_vec();
+            byte_buffer.push(scheme.version());
+            extend_with_len_prefixed(&mut byte_buffer, deployment_id.as_bytes());
+            extend_with_len_prefixed(&mut byte_buffer, feed_id.as_bytes());
+            byte_buffer.extend(timestamp.to_be_bytes());
+            match feed_result {
+                Ok(value) => extend_with_value(&mut byte_buffer, value)?,
+                Err(FeedError::APIError(error)) => {
+                    byte_buffer.push(API_ERROR);
+                    extend_with_len_prefixed(&mut byte_buffer, error.as_bytes());
+                }
+                Err(FeedError::UndefinedError) => byte_buffer.push(UNDEFINED_ERROR),
+            }
+            Ok(byte_buffer)
+        }
+    }
+}
 
 pub fn generate_signature(
     priv_key_hex: &str,
+    scheme: ReportSigningScheme,
+    deployment_id: &str,
     feed_id: &str,
     timestamp: Timestamp,
     feed_result: &FeedResult,
@@ -10,21 +115,124 @@ pub fn generate_signature(
     //TODO(adikov): refactor crypto lib to return proper Results, not <val, string>
     let priv_key = deserialize_priv_key(priv_key_hex).expect("Wrong key format!");
 
-    let mut byte_buffer: Vec<u8> = feed_id
-        .as_bytes()
-        .iter()
-        .copied()
-        .chain(timestamp.to_be_bytes().to_vec())
-        .collect();
+    let message = report_signing_message(scheme, deployment_id, feed_id, timestamp, feed_result)?;
 
-    match feed_result {
-        Ok(result) => {
-            byte_buffer.extend(result.as_bytes(18, timestamp as u64));
-        }
-        Err(error) => {
-            log::warn!("Error parsing recvd result of vote: {}", error);
+    Ok(sign_message(&priv_key, &message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocksense_crypto::verify_signature;
+    use blocksense_feed_registry::types::{Decimal, StructField};
+
+    const SECRET_KEY: &str = "536d1f9d97166eba5ff0efb8cc8dbeb856fb13d2d126ed1efc761e9955014003";
+
+    #[test]
+    fn test_report_signing_schemes() {
+        let timestamp = 1_700_000_000_000;
+        let result = Ok(FeedType::Decimal(Decimal::new(12345, 2)));
+        let message = |scheme, deployment_id, result: &FeedResult| {
+            report_signing_message(scheme, deployment_id, "1", timestamp, result).unwrap()
+        };
+
+        // V0 does not depend on the deployment, nor on the scale of decimals.
+        assert_eq!(
+            message(ReportSigningScheme::V0, "a", &result),
+            message(ReportSigningScheme::V0, "b", &result)
+        );
+        assert_eq!(
+            message(ReportSigningScheme::V0, "a", &result),
+            message(
+                ReportSigningScheme::V0,
+                "a",
+                &Ok(FeedType::Decimal(Decimal::new(123450, 3)))
+            )
+        );
+
+        let v1 = message(ReportSigningScheme::V1, "a", &result);
+        assert!(v1.starts_with(REPORT_SIGNING_DOMAIN));
+        assert_ne!(v1, message(ReportSigningScheme::V0, "a", &result));
+        assert_ne!(v1, message(ReportSigningScheme::V1, "b", &result));
+        assert_ne!(
+            v1,
+            message(
+                ReportSigningScheme::V1,
+                "a",
+                &Ok(FeedType::Decimal(Decimal::new(123450, 3)))
+            )
+        );
+        assert_ne!(
+            message(ReportSigningScheme::V1, "a", &Ok(FeedType::Numerical(1.0))),
+            message(ReportSigningScheme::V1, "a", &Ok(FeedType::Bytes(vec![])))
+        );
+        assert_ne!(
+            message(
+                ReportSigningScheme::V1,
+                "a",
+                &Err(FeedError::APIError("timeout".to_string()))
+            ),
+            message(
+                ReportSigningScheme::V1,
+                "a",
+                &Err(FeedError::UndefinedError)
+            )
+        );
+        // The length prefixes keep the split between the fields of a struct unambiguous.
+        let field = |name: &str, value: &str| StructField::new(name, FeedType::Text(value.into()));
+        assert_ne!(
+            message(
+                ReportSigningScheme::V1,
+                "a",
+                &Ok(FeedType::Struct(vec![field("ab", "c")]))
+            ),
+            message(
+                ReportSigningScheme::V1,
+                "a",
+                &Ok(FeedType::Struct(vec![field("a", "bc")]))
+            )
+        );
+
+        // Values that can not be signed are rejected instead of being signed without their bytes.
+        for scheme in [ReportSigningScheme::V0, ReportSigningScheme::V1] {
+            for value in [f64::NAN, f64::INFINITY] {
+                let result = Ok(FeedType::Numerical(value));
+                assert!(report_signing_message(scheme, "a", "1", timestamp, &result).is_err());
+            }
         }
-    };
+        assert!(report_signing_message(
+            ReportSigningScheme::V0,
+            "a",
+            "1",
+            timestamp,
+            &Ok(FeedType::Numerical(1e40))
+        )
+        .is_err());
+        assert!(generate_signature(
+            SECRET_KEY,
+            ReportSigningScheme::V0,
+            "a",
+            "1",
+            timestamp,
+            &Ok(FeedType::Numerical(f64::NAN))
+        )
+        .is_err());
 
-    Ok(sign_message(&priv_key, &byte_buffer))
+        let signature = generate_signature(
+            SECRET_KEY,
+            ReportSigningScheme::V1,
+            "a",
+            "1",
+            timestamp,
+            &result,
+        )
+        .unwrap();
+        let pub_key = deserialize_priv_key(SECRET_KEY).unwrap().sk_to_pk();
+        assert!(verify_signature(&pub_key, &signature, &v1));
+        assert!(!verify_signature(
+            &pub_key,
+            &signature,
+            &message(ReportSigningScheme::V1, "b", &result)
+        ));
+    }
 }
@@ -4,36 +4,214 @@ use std::{
 };
 
 use num::Integer;
-use tracing::{error, info_span};
+use thiserror::Error;
+use tracing::info_span;
 
-use super::types::FeedType;
+use blocksense_config::Aggregation;
+pub use blocksense_config::DEFAULT_TRIM_PERCENTAGE;
+use blocksense_registry::config::StructFieldConfig;
 
-#[derive(Clone, Copy)]
+use super::types::{Decimal, FeedType, StructField};
+
+/// Share of the votes dropped from each end by the interquartile mean.
+const INTERQUARTILE_TRIM_PERCENTAGE: f64 = 25.0;
+
+#[derive(Clone)]
 pub enum FeedAggregate {
     AverageAggregator,
     MedianAggregator,
     MajorityVoteAggregator,
+    /// Drops the given percentage of the lowest and of the highest votes and
+    /// averages the rest.
+    TrimmedMeanAggregator(f64),
+    WeightedMedianAggregator,
+    InterquartileMeanAggregator,
+    /// Aggregates every field of struct values with its own aggregator.
+    StructAggregator(Vec<(String, FeedAggregate)>),
 }
 
-impl FeedAggregate {
-    pub fn aggregate(&self, values: &[FeedType]) -> FeedType {
-        match self {
-            FeedAggregate::AverageAggregator => {
-                let span = info_span!("AverageAggregator");
-                let _guard = span.enter();
-                assert!(!values.is_empty());
-                let num_elements = values.len() as f64;
-                let mut filtered = Vec::new();
+#[derive(Debug, Clone, Error)]
+pub enum AggregationError {
+    #[error("Aggregating empty set of values")]
+    EmptySet,
+
+    #[error("Non-finite value {0} can not be aggregated")]
+    NonFiniteValue(f64),
+
+    #[error("Aggregator expects {expected} values, but got {found}")]
+    TypeMismatch {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("Aggregated value overflows the decimal representation")]
+    Overflow,
+
+    #[error("Struct value is missing field {0}")]
+    MissingField(String),
 
+    #[error("Got {weights} weights for {values} values")]
+    WeightsMismatch { values: usize, weights: usize },
+
+    #[error("Invalid weight {0}, weights must be finite and non-negative")]
+    InvalidWeight(f64),
+}
+
+pub type AggregationResult = Result<FeedType, AggregationError>;
+
+fn type_mismatch(expected: &'static str, found: &FeedType) -> AggregationError {
+    AggregationError::TypeMismatch {
+        expected,
+        found: found.enum_type_to_string().to_string(),
+    }
+}
+
+/// Numerical votes sorted in ascending order together with their weights.
+/// Decimal votes are brought to a common scale, so that they can be summed
+/// and compared exactly.
+enum SortedNumbers {
+    Float(Vec<(f64, f64)>),
+    Decimal {
+        mantissas: Vec<(i128, f64)>,
+        scale: u8,
+    },
+}
+
+impl SortedNumbers {
+    fn new(values: &[FeedType], weights: &[f64]) -> Result<SortedNumbers, AggregationError> {
+        if values.len() != weights.len() {
+            return Err(AggregationError::WeightsMismatch {
+                values: values.len(),
+                weights: weights.len(),
+            });
+        }
+        if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+            return Err(AggregationError::InvalidWeight(*weight));
+        }
+        match values.first() {
+            None => Err(AggregationError::EmptySet),
+            Some(FeedType::Decimal(_)) => {
+                let mut decimals = Vec::with_capacity(values.len());
                 for value in values {
                     match value {
-                        FeedType::Numerical(x) => filtered.push(x),
-                        _ => error!("Attempting to perform arithmetic on non-numerical type!"), //TODO(snikolov): What level of error?
-                    };
+                        FeedType::Decimal(d) => decimals.push(*d),
+                        _ => return Err(type_mismatch("FeedType::Decimal", value)),
+                    }
+                }
+                let scale = decimals.iter().map(|d| d.scale).max().unwrap_or(0);
+                let mut mantissas = Vec::with_capacity(decimals.len());
+                for (d, weight) in decimals.iter().zip(weights) {
+                    let d = d.rescale(scale).ok_or(AggregationError::Overflow)?;
+                    mantissas.push((d.mantissa, *weight));
+                }
+                mantissas.sort_by_key(|(m, _)| *m);
+                Ok(SortedNumbers::Decimal { mantissas, scale })
+            }
+            Some(_) => {
+                let mut numbers = Vec::with_capacity(values.len());
+                for (value, weight) in values.iter().zip(weights) {
+                    match value {
+                        FeedType::Numerical(x) if x.is_finite() => numbers.push((*x, *weight)),
+                        FeedType::Numerical(x) => return Err(AggregationError::NonFiniteValue(*x)),
+                        _ => return Err(type_mismatch("FeedType::Numerical", value)),
+                    }
+                }
+                numbers.sort_by(|a, b| a.0.total_cmp(&b.0));
+                Ok(SortedNumbers::Float(numbers))
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SortedNumbers::Float(numbers) => numbers.len(),
+            SortedNumbers::Decimal { mantissas, .. } => mantissas.len(),
+        }
+    }
+
+    fn weight(&self, index: usize) -> f64 {
+        match self {
+            SortedNumbers::Float(numbers) => numbers[index].1,
+            SortedNumbers::Decimal { mantissas, .. } => mantissas[index].1,
+        }
+    }
+
+    /// Mean of the votes at the given positions in sorted order. Decimal
+    /// means are truncated towards zero at the common scale.
+    fn mean(&self, indices: impl ExactSizeIterator<Item = usize>) -> AggregationResult {
+        let count = indices.len();
+        match self {
+            SortedNumbers::Float(numbers) => {
+                let sum: f64 = indices.map(|i| numbers[i].0).sum();
+                Ok(FeedType::Numerical(sum / count as f64))
+            }
+            SortedNumbers::Decimal { mantissas, scale } => {
+                let mut sum = 0i128;
+                for i in indices {
+                    sum = sum
+                        .checked_add(mantissas[i].0)
+                        .ok_or(AggregationError::Overflow)?;
                 }
+                Ok(FeedType::Decimal(Decimal::new(sum / count as i128, *scale)))
+            }
+        }
+    }
+
+    fn median(&self) -> AggregationResult {
+        let size = self.len();
+        let middle = size / 2;
+        if size.is_odd() {
+            self.mean(middle..middle + 1)
+        } else {
+            self.mean(middle - 1..middle + 1)
+        }
+    }
+
+    fn trimmed_mean(&self, trim_percentage: f64) -> AggregationResult {
+        let size = self.len();
+        let to_trim = ((size as f64) * trim_percentage / 100.0).floor() as usize;
+        self.mean(to_trim..size - to_trim)
+    }
+
+    fn weighted_median(&self) -> AggregationResult {
+        let size = self.len();
+        let total: f64 = (0..size).map(|i| self.weight(i)).sum();
+        let half = total / 2.0;
+        let mut cumulative = 0.0;
+        for i in 0..size {
+            cumulative += self.weight(i);
+            if cumulative > half {
+                return self.mean(i..i + 1);
+            }
+            // The lower half ends exactly here, so the median lies between this
+            // vote and the next one that carries any weight.
+            if cumulative == half {
+                return match (i + 1..size).find(|j| self.weight(*j) > 0.0) {
+                    Some(next) => self.mean([i, next].into_iter()),
+                    None => self.mean(i..i + 1),
+                };
+            }
+        }
+        self.mean(size - 1..size)
+    }
+}
+
+impl FeedAggregate {
+    pub fn aggregate(&self, values: &[FeedType]) -> AggregationResult {
+        let weights = vec![1.0; values.len()];
+        self.aggregate_weighted(values, &weights)
+    }
 
-                let sum: f64 = filtered.into_iter().sum();
-                FeedType::Numerical(sum / num_elements)
+    /// Aggregates `values`, where `weights[i]` is the weight of `values[i]`.
+    /// Only the weighted median takes the weights into account, every other
+    /// aggregator treats all votes equally.
+    pub fn aggregate_weighted(&self, values: &[FeedType], weights: &[f64]) -> AggregationResult {
+        match self {
+            FeedAggregate::AverageAggregator => {
+                let span = info_span!("AverageAggregator");
+                let _guard = span.enter();
+                let numbers = SortedNumbers::new(values, weights)?;
+                numbers.mean(0..numbers.len())
             }
             FeedAggregate::MajorityVoteAggregator => {
                 let span = info_span!("MajorityVoteAggregator");
@@ -45,9 +223,7 @@ impl FeedAggregate {
                 for v in values {
                     match v {
                         FeedType::Text(t) => *frequency_map.entry(t).or_insert(0) += 1,
-                        _ => {
-                            error!("Attempting to perform frequency_map on f64!");
-                        }
+                        _ => return Err(type_mismatch("FeedType::Text", v)),
                     }
                 }
 
@@ -56,53 +232,127 @@ impl FeedAggregate {
                     .into_iter()
                     .max_by_key(|&(_, count)| count)
                     .map(|(s, _)| s)
-                    .expect("Aggregating empty set of values!")
+                    .ok_or(AggregationError::EmptySet)?
                     .clone();
-                FeedType::Text(result)
+                Ok(FeedType::Text(result))
             }
             FeedAggregate::MedianAggregator => {
                 let span = info_span!("MedianAggregator");
                 let _guard = span.enter();
-                let mut filtered = Vec::new();
-
-                for value in values {
-                    match value {
-                        FeedType::Numerical(x) => filtered.push(x),
-                        _ => error!("Attempting to perform arithmetic on non-numerical type!"), //TODO(snikolov): What level of error?
-                    };
+                SortedNumbers::new(values, weights)?.median()
+            }
+            FeedAggregate::TrimmedMeanAggregator(trim_percentage) => {
+                let span = info_span!("TrimmedMeanAggregator");
+                let _guard = span.enter();
+                SortedNumbers::new(values, weights)?.trimmed_mean(*trim_percentage)
+            }
+            FeedAggregate::WeightedMedianAggregator => {
+                let span = info_span!("WeightedMedianAggregator");
+                let _guard = span.enter();
+                SortedNumbers::new(values, weights)?.weighted_median()
+            }
+            FeedAggregate::InterquartileMeanAggregator => {
+                let span = info_span!("InterquartileMeanAggregator");
+                let _guard = span.enter();
+                SortedNumbers::new(values, weights)?.trimmed_mean(INTERQUARTILE_TRIM_PERCENTAGE)
+            }
+            FeedAggregate::StructAggregator(fields) => {
+                let span = info_span!("StructAggregator");
+                let _guard = span.enter();
+                if values.is_empty() {
+                    return Err(AggregationError::EmptySet);
                 }
-                let size = filtered.len();
-                assert!(size > 0);
-                filtered.sort_by(|a, b| {
-                    a.partial_cmp(b)
-                        .expect("Ordering between elements does not exists.")
-                });
-
-                let middle = size / 2;
-                if size.is_odd() {
-                    FeedType::Numerical(*filtered[middle])
-                } else {
-                    FeedType::Numerical((filtered[middle] + filtered[middle - 1]) / 2.0)
+                let mut aggregated = Vec::with_capacity(fields.len());
+                for (index, (name, aggregator)) in fields.iter().enumerate() {
+                    let mut field_values = Vec::with_capacity(values.len());
+                    for value in values {
+                        let FeedType::Struct(value_fields) = value else {
+                            return Err(type_mismatch("FeedType::Struct", value));
+                        };
+                        match value_fields.get(index) {
+                            Some(field) if field.name == *name => {
+                                field_values.push(field.value.clone())
+                            }
+                            _ => return Err(AggregationError::MissingField(name.clone())),
+                        }
+                    }
+                    aggregated.push(StructField {
+                        name: name.clone(),
+                        value: aggregator.aggregate_weighted(&field_values, weights)?,
+                    });
                 }
+                Ok(FeedType::Struct(aggregated))
             }
         }
     }
+
+    /// Parses the `aggregation` field of a feed config. The trimmed mean
+    /// accepts an optional trim percentage, e.g. `trimmed_mean:20`.
     pub fn create_from_str(aggregate_type: &str) -> anyhow::Result<Self> {
-        match aggregate_type {
-            "median" => Ok(Self::MedianAggregator),
-            "majority" => Ok(Self::MajorityVoteAggregator),
-            "average" => Ok(Self::AverageAggregator),
-            _ => anyhow::bail!("Could not convert {aggregate_type} to a valid aggregator!"),
+        Ok(match aggregate_type.parse::<Aggregation>()? {
+            Aggregation::Median => Self::MedianAggregator,
+            Aggregation::Majority => Self::MajorityVoteAggregator,
+            Aggregation::Average => Self::AverageAggregator,
+            Aggregation::TrimmedMean(trim_percentage) => {
+                Self::TrimmedMeanAggregator(trim_percentage)
+            }
+            Aggregation::WeightedMedian => Self::WeightedMedianAggregator,
+            Aggregation::InterquartileMean => Self::InterquartileMeanAggregator,
+        })
+    }
+
+    /// Creates the aggregator of a struct feed. Fields without an
+    /// aggregation of their own use `default_aggregation`.
+    pub fn create_for_struct(
+        fields: &[StructFieldConfig],
+        default_aggregation: &str,
+    ) -> anyhow::Result<Self> {
+        let mut aggregators = Vec::with_capacity(fields.len());
+        for field in fields {
+            let aggregation = field.aggregation.as_deref().unwrap_or(default_aggregation);
+            let aggregator = Self::create_from_str(aggregation)?;
+            if matches!(
+                aggregator,
+                Self::MajorityVoteAggregator | Self::StructAggregator(_)
+            ) {
+                anyhow::bail!(
+                    "Aggregation {aggregation} can not be used for struct field {}!",
+                    field.name
+                );
+            }
+            aggregators.push((field.name.clone(), aggregator));
         }
+        Ok(Self::StructAggregator(aggregators))
     }
 }
 
+/// Lists the aggregators of the fields, e.g. `bid: MedianAggregator, ask: AverageAggregator`.
+fn fmt_struct_aggregators(
+    f: &mut core::fmt::Formatter<'_>,
+    fields: &[(String, FeedAggregate)],
+) -> core::fmt::Result {
+    write!(f, "StructAggregator(")?;
+    for (index, (name, aggregator)) in fields.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{name}: {aggregator}")?;
+    }
+    write!(f, ")")
+}
+
 impl Display for FeedAggregate {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             FeedAggregate::AverageAggregator => write!(f, "AverageAggregator"),
             FeedAggregate::MajorityVoteAggregator => write!(f, "FeedAggregate"),
             FeedAggregate::MedianAggregator => write!(f, "MedianAggregator"),
+            FeedAggregate::TrimmedMeanAggregator(trim_percentage) => {
+                write!(f, "TrimmedMeanAggregator({trim_percentage}%)")
+            }
+            FeedAggregate::WeightedMedianAggregator => write!(f, "WeightedMedianAggregator"),
+            FeedAggregate::InterquartileMeanAggregator => write!(f, "InterquartileMeanAggregator"),
+            FeedAggregate::StructAggregator(fields) => fmt_struct_aggregators(f, fields),
         }
     }
 }
@@ -113,6 +363,12 @@ impl Debug for FeedAggregate {
             FeedAggregate::AverageAggregator => write!(f, "AverageAggregator"),
             FeedAggregate::MajorityVoteAggregator => write!(f, "FeedAggregate"),
             FeedAggregate::MedianAggregator => write!(f, "MedianAggregator"),
+            FeedAggregate::TrimmedMeanAggregator(trim_percentage) => {
+                write!(f, "TrimmedMeanAggregator({trim_percentage}%)")
+            }
+            FeedAggregate::WeightedMedianAggregator => write!(f, "WeightedMedianAggregator"),
+            FeedAggregate::InterquartileMeanAggregator => write!(f, "InterquartileMeanAggregator"),
+            FeedAggregate::StructAggregator(fields) => fmt_struct_aggregators(f, fields),
         }
     }
 }
@@ -130,7 +386,7 @@ mod tests {
             .map(FeedType::Numerical)
             .collect();
 
-        let result = aggregator.aggregate(&values[..]);
+        let result = aggregator.aggregate(&values[..]).unwrap();
         let expected_result = (2. + 2. + 3.) / 3.;
         assert_eq!(FeedType::Numerical(expected_result), result);
     }
@@ -144,7 +400,7 @@ mod tests {
             .map(FeedType::Numerical)
             .collect();
 
-        let result = aggregator.aggregate(&values[..]);
+        let result = aggregator.aggregate(&values[..]).unwrap();
 
         assert_eq!(result, FeedType::Numerical(0.));
     }
@@ -161,7 +417,7 @@ mod tests {
         .map(FeedType::Numerical)
         .collect();
 
-        let result = aggregator.aggregate(&values[..]);
+        let result = aggregator.aggregate(&values[..]).unwrap();
 
         assert_eq!(
             result,
@@ -175,8 +431,310 @@ mod tests {
 
         let values: Vec<FeedType> = vec![0., 0.].into_iter().map(FeedType::Numerical).collect();
 
-        let result = aggregator.aggregate(&values[..]);
+        let result = aggregator.aggregate(&values[..]).unwrap();
 
         assert_ne!(result, FeedType::Numerical(0.00000000001));
     }
+
+    fn numerical(values: Vec<f64>) -> Vec<FeedType> {
+        values.into_iter().map(FeedType::Numerical).collect()
+    }
+
+    #[test]
+    fn test_trimmed_mean_aggregator_drops_outliers() {
+        let aggregator = FeedAggregate::TrimmedMeanAggregator(10.0);
+
+        let values = numerical(vec![1000., 10., 11., 12., 9., 10., 11., 9., 10., -500.]);
+
+        let result = aggregator.aggregate(&values[..]).unwrap();
+
+        assert_eq!(result, FeedType::Numerical(82. / 8.));
+    }
+
+    #[test]
+    fn test_trimmed_mean_aggregator_small_set() {
+        let aggregator = FeedAggregate::TrimmedMeanAggregator(20.0);
+
+        // 20% of 3 votes rounds down to nothing to trim.
+        let values = numerical(vec![1., 2., 6.]);
+
+        let result = aggregator.aggregate(&values[..]).unwrap();
+
+        assert_eq!(result, FeedType::Numerical(3.));
+    }
+
+    #[test]
+    fn test_interquartile_mean_aggregator() {
+        let aggregator = FeedAggregate::InterquartileMeanAggregator;
+
+        let values = numerical(vec![8., 1., 100., 3., 5., 4., 2., 7.]);
+
+        let result = aggregator.aggregate(&values[..]).unwrap();
+
+        assert_eq!(result, FeedType::Numerical((3. + 4. + 5. + 7.) / 4.));
+    }
+
+    #[test]
+    fn test_weighted_median_aggregator() {
+        let aggregator = FeedAggregate::WeightedMedianAggregator;
+
+        let values = numerical(vec![1., 2., 3., 4.]);
+
+        let result = aggregator
+            .aggregate_weighted(&values[..], &[1., 1., 1., 5.])
+            .unwrap();
+        assert_eq!(result, FeedType::Numerical(4.));
+
+        let result = aggregator
+            .aggregate_weighted(&values[..], &[3., 1., 1., 1.])
+            .unwrap();
+        assert_eq!(result, FeedType::Numerical(1.5));
+    }
+
+    #[test]
+    fn test_weighted_median_aggregator_equal_weights() {
+        let aggregator = FeedAggregate::WeightedMedianAggregator;
+
+        let odd = numerical(vec![5., 1., 3.]);
+        assert_eq!(
+            aggregator.aggregate(&odd[..]).unwrap(),
+            FeedType::Numerical(3.)
+        );
+
+        let even = numerical(vec![4., 1., 3., 2.]);
+        assert_eq!(
+            aggregator.aggregate(&even[..]).unwrap(),
+            FeedType::Numerical(2.5)
+        );
+    }
+
+    #[test]
+    fn test_create_from_str() {
+        assert!(matches!(
+            FeedAggregate::create_from_str("trimmed_mean").unwrap(),
+            FeedAggregate::TrimmedMeanAggregator(p) if p == DEFAULT_TRIM_PERCENTAGE
+        ));
+        assert!(matches!(
+            FeedAggregate::create_from_str("trimmed_mean:20").unwrap(),
+            FeedAggregate::TrimmedMeanAggregator(p) if p == 20.0
+        ));
+        assert!(matches!(
+            FeedAggregate::create_from_str("weighted_median").unwrap(),
+            FeedAggregate::WeightedMedianAggregator
+        ));
+        assert!(matches!(
+            FeedAggregate::create_from_str("interquartile_mean").unwrap(),
+            FeedAggregate::InterquartileMeanAggregator
+        ));
+        assert!(FeedAggregate::create_from_str("trimmed_mean:50").is_err());
+        assert!(FeedAggregate::create_from_str("trimmed_mean:abc").is_err());
+        assert!(FeedAggregate::create_from_str("mode").is_err());
+    }
+
+    #[test]
+    fn test_aggregate_empty_set() {
+        let aggregators = [
+            FeedAggregate::AverageAggregator,
+            FeedAggregate::MedianAggregator,
+            FeedAggregate::MajorityVoteAggregator,
+            FeedAggregate::TrimmedMeanAggregator(DEFAULT_TRIM_PERCENTAGE),
+            FeedAggregate::WeightedMedianAggregator,
+            FeedAggregate::InterquartileMeanAggregator,
+        ];
+        for aggregator in aggregators {
+            assert!(matches!(
+                aggregator.aggregate(&[]),
+                Err(AggregationError::EmptySet)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_aggregate_weights_mismatch() {
+        let values = numerical(vec![1., 2., 3.]);
+        assert!(matches!(
+            FeedAggregate::WeightedMedianAggregator.aggregate_weighted(&values, &[1.0, 2.0]),
+            Err(AggregationError::WeightsMismatch {
+                values: 3,
+                weights: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_invalid_weights() {
+        let values = numerical(vec![1., 2., 3.]);
+        assert!(matches!(
+            FeedAggregate::WeightedMedianAggregator.aggregate_weighted(&values, &[1.0, -1.0, 1.0]),
+            Err(AggregationError::InvalidWeight(w)) if w == -1.0
+        ));
+        assert!(matches!(
+            FeedAggregate::MedianAggregator.aggregate_weighted(&values, &[1.0, 1.0, f64::NAN]),
+            Err(AggregationError::InvalidWeight(w)) if w.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_non_finite_value() {
+        let values = numerical(vec![1., f64::NAN, 3.]);
+        assert!(matches!(
+            FeedAggregate::MedianAggregator.aggregate(&values[..]),
+            Err(AggregationError::NonFiniteValue(x)) if x.is_nan()
+        ));
+
+        let values = numerical(vec![1., f64::INFINITY]);
+        assert!(matches!(
+            FeedAggregate::AverageAggregator.aggregate(&values[..]),
+            Err(AggregationError::NonFiniteValue(x)) if x == f64::INFINITY
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_type_mismatch() {
+        let values = [FeedType::Numerical(1.), FeedType::Text("1".to_string())];
+        assert!(matches!(
+            FeedAggregate::AverageAggregator.aggregate(&values[..]),
+            Err(AggregationError::TypeMismatch {
+                expected: "FeedType::Numerical",
+                ..
+            })
+        ));
+        assert!(matches!(
+            FeedAggregate::MajorityVoteAggregator.aggregate(&values[..]),
+            Err(AggregationError::TypeMismatch {
+                expected: "FeedType::Text",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decimal_aggregation_is_exact() {
+        let values = vec![
+            FeedType::Decimal(Decimal::new(105, 2)),
+            FeedType::Decimal(Decimal::new(2, 0)),
+            FeedType::Decimal(Decimal::new(31, 1)),
+        ];
+
+        let median = FeedAggregate::MedianAggregator.aggregate(&values).unwrap();
+        assert_eq!(median, FeedType::Decimal(Decimal::new(200, 2)));
+
+        let average = FeedAggregate::AverageAggregator.aggregate(&values).unwrap();
+        assert_eq!(average, FeedType::Decimal(Decimal::new(205, 2)));
+
+        let values = vec![
+            FeedType::Decimal(Decimal::new(100_000_000_000_000_001, 18)),
+            FeedType::Decimal(Decimal::new(100_000_000_000_000_003, 18)),
+        ];
+        let average = FeedAggregate::AverageAggregator.aggregate(&values).unwrap();
+        assert_eq!(
+            average,
+            FeedType::Decimal(Decimal::new(100_000_000_000_000_002, 18))
+        );
+    }
+
+    #[test]
+    fn test_decimal_aggregation_errors() {
+        let values = vec![
+            FeedType::Decimal(Decimal::new(i128::MAX, 0)),
+            FeedType::Decimal(Decimal::new(1, 1)),
+        ];
+        assert!(matches!(
+            FeedAggregate::MedianAggregator.aggregate(&values),
+            Err(AggregationError::Overflow)
+        ));
+
+        let values = vec![
+            FeedType::Decimal(Decimal::new(1, 1)),
+            FeedType::Numerical(0.1),
+        ];
+        assert!(matches!(
+            FeedAggregate::AverageAggregator.aggregate(&values),
+            Err(AggregationError::TypeMismatch {
+                expected: "FeedType::Decimal",
+                ..
+            })
+        ));
+    }
+
+    fn bid_ask(bid: f64, ask: Decimal) -> FeedType {
+        FeedType::Struct(vec![
+            StructField::new("bid", FeedType::Numerical(bid)),
+            StructField::new("ask", FeedType::Decimal(ask)),
+        ])
+    }
+
+    fn field_config(name: &str, value_type: &str, aggregation: Option<&str>) -> StructFieldConfig {
+        StructFieldConfig {
+            name: name.to_string(),
+            value_type: value_type.to_string(),
+            aggregation: aggregation.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_struct_aggregator_aggregates_fields_separately() {
+        let aggregator = FeedAggregate::create_for_struct(
+            &[
+                field_config("bid", "numerical", None),
+                field_config("ask", "decimal", Some("average")),
+            ],
+            "median",
+        )
+        .unwrap();
+        assert_eq!(
+            aggregator.to_string(),
+            "StructAggregator(bid: MedianAggregator, ask: AverageAggregator)"
+        );
+
+        let values = vec![
+            bid_ask(1., Decimal::new(110, 2)),
+            bid_ask(100., Decimal::new(120, 2)),
+            bid_ask(2., Decimal::new(160, 2)),
+        ];
+        assert_eq!(
+            aggregator.aggregate(&values).unwrap(),
+            bid_ask(2., Decimal::new(130, 2))
+        );
+    }
+
+    #[test]
+    fn test_struct_aggregator_errors() {
+        assert!(FeedAggregate::create_for_struct(
+            &[field_config("bid", "numerical", Some("majority"))],
+            "median"
+        )
+        .is_err());
+
+        let aggregator = FeedAggregate::create_for_struct(
+            &[
+                field_config("bid", "numerical", None),
+                field_config("ask", "decimal", None),
+            ],
+            "median",
+        )
+        .unwrap();
+        assert!(matches!(
+            aggregator.aggregate(&[]),
+            Err(AggregationError::EmptySet)
+        ));
+
+        let values = vec![
+            bid_ask(1., Decimal::new(1, 0)),
+            FeedType::Struct(vec![StructField::new("bid", FeedType::Numerical(1.))]),
+        ];
+        assert!(matches!(
+            aggregator.aggregate(&values),
+            Err(AggregationError::MissingField(name)) if name == "ask"
+        ));
+
+        let values = vec![bid_ask(1., Decimal::new(1, 0)), FeedType::Numerical(1.)];
+        assert!(matches!(
+            aggregator.aggregate(&values),
+            Err(AggregationError::TypeMismatch {
+                expected: "FeedType::Struct",
+                ..
+            })
+        ));
+    }
 }
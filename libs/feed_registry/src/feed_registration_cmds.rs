@@ -11,10 +11,17 @@ pub struct DeleteAssetFeed {
     pub id: u32,
 }
 
+/// Replaces the config of a registered feed, keeping its history and round counters.
+#[derive(Debug, Clone)]
+pub struct UpdateAssetFeed {
+    pub config: FeedConfig,
+}
+
 #[allow(clippy::large_enum_variant)]
 pub enum FeedsManagementCmds {
     RegisterNewAssetFeed(RegisterNewAssetFeed),
     DeleteAssetFeed(DeleteAssetFeed),
+    UpdateAssetFeed(UpdateAssetFeed),
 }
 
 pub enum ProcessorResultValue {
@@ -1,5 +1,8 @@
 pub mod aggregate;
 pub mod api;
 pub mod feed_registration_cmds;
+pub mod market_hours;
 pub mod registry;
+pub mod reports_encoding;
+pub mod reputation;
 pub mod types;
@@ -0,0 +1,428 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use blocksense_config::{MarketCalendarConfig, TradingSession};
+use blocksense_registry::config::FeedConfig;
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::types::Timestamp;
+
+/// Values of `market_hours` for markets that trade around the clock.
+const ALWAYS_OPEN_MARKETS: [&str; 2] = ["", "Crypto"];
+
+/// How far ahead to look for the next session; long enough to get past any run of holidays.
+const MAX_LOOKAHEAD_DAYS: u64 = 31;
+
+/// Trading calendar of a single market: its weekly sessions and holidays, both expressed in
+/// the market's local timezone. All timestamps taken and returned are unix milliseconds.
+#[derive(Debug)]
+pub struct MarketCalendar {
+    timezone: Tz,
+    sessions: Vec<TradingSession>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl From<&MarketCalendarConfig> for MarketCalendar {
+    fn from(config: &MarketCalendarConfig) -> Self {
+        MarketCalendar {
+            timezone: config.timezone,
+            sessions: config.sessions.clone(),
+            holidays: config.holidays.iter().copied().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketStatus {
+    pub is_open: bool,
+    /// Start of the next session, set only while the market is closed.
+    pub next_open: Option<Timestamp>,
+    /// End of the current session, set only while the market is open.
+    pub next_close: Option<Timestamp>,
+}
+
+impl MarketStatus {
+    fn always_open() -> MarketStatus {
+        MarketStatus {
+            is_open: true,
+            next_open: None,
+            next_close: None,
+        }
+    }
+}
+
+impl MarketCalendar {
+    fn local_date(&self, timestamp_ms: i64) -> NaiveDate {
+        DateTime::from_timestamp_millis(timestamp_ms)
+            .unwrap_or_default()
+            .with_timezone(&self.timezone)
+            .date_naive()
+    }
+
+    fn to_unix_ms(&self, local: NaiveDateTime) -> i64 {
+        // Markets do not open or close inside a DST gap, but rather than panicking on such a
+        // calendar, fall back to reading the local time as UTC.
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .map_or_else(
+                || local.and_utc().timestamp_millis(),
+                |dt| dt.timestamp_millis(),
+            )
+    }
+
+    /// The sessions opening on `date` as `[open, close)` intervals.
+    fn sessions_on(&self, date: NaiveDate) -> impl Iterator<Item = (i64, i64)> + '_ {
+        let is_holiday = self.holidays.contains(&date);
+        self.sessions
+            .iter()
+            .filter(move |session| !is_holiday && session.days.contains(&date.weekday()))
+            .map(move |session| {
+                let close_date = if session.close <= session.open {
+                    date + Days::new(1)
+                } else {
+                    date
+                };
+                (
+                    self.to_unix_ms(date.and_time(session.open)),
+                    self.to_unix_ms(close_date.and_time(session.close)),
+                )
+            })
+    }
+
+    /// All sessions that can overlap the local dates `from..=to`, ordered by their opening.
+    /// Starts a day early to catch overnight sessions.
+    fn sessions_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<(i64, i64)> {
+        let mut sessions = vec![];
+        let mut date = from.pred_opt().unwrap_or(from);
+        while date <= to {
+            sessions.extend(self.sessions_on(date));
+            date = match date.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        sessions.sort();
+        sessions
+    }
+
+    pub fn is_open(&self, timestamp: Timestamp) -> bool {
+        let timestamp = timestamp as i64;
+        let date = self.local_date(timestamp);
+        self.sessions_between(date, date)
+            .iter()
+            .any(|&(open, close)| open <= timestamp && timestamp < close)
+    }
+
+    /// How long the market was open during `[from, to)`, in milliseconds.
+    pub fn open_duration_ms(&self, from: Timestamp, to: Timestamp) -> u128 {
+        if to <= from {
+            return 0;
+        }
+        let (from, to) = (from as i64, to as i64);
+        let mut total = 0;
+        // Sessions may overlap, so only count the part after what was already counted.
+        let mut counted_until = from;
+        for (open, close) in self.sessions_between(self.local_date(from), self.local_date(to)) {
+            let start = open.max(counted_until);
+            let end = close.min(to);
+            if end > start {
+                total += end - start;
+                counted_until = end;
+            }
+        }
+        total as u128
+    }
+
+    /// Start of the first session opening after `timestamp`.
+    pub fn next_open(&self, timestamp: Timestamp) -> Option<Timestamp> {
+        let timestamp = timestamp as i64;
+        let date = self.local_date(timestamp);
+        self.sessions_between(date, date + Days::new(MAX_LOOKAHEAD_DAYS))
+            .into_iter()
+            .map(|(open, _)| open)
+            .find(|&open| open > timestamp)
+            .map(|open| open as Timestamp)
+    }
+
+    /// End of the session running at `timestamp`. Back-to-back sessions, like the daily FX
+    /// sessions, are treated as a single one.
+    pub fn next_close(&self, timestamp: Timestamp) -> Option<Timestamp> {
+        let timestamp = timestamp as i64;
+        let date = self.local_date(timestamp);
+        let mut close: Option<i64> = None;
+        for (session_open, session_close) in
+            self.sessions_between(date, date + Days::new(MAX_LOOKAHEAD_DAYS))
+        {
+            match close {
+                None if session_open <= timestamp && timestamp < session_close => {
+                    close = Some(session_close)
+                }
+                Some(current) if session_open <= current => {
+                    close = Some(current.max(session_close))
+                }
+                Some(_) => break,
+                None => {}
+            }
+        }
+        close.map(|close| close as Timestamp)
+    }
+
+    pub fn status(&self, timestamp: Timestamp) -> MarketStatus {
+        if self.is_open(timestamp) {
+            MarketStatus {
+                is_open: true,
+                next_open: None,
+                next_close: self.next_close(timestamp),
+            }
+        } else {
+            MarketStatus {
+                is_open: false,
+                next_open: self.next_open(timestamp),
+                next_close: None,
+            }
+        }
+    }
+}
+
+/// All known market calendars, keyed by the names used in the feeds' `market_hours`.
+#[derive(Debug, Default)]
+pub struct MarketCalendars {
+    calendars: HashMap<String, Arc<MarketCalendar>>,
+}
+
+impl MarketCalendars {
+    /// Creates the built-in calendars, extended or overridden by the `configured` ones.
+    /// The built-in calendars have no holidays.
+    pub fn new(configured: &HashMap<String, MarketCalendarConfig>) -> MarketCalendars {
+        let mut calendars = HashMap::new();
+        for (names, config) in builtin_calendars() {
+            let calendar = Arc::new(MarketCalendar::from(&config));
+            for name in names {
+                calendars.insert(name.to_string(), calendar.clone());
+            }
+        }
+        for (name, config) in configured {
+            calendars.insert(name.clone(), Arc::new(MarketCalendar::from(config)));
+        }
+        MarketCalendars { calendars }
+    }
+
+    /// Returns whether `market_hours` is a market that never closes or has a calendar.
+    pub fn is_known(&self, market_hours: &str) -> bool {
+        ALWAYS_OPEN_MARKETS.contains(&market_hours) || self.calendars.contains_key(market_hours)
+    }
+
+    /// Fails for feeds whose `market_hours` is neither a market that never closes nor has a
+    /// calendar, so that feeds are not silently published around the clock.
+    pub fn validate_feed(&self, feed: &FeedConfig) -> anyhow::Result<()> {
+        match &feed.additional_feed_info.market_hours {
+            Some(market_hours) if !self.is_known(market_hours) => anyhow::bail!(
+                "Unknown market_hours {market_hours} for feed {} with id {}",
+                feed.full_name,
+                feed.id
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the calendar for `market_hours`, or `None` for markets that never close.
+    /// Unknown markets are treated as never closing.
+    pub fn get(&self, market_hours: Option<&str>) -> Option<Arc<MarketCalendar>> {
+        self.calendars.get(market_hours?).cloned()
+    }
+
+    pub fn for_feed(&self, feed: &FeedConfig) -> Option<Arc<MarketCalendar>> {
+        self.get(feed.additional_feed_info.market_hours.as_deref())
+    }
+
+    pub fn status(&self, market_hours: Option<&str>, timestamp: Timestamp) -> MarketStatus {
+        match self.get(market_hours) {
+            Some(calendar) => calendar.status(timestamp),
+            None => MarketStatus::always_open(),
+        }
+    }
+}
+
+fn session(days: &[Weekday], open: (u32, u32), close: (u32, u32)) -> TradingSession {
+    TradingSession {
+        days: days.to_vec(),
+        open: NaiveTime::from_hms_opt(open.0, open.1, 0).expect("valid session open"),
+        close: NaiveTime::from_hms_opt(close.0, close.1, 0).expect("valid session close"),
+    }
+}
+
+fn builtin_calendars() -> Vec<(Vec<&'static str>, MarketCalendarConfig)> {
+    use Weekday::{Fri, Mon, Sun, Thu, Tue, Wed};
+    let weekdays = [Mon, Tue, Wed, Thu, Fri];
+    // Overnight sessions from Sunday evening until Friday evening, New York time.
+    let sunday_to_thursday = [Sun, Mon, Tue, Wed, Thu];
+    let calendar = |timezone: Tz, sessions: Vec<TradingSession>| MarketCalendarConfig {
+        timezone,
+        sessions,
+        holidays: vec![],
+    };
+    vec![
+        (
+            vec!["NYSE", "US_Equities"],
+            calendar(
+                chrono_tz::America::New_York,
+                vec![session(&weekdays, (9, 30), (16, 0))],
+            ),
+        ),
+        (
+            vec!["Forex", "FX"],
+            calendar(
+                chrono_tz::America::New_York,
+                vec![session(&sunday_to_thursday, (17, 0), (17, 0))],
+            ),
+        ),
+        (
+            vec!["WTI", "NYMEX", "Precious_Metals"],
+            calendar(
+                chrono_tz::America::New_York,
+                vec![session(&sunday_to_thursday, (18, 0), (17, 0))],
+            ),
+        ),
+        (
+            vec!["LSE", "UK_ETF"],
+            calendar(
+                chrono_tz::Europe::London,
+                vec![session(&weekdays, (8, 0), (16, 30))],
+            ),
+        ),
+        (
+            vec!["Euronext_Milan", "MI_ETF"],
+            calendar(
+                chrono_tz::Europe::Rome,
+                vec![session(&weekdays, (9, 0), (17, 30))],
+            ),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(rfc3339: &str) -> Timestamp {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .timestamp_millis() as Timestamp
+    }
+
+    const HOUR_MS: u128 = 3_600_000;
+
+    #[test]
+    fn nyse_follows_new_york_time_across_dst() {
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let nyse = calendars.get(Some("NYSE")).unwrap();
+
+        // Wednesday in winter (UTC-5) and in summer (UTC-4).
+        assert!(!nyse.is_open(ms("2025-01-15T14:29:59Z")));
+        assert!(nyse.is_open(ms("2025-01-15T14:30:00Z")));
+        assert!(!nyse.is_open(ms("2025-01-15T21:00:00Z")));
+        assert!(nyse.is_open(ms("2025-07-16T13:30:00Z")));
+        assert!(!nyse.is_open(ms("2025-07-16T20:00:00Z")));
+        // Saturday
+        assert!(!nyse.is_open(ms("2025-01-18T15:00:00Z")));
+    }
+
+    #[test]
+    fn configured_calendar_overrides_builtin_and_respects_holidays() {
+        let configured: HashMap<String, MarketCalendarConfig> = serde_json::from_str(
+            r#"
+            {
+                "NYSE": {
+                    "timezone": "America/New_York",
+                    "sessions": [{"days": ["Mon", "Tue", "Wed", "Thu", "Fri"], "open": "09:30", "close": "16:00"}],
+                    "holidays": ["2025-12-25"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let calendars = MarketCalendars::new(&configured);
+        let nyse = calendars.get(Some("NYSE")).unwrap();
+
+        assert!(!nyse.is_open(ms("2025-12-25T15:00:00Z")));
+        assert!(nyse.is_open(ms("2025-12-26T15:00:00Z")));
+        assert_eq!(
+            nyse.next_open(ms("2025-12-24T22:00:00Z")),
+            Some(ms("2025-12-26T14:30:00Z"))
+        );
+        // The other builtin names are unaffected.
+        assert!(calendars.get(Some("US_Equities")).is_some());
+    }
+
+    #[test]
+    fn fx_trades_overnight_from_sunday_to_friday() {
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let fx = calendars.get(Some("FX")).unwrap();
+
+        assert!(!fx.is_open(ms("2025-01-19T21:59:59Z"))); // Sunday 16:59:59 New York
+        assert!(fx.is_open(ms("2025-01-19T22:00:00Z")));
+        assert!(fx.is_open(ms("2025-01-22T03:00:00Z"))); // Tuesday night
+        assert!(fx.is_open(ms("2025-01-24T21:59:59Z"))); // Friday 16:59:59 New York
+        assert!(!fx.is_open(ms("2025-01-24T22:00:00Z")));
+
+        // The daily sessions are back-to-back, so the market closes on Friday.
+        assert_eq!(
+            fx.next_close(ms("2025-01-21T12:00:00Z")),
+            Some(ms("2025-01-24T22:00:00Z"))
+        );
+        assert_eq!(
+            fx.status(ms("2025-01-25T12:00:00Z")),
+            MarketStatus {
+                is_open: false,
+                next_open: Some(ms("2025-01-26T22:00:00Z")),
+                next_close: None,
+            }
+        );
+    }
+
+    #[test]
+    fn open_duration_skips_closed_hours() {
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let nyse = calendars.get(Some("NYSE")).unwrap();
+
+        // From Friday noon until Monday noon New York time: 4h on Friday and 2.5h on Monday.
+        let friday_noon = ms("2025-01-17T17:00:00Z");
+        let monday_noon = ms("2025-01-20T17:00:00Z");
+        assert_eq!(
+            nyse.open_duration_ms(friday_noon, monday_noon),
+            6 * HOUR_MS + HOUR_MS / 2
+        );
+        assert_eq!(nyse.open_duration_ms(monday_noon, friday_noon), 0);
+    }
+
+    #[test]
+    fn crypto_and_unknown_markets_never_close() {
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let saturday = ms("2025-01-18T15:00:00Z");
+
+        for market_hours in [None, Some(""), Some("Crypto"), Some("Moon_Exchange")] {
+            assert!(calendars.get(market_hours).is_none());
+            assert_eq!(
+                calendars.status(market_hours, saturday),
+                MarketStatus::always_open()
+            );
+        }
+        assert!(calendars.is_known("Crypto"));
+        assert!(calendars.is_known("LSE"));
+        assert!(!calendars.is_known("Moon_Exchange"));
+    }
+
+    #[test]
+    fn feeds_with_unknown_market_hours_are_rejected() {
+        let calendars = MarketCalendars::new(&HashMap::new());
+        let mut feed = blocksense_config::test_feed_config(1, 0);
+        for market_hours in [None, Some("Crypto"), Some("NYSE")] {
+            feed.additional_feed_info.market_hours = market_hours.map(str::to_owned);
+            assert!(calendars.validate_feed(&feed).is_ok());
+        }
+        feed.additional_feed_info.market_hours = Some("Moon_Exchange".to_owned());
+        assert!(calendars.validate_feed(&feed).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
     collections::HashMap,
+    fs,
+    path::Path,
     sync::Arc,
     time::{Duration, SystemTime},
 };
@@ -10,13 +12,12 @@ use blocksense_utils::time::current_unix_time;
 use chrono::{DateTime, TimeZone, Utc};
 use ringbuf::{
     storage::Heap,
-    traits::{Consumer, RingBuffer},
+    traits::{Consumer, Observer, RingBuffer},
     HeapRb, SharedRb,
 };
 use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
-use std::time::UNIX_EPOCH;
 use tokio::{sync::RwLock, time};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 /// Map representing feed_id -> FeedMetaData
 #[derive(Debug)]
@@ -64,7 +65,8 @@ pub fn new_feeds_meta_data_reg_with_test_data() -> FeedMetaDataRegistry {
         "numerical".to_string(),
         "average".to_string(),
         None,
-    );
+    )
+    .unwrap();
     let fmd2 = FeedMetaData::new(
         "BTS/USD".to_string(),
         30000,
@@ -75,7 +77,8 @@ pub fn new_feeds_meta_data_reg_with_test_data() -> FeedMetaDataRegistry {
         "numerical".to_string(),
         "average".to_string(),
         None,
-    );
+    )
+    .unwrap();
     let fmd3 = FeedMetaData::new(
         "ETH/USD".to_string(),
         60000,
@@ -86,7 +89,8 @@ pub fn new_feeds_meta_data_reg_with_test_data() -> FeedMetaDataRegistry {
         "numerical".to_string(),
         "average".to_string(),
         None,
-    );
+    )
+    .unwrap();
 
     let mut fmdr = FeedMetaDataRegistry::new();
 
@@ -101,34 +105,31 @@ pub fn new_feeds_meta_data_reg_from_config(conf: &AllFeedsConfig) -> FeedMetaDat
     let mut fmdr = FeedMetaDataRegistry::new();
 
     for feed in &conf.feeds {
-        fmdr.push(
-            feed.id,
-            FeedMetaData::new(
-                feed.full_name.clone(),
-                feed.schedule.interval_ms,
-                feed.quorum.percentage,
-                feed.schedule.deviation_percentage,
-                feed.schedule.heartbeat_ms,
-                UNIX_EPOCH + Duration::from_millis(feed.schedule.first_report_start_unix_time_ms),
-                feed.value_type.clone(),
-                feed.quorum.aggregation.clone(),
-                None, // Will be filled once FeedsSlotsManager is started and processors are up and running.
-            ),
-        );
+        // The processor_cmd_chan will be filled once FeedsSlotsManager is started and processors are up and running.
+        match FeedMetaData::from_config(feed) {
+            Ok(feed_metadata) => fmdr.push(feed.id, feed_metadata),
+            Err(e) => error!("Skipping feed {} with invalid config: {e}", feed.id),
+        }
     }
 
     fmdr
 }
 
 // For a given Feed this struct represents the received votes from different reporters.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct FeedReports {
     pub report: HashMap<u64, DataFeedPayload>,
+    /// Number of times each reporter replaced its vote in the current slot.
+    pub revotes: HashMap<u64, u32>,
+    /// Number of votes for past slots each reporter sent during the current slot.
+    pub late_votes: HashMap<u64, u32>,
 }
 
 impl FeedReports {
     pub fn clear(&mut self) {
         self.report.clear();
+        self.revotes.clear();
+        self.late_votes.clear();
     }
 }
 
@@ -179,6 +180,12 @@ where
     serialize_map.end()
 }
 
+/// Deserialization counterpart of the `FeedAggregateHistory` serialization.
+#[derive(Deserialize)]
+struct FeedAggregateHistorySnapshot {
+    aggregate_history: HashMap<u32, Vec<HistoryEntry>>,
+}
+
 impl Default for FeedAggregateHistory {
     fn default() -> Self {
         Self::new()
@@ -258,6 +265,60 @@ impl FeedAggregateHistory {
     pub fn last_value(&self, feed_id: u32) -> Option<&FeedType> {
         self.last(feed_id).map(|h| &h.value)
     }
+
+    /// Writes a snapshot of all registered histories to `path`. The snapshot is first written
+    /// to a temporary file next to `path` and then renamed, so a crash mid-write never leaves
+    /// a truncated snapshot behind.
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let serialized = serde_json::to_vec(self)?;
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serialized)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Restores the histories of registered feeds from a snapshot written by `save_to_file`.
+    /// Feeds present in the snapshot but not registered are ignored, and if the snapshot holds
+    /// more entries than the ring buffer capacity only the most recent ones are kept.
+    /// Returns the number of restored entries; a missing snapshot restores nothing.
+    pub fn load_from_file(&mut self, path: &Path) -> anyhow::Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let snapshot: FeedAggregateHistorySnapshot = serde_json::from_slice(&fs::read(path)?)?;
+        let mut restored = 0;
+        for (feed_id, entries) in snapshot.aggregate_history {
+            let Some(ring_buffer) = self.aggregate_history.get_mut(&feed_id) else {
+                debug!("Skipping snapshot history for unregistered feed id {feed_id}");
+                continue;
+            };
+            ring_buffer.clear();
+            for entry in entries {
+                ring_buffer.push_overwrite(entry);
+            }
+            restored += ring_buffer.occupied_len();
+        }
+        Ok(restored)
+    }
+
+    /// Drops all entries of `feed_id` with an update number greater than `last_update_number`.
+    /// Used when a restored history turns out to be ahead of the on-chain state.
+    /// Returns the number of dropped entries.
+    pub fn discard_after(&mut self, feed_id: u32, last_update_number: u128) -> usize {
+        let Some(ring_buffer) = self.aggregate_history.get_mut(&feed_id) else {
+            return 0;
+        };
+        let entries: Vec<HistoryEntry> = ring_buffer.pop_iter().collect();
+        let mut discarded = 0;
+        for entry in entries {
+            if entry.update_number > last_update_number {
+                discarded += 1;
+            } else {
+                ring_buffer.push_overwrite(entry);
+            }
+        }
+        discarded
+    }
 }
 
 // This struct holds all the Feeds by ID (the key in the map) and the received votes for them
@@ -283,27 +344,47 @@ impl AllFeedsReports {
             reports: HashMap::new(),
         }
     }
+    fn feed_reports(&mut self, feed_id: u32) -> &Arc<RwLock<FeedReports>> {
+        //TODO: Reject votes for unregistered feed ID-s
+        self.reports.entry(feed_id).or_default()
+    }
     pub async fn push(
         &mut self,
         feed_id: u32,
         reporter_id: u64,
         data: DataFeedPayload,
     ) -> VoteStatus {
-        let res = self.reports.entry(feed_id).or_insert_with(|| {
-            Arc::new(RwLock::new(FeedReports {
-                report: HashMap::new(),
-            }))
-        }); //TODO: Reject votes for unregistered feed ID-s
-        let mut res = res.write().await;
+        let mut res = self.feed_reports(feed_id).write().await;
 
         match res.report.insert(reporter_id, data) {
-            Some(old_value) => VoteStatus::RevoteForSlot(Box::new(old_value)),
+            Some(old_value) => {
+                *res.revotes.entry(reporter_id).or_default() += 1;
+                VoteStatus::RevoteForSlot(Box::new(old_value))
+            }
             None => VoteStatus::FirstVoteForSlot,
         }
     }
+    /// Counts a vote of `reporter_id` for a past slot of `feed_id`, to be scored along with the
+    /// current slot.
+    pub async fn push_late(&mut self, feed_id: u32, reporter_id: u64) {
+        let mut res = self.feed_reports(feed_id).write().await;
+        *res.late_votes.entry(reporter_id).or_default() += 1;
+    }
+    /// Number of times `reporter_id` replaced its vote for `feed_id` in the current slot, or
+    /// `None` if it has not voted yet.
+    pub async fn revotes(&self, feed_id: u32, reporter_id: u64) -> Option<u32> {
+        let reports = self.reports.get(&feed_id)?.read().await;
+        if !reports.report.contains_key(&reporter_id) {
+            return None;
+        }
+        Some(reports.revotes.get(&reporter_id).copied().unwrap_or(0))
+    }
     pub fn get(&self, feed_id: u32) -> Option<Arc<RwLock<FeedReports>>> {
         self.reports.get(&feed_id).cloned()
     }
+    pub fn remove(&mut self, feed_id: u32) {
+        self.reports.remove(&feed_id);
+    }
 }
 
 pub struct SlotTimeTracker {
@@ -407,6 +488,7 @@ mod tests {
 
     use crate::registry::new_feeds_meta_data_reg_with_test_data;
     use crate::registry::AllFeedsReports;
+    use crate::registry::FeedAggregateHistory;
     use crate::registry::SlotTimeTracker;
     use crate::types::test_payload_from_result;
     use crate::types::FeedMetaData;
@@ -414,6 +496,7 @@ mod tests {
     use crate::types::FeedType;
     use crate::types::Repeatability;
     use crate::types::ReportRelevance;
+    use ringbuf::traits::Observer;
     use std::sync::Arc;
     use std::time::Instant;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -559,9 +642,9 @@ mod tests {
 
         let feed = FeedMetaData::new_oneshot(
             "TestFeed".to_string(),
-            voting_wait_duration_ms,
             10.0f32, // 10%
             voting_start_time,
+            voting_start_time + Duration::from_millis(voting_wait_duration_ms),
         );
 
         assert_eq!(feed.get_name(), "TestFeed");
@@ -630,6 +713,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_report_relevance_after_oneshot_voting_end() {
+        let voting_start_time = SystemTime::now();
+        let voting_end_time = voting_start_time + Duration::from_secs(30);
+        let feed = FeedMetaData::new_oneshot(
+            "TestFeed".to_string(),
+            10.0f32, // 10%
+            voting_start_time,
+            voting_end_time,
+        );
+        let voting_end_time_ms = voting_end_time
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        assert_eq!(feed.get_voting_end_time_ms(), voting_end_time_ms);
+
+        // A report stamped within the voting window is rejected once voting is closed.
+        let msg_timestamp = voting_end_time_ms - 1000;
+        assert_eq!(
+            feed.check_report_relevance(voting_end_time_ms - 500, msg_timestamp),
+            ReportRelevance::Relevant
+        );
+        assert_eq!(
+            feed.check_report_relevance(voting_end_time_ms + 1, msg_timestamp),
+            ReportRelevance::NonRelevantOld
+        );
+    }
+
     #[test]
     fn test_time_to_slot_end_ms() {
         // setup
@@ -650,9 +761,9 @@ mod tests {
 
         let oneshot_feed = FeedMetaData::new_oneshot(
             "TestFeed".to_string(),
-            voting_wait_duration_ms,
             10.0f32, // 10%
             voting_start_time,
+            voting_start_time + Duration::from_millis(voting_wait_duration_ms),
         );
         let regular_feed = FeedMetaData::new(
             "TestFeed".to_string(),
@@ -664,7 +775,8 @@ mod tests {
             "numeric".to_string(),
             "average".to_string(),
             None,
-        );
+        )
+        .unwrap();
 
         // setup messages
         let message_with_old_timestamp = current_time_as_ms + 40000;
@@ -769,6 +881,24 @@ mod tests {
         assert!(values.len() as u32 == NTHREADS);
     }
 
+    #[tokio::test]
+    async fn revotes_are_counted_until_the_slot_is_cleared() {
+        let mut reports = AllFeedsReports::new();
+        let payload = || test_payload_from_result(Ok(FeedType::Numerical(0.1)));
+        assert_eq!(reports.revotes(1, 7).await, None);
+        reports.push(1, 7, payload()).await;
+        assert_eq!(reports.revotes(1, 7).await, Some(0));
+        reports.push(1, 7, payload()).await;
+        reports.push(1, 7, payload()).await;
+        reports.push(1, 8, payload()).await;
+        assert_eq!(reports.revotes(1, 7).await, Some(2));
+        assert_eq!(reports.revotes(1, 8).await, Some(0));
+        assert_eq!(reports.revotes(2, 7).await, None);
+
+        reports.get(1).unwrap().write().await.clear();
+        assert_eq!(reports.revotes(1, 7).await, None);
+    }
+
     #[tokio::test]
     async fn test_await_end_of_current_slot() {
         // setup
@@ -856,4 +986,84 @@ mod tests {
         // assert
         assert!(duration_ms < 0);
     }
+
+    #[test]
+    fn test_feed_aggregate_history_snapshot_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "feed_aggregate_history_roundtrip_{}.json",
+            std::process::id()
+        ));
+        let mut history = FeedAggregateHistory::new();
+        history.register_feed(1, 3);
+        history.register_feed(2, 3);
+        for i in 0..5 {
+            history.push_next(1, FeedType::Numerical(i as f64), 1000 + i);
+        }
+        history.push_next(2, FeedType::Text("hello".to_string()), 2000);
+        history.save_to_file(&path).unwrap();
+
+        // Feed 3 was never in the snapshot and feed 2 is no longer registered.
+        let mut restored = FeedAggregateHistory::new();
+        restored.register_feed(1, 2);
+        restored.register_feed(3, 2);
+        assert_eq!(restored.load_from_file(&path).unwrap(), 2);
+        std::fs::remove_file(&path).unwrap();
+
+        let last = restored.last(1).unwrap();
+        assert_eq!(last.value, FeedType::Numerical(4.0));
+        assert_eq!(last.update_number, 4);
+        assert_eq!(last.end_slot_timestamp, 1004);
+        assert_eq!(restored.get(1).unwrap().occupied_len(), 2);
+        assert!(restored.last(3).is_none());
+        assert!(!restored.is_registered_feed(2));
+
+        // New updates continue the restored numbering.
+        restored.push_next(1, FeedType::Numerical(5.0), 1005);
+        assert_eq!(restored.last(1).unwrap().update_number, 5);
+    }
+
+    #[test]
+    fn test_feed_aggregate_history_load_missing_snapshot() {
+        let path = std::env::temp_dir().join("feed_aggregate_history_does_not_exist.json");
+        let mut history = FeedAggregateHistory::new();
+        history.register_feed(1, 3);
+        assert_eq!(history.load_from_file(&path).unwrap(), 0);
+        assert!(history.last(1).is_none());
+    }
+
+    #[test]
+    fn test_feed_aggregate_history_discard_after() {
+        let mut history = FeedAggregateHistory::new();
+        history.register_feed(1, 10);
+        for i in 0..5 {
+            history.push_next(1, FeedType::Numerical(i as f64), 1000 + i);
+        }
+        assert_eq!(history.discard_after(1, 2), 2);
+        assert_eq!(history.last(1).unwrap().update_number, 2);
+        assert_eq!(history.get(1).unwrap().occupied_len(), 3);
+        assert_eq!(history.discard_after(1, 2), 0);
+        assert_eq!(history.discard_after(42, 0), 0);
+    }
+
+    #[test]
+    fn test_feeds_with_invalid_struct_fields_are_rejected() {
+        let mut struct_feed = blocksense_config::test_feed_config(1, 1);
+        struct_feed.value_type = "struct".to_string();
+        struct_feed.struct_fields = serde_json::from_str(
+            r#"[{"name": "bid", "value_type": "numerical", "aggregation": "majority"}]"#,
+        )
+        .unwrap();
+        assert!(FeedMetaData::from_config(&struct_feed).is_err());
+
+        let mut feed = FeedMetaData::from_config(&blocksense_config::test_feed_config(1, 0))
+            .expect("Valid feed config");
+        assert!(feed.update_from_config(&struct_feed).is_err());
+        assert_eq!(feed.get_value_variant(), Ok(FeedType::Numerical(0.0)));
+
+        let feeds_config = blocksense_config::AllFeedsConfig {
+            feeds: vec![blocksense_config::test_feed_config(0, 0), struct_feed],
+        };
+        let fmdr = super::new_feeds_meta_data_reg_from_config(&feeds_config);
+        assert_eq!(fmdr.get_keys(), vec![0]);
+    }
 }
@@ -0,0 +1,313 @@
+//! Versioned binary encoding of report batches, the compact alternative to JSON arrays of
+//! `DataFeedPayload` in `/post_reports_batch`.
+//!
+//! An encoded batch is a version byte followed by the SSZ serialization of the reports. Feed
+//! ids are numbers and signatures are compressed to 96 bytes.
+
+use anyhow::{anyhow, bail, Result};
+use blocksense_crypto::{JsonSerializableSignature, Signature};
+use ssz_rs::prelude::*;
+
+use crate::types::{
+    DataFeedPayload, Decimal, FeedError, FeedResult, FeedType, PayloadMetaData,
+    ReportSigningScheme, StructField,
+};
+
+/// Content-Type of report batches in the binary encoding.
+pub const REPORTS_BATCH_CONTENT_TYPE: &str = "application/vnd.blocksense.reports+ssz";
+pub const REPORTS_BATCH_VERSION: u8 = 1;
+
+pub const MAX_REPORTS_IN_BATCH: usize = 16 * 1024;
+pub const MAX_VALUE_SIZE: usize = 32 * 1024;
+pub const MAX_STRUCT_FIELDS: usize = 1024;
+pub const MAX_FIELD_NAME_SIZE: usize = 256;
+const SIGNATURE_SIZE: usize = 96;
+
+const NUMERICAL: u8 = 0;
+const TEXT: u8 = 1;
+const BYTES: u8 = 2;
+const DECIMAL: u8 = 3;
+const STRUCT: u8 = 4;
+const API_ERROR: u8 = 0x80;
+const UNDEFINED_ERROR: u8 = 0x81;
+
+/// A feed value or a reported error. The fields of struct values are in `EncodedReport`.
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+struct EncodedValue {
+    kind: u8,
+    data: List<u8, MAX_VALUE_SIZE>,
+}
+
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+struct EncodedStructField {
+    name: List<u8, MAX_FIELD_NAME_SIZE>,
+    value: EncodedValue,
+}
+
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+struct EncodedReport {
+    reporter_id: u64,
+    feed_id: u32,
+    timestamp: u128,
+    signature: Vector<u8, SIGNATURE_SIZE>,
+    signing_scheme: u8,
+    result: EncodedValue,
+    fields: List<EncodedStructField, MAX_STRUCT_FIELDS>,
+}
+
+#[derive(Debug, PartialEq, SimpleSerialize, Default, Clone)]
+struct EncodedReportsBatch {
+    reports: List<EncodedReport, MAX_REPORTS_IN_BATCH>,
+}
+
+fn to_list<const N: usize>(what: &str, data: Vec<u8>) -> Result<List<u8, N>> {
+    let len = data.len();
+    List::try_from(data).map_err(|_| anyhow!("{what} of {len} bytes exceeds the limit of {N}"))
+}
+
+fn encode_value(value: &FeedType) -> Result<EncodedValue> {
+    let (kind, data) = match value {
+        FeedType::Numerical(value) => (NUMERICAL, value.to_le_bytes().to_vec()),
+        FeedType::Text(value) => (TEXT, value.as_bytes().to_vec()),
+        FeedType::Bytes(value) => (BYTES, value.clone()),
+        FeedType::Decimal(value) => {
+            let mut data = value.mantissa.to_le_bytes().to_vec();
+            data.push(value.scale);
+            (DECIMAL, data)
+        }
+        FeedType::Struct(_) => (STRUCT, vec![]),
+    };
+    Ok(EncodedValue {
+        kind,
+        data: to_list("Feed value", data)?,
+    })
+}
+
+fn encode_result(result: &FeedResult) -> Result<(EncodedValue, Vec<EncodedStructField>)> {
+    match result {
+        Ok(FeedType::Struct(fields)) => {
+            let fields: Vec<EncodedStructField> = fields
+                .iter()
+                .map(|field| {
+                    if let FeedType::Struct(_) = field.value {
+                        bail!("Struct field {} can not be a struct", field.name);
+                    }
+                    Ok(EncodedStructField {
+                        name: to_list("Struct field name", field.name.as_bytes().to_vec())?,
+                        value: encode_value(&field.value)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok((encode_value(&FeedType::Struct(vec![]))?, fields))
+        }
+        Ok(value) => Ok((encode_value(value)?, vec![])),
+        Err(error) => {
+            let (kind, data) = match error {
+                FeedError::APIError(error) => (API_ERROR, error.as_bytes().to_vec()),
+                FeedError::UndefinedError => (UNDEFINED_ERROR, vec![]),
+            };
+            let value = EncodedValue {
+                kind,
+                data: to_list("Feed error", data)?,
+            };
+            Ok((value, vec![]))
+        }
+    }
+}
+
+fn encode_report(payload: &DataFeedPayload) -> Result<EncodedReport> {
+    let metadata = &payload.payload_metadata;
+    let feed_id = metadata
+        .feed_id
+        .parse::<u32>()
+        .map_err(|e| anyhow!("Feed id {} is not a number: {e}", metadata.feed_id))?;
+    // The signature covers the feed id string, which is decoded from the number.
+    if feed_id.to_string() != metadata.feed_id {
+        bail!("Feed id {} is not in canonical form", metadata.feed_id);
+    }
+    let (result, fields) = encode_result(&payload.result)?;
+    let fields_count = fields.len();
+    Ok(EncodedReport {
+        reporter_id: metadata.reporter_id,
+        feed_id,
+        timestamp: metadata.timestamp,
+        signature: Vector::try_from(metadata.signature.sig.compress().to_vec())
+            .map_err(|_| anyhow!("Compressed signature is not {SIGNATURE_SIZE} bytes"))?,
+        signing_scheme: metadata.signing_scheme.version(),
+        result,
+        fields: List::try_from(fields).map_err(|_| {
+            anyhow!("Struct of {fields_count} fields exceeds the limit of {MAX_STRUCT_FIELDS}")
+        })?,
+    })
+}
+
+fn decode_value(value: &EncodedValue) -> Result<FeedType> {
+    let data = value.data.to_vec();
+    let len = data.len();
+    match value.kind {
+        NUMERICAL => {
+            let bytes = data
+                .try_into()
+                .map_err(|_| anyhow!("Numerical value of {len} bytes"))?;
+            let value = f64::from_le_bytes(bytes);
+            if !value.is_finite() {
+                bail!("Non-finite numerical value {value}");
+            }
+            Ok(FeedType::Numerical(value))
+        }
+        TEXT => Ok(FeedType::Text(String::from_utf8(data)?)),
+        BYTES => Ok(FeedType::Bytes(data)),
+        DECIMAL => {
+            let Some((&scale, mantissa)) = data.split_last() else {
+                bail!("Empty decimal value");
+            };
+            let mantissa = mantissa
+                .try_into()
+                .map_err(|_| anyhow!("Decimal value of {len} bytes"))?;
+            Ok(FeedType::Decimal(Decimal::new(
+                i128::from_le_bytes(mantissa),
+                scale,
+            )))
+        }
+        STRUCT => bail!("Struct field can not be a struct"),
+        kind => bail!("Unknown value kind {kind}"),
+    }
+}
+
+fn decode_report(report: &EncodedReport) -> Result<DataFeedPayload> {
+    let sig = Signature::uncompress(&report.signature)
+        .map_err(|e| anyhow!("Invalid signature: {e:?}"))?;
+    let signing_scheme = ReportSigningScheme::from_version(report.signing_scheme)
+        .ok_or_else(|| anyhow!("Unknown signing scheme {}", report.signing_scheme))?;
+    let result = match report.result.kind {
+        STRUCT => Ok(FeedType::Struct(
+            report
+                .fields
+                .iter()
+                .map(|field| {
+                    Ok(StructField {
+                        name: String::from_utf8(field.name.to_vec())?,
+                        value: decode_value(&field.value)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        )),
+        API_ERROR => Err(FeedError::APIError(String::from_utf8(
+            report.result.data.to_vec(),
+        )?)),
+        UNDEFINED_ERROR => Err(FeedError::UndefinedError),
+        _ => Ok(decode_value(&report.result)?),
+    };
+    Ok(DataFeedPayload {
+        payload_metadata: PayloadMetaData {
+            reporter_id: report.reporter_id,
+            feed_id: report.feed_id.to_string(),
+            timestamp: report.timestamp,
+            signature: JsonSerializableSignature { sig },
+            signing_scheme,
+        },
+        result,
+    })
+}
+
+pub fn encode_reports_batch(reports: &[DataFeedPayload]) -> Result<Vec<u8>> {
+    let reports = reports
+        .iter()
+        .map(encode_report)
+        .collect::<Result<Vec<_>>>()?;
+    let count = reports.len();
+    let batch = EncodedReportsBatch {
+        reports: List::try_from(reports).map_err(|_| {
+            anyhow!("Batch of {count} reports exceeds the limit of {MAX_REPORTS_IN_BATCH}")
+        })?,
+    };
+    let mut encoded = vec![REPORTS_BATCH_VERSION];
+    encoded.extend(ssz_rs::serialize(&batch)?);
+    Ok(encoded)
+}
+
+pub fn decode_reports_batch(encoded: &[u8]) -> Result<Vec<DataFeedPayload>> {
+    let Some((&version, batch)) = encoded.split_first() else {
+        bail!("Empty reports batch");
+    };
+    if version != REPORTS_BATCH_VERSION {
+        bail!("Unsupported reports batch version {version}");
+    }
+    let batch: EncodedReportsBatch = ssz_rs::deserialize(batch)?;
+    batch.reports.iter().map(decode_report).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_payload_from_result;
+
+    fn with_feed_id(mut payload: DataFeedPayload, feed_id: &str) -> DataFeedPayload {
+        payload.payload_metadata.feed_id = feed_id.to_string();
+        payload.payload_metadata.reporter_id = 7;
+        payload.payload_metadata.timestamp = 1_700_000_000_000;
+        payload
+    }
+
+    #[test]
+    fn test_reports_batch_roundtrip() {
+        let results = vec![
+            Ok(FeedType::Numerical(-1.25)),
+            Ok(FeedType::Text("text".to_string())),
+            Ok(FeedType::Bytes(vec![0, 1, 2])),
+            Ok(FeedType::Decimal(Decimal::new(-123_456_789, 6))),
+            Ok(FeedType::Struct(vec![
+                StructField::new("bid", FeedType::Numerical(1.5)),
+                StructField::new("ask", FeedType::Decimal(Decimal::new(16, 1))),
+            ])),
+            Err(FeedError::APIError("timeout".to_string())),
+            Err(FeedError::UndefinedError),
+        ];
+        let mut reports: Vec<DataFeedPayload> = results
+            .into_iter()
+            .enumerate()
+            .map(|(i, result)| with_feed_id(test_payload_from_result(result), &i.to_string()))
+            .collect();
+        reports[0].payload_metadata.signing_scheme = ReportSigningScheme::V1;
+
+        let encoded = encode_reports_batch(&reports).unwrap();
+        assert_eq!(encoded[0], REPORTS_BATCH_VERSION);
+        assert!(encoded.len() < serde_json::to_vec(&reports).unwrap().len());
+
+        let decoded = decode_reports_batch(&encoded).unwrap();
+        assert_eq!(decoded.len(), reports.len());
+        for (decoded, report) in decoded.iter().zip(&reports) {
+            let (d, r) = (&decoded.payload_metadata, &report.payload_metadata);
+            assert_eq!(d.reporter_id, r.reporter_id);
+            assert_eq!(d.feed_id, r.feed_id);
+            assert_eq!(d.timestamp, r.timestamp);
+            assert_eq!(d.signature.sig, r.signature.sig);
+            assert_eq!(d.signing_scheme, r.signing_scheme);
+            assert_eq!(decoded.result, report.result);
+        }
+    }
+
+    #[test]
+    fn test_reports_batch_rejects_invalid_input() {
+        let report = test_payload_from_result(Ok(FeedType::Numerical(1.0)));
+        assert!(encode_reports_batch(&[with_feed_id(report.clone(), "01")]).is_err());
+        assert!(encode_reports_batch(&[with_feed_id(report.clone(), "BTC/USD")]).is_err());
+        let nested = test_payload_from_result(Ok(FeedType::Struct(vec![StructField::new(
+            "inner",
+            FeedType::Struct(vec![]),
+        )])));
+        assert!(encode_reports_batch(&[nested]).is_err());
+
+        let mut encoded = encode_reports_batch(&[report]).unwrap();
+        assert!(decode_reports_batch(&encoded[..encoded.len() - 1]).is_err());
+        encoded[0] = REPORTS_BATCH_VERSION + 1;
+        assert!(decode_reports_batch(&encoded).is_err());
+        assert!(decode_reports_batch(&[]).is_err());
+
+        for value in [f64::NAN, f64::NEG_INFINITY] {
+            let report = test_payload_from_result(Ok(FeedType::Numerical(value)));
+            let encoded = encode_reports_batch(&[with_feed_id(report, "1")]).unwrap();
+            assert!(decode_reports_batch(&encoded).is_err());
+        }
+    }
+}
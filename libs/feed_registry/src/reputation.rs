@@ -0,0 +1,422 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use blocksense_config::ReporterReputationConfig;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::registry::FeedReports;
+use crate::types::FeedType;
+
+/// Exponentially decaying averages of the observations of a reporter, each in `[0, 1]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReporterReputation {
+    /// How close the votes are to the aggregated values.
+    pub accuracy: f64,
+    /// How often the reporter votes in the slots of the feeds.
+    pub liveness: f64,
+    /// How often the votes arrive before the end of their slot.
+    pub punctuality: f64,
+    /// Number of slots the reporter was scored on.
+    pub observations: u64,
+    pub jailed: bool,
+}
+
+impl Default for ReporterReputation {
+    fn default() -> Self {
+        ReporterReputation {
+            accuracy: 1.0,
+            liveness: 1.0,
+            punctuality: 1.0,
+            observations: 0,
+            jailed: false,
+        }
+    }
+}
+
+impl ReporterReputation {
+    pub fn score(&self) -> f64 {
+        self.accuracy * self.liveness * self.punctuality
+    }
+}
+
+/// Reputation of all reporters the sequencer has seen. Jailing is soft: the votes of a jailed
+/// reporter are left out of the aggregation, but are still scored against the aggregated
+/// values so the reporter is released once its score recovers.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReporterReputations {
+    #[serde(skip)]
+    config: ReporterReputationConfig,
+    reporters: HashMap<u64, ReporterReputation>,
+    /// Reporters that have voted for each feed, the only ones expected to vote for it.
+    #[serde(default)]
+    feed_reporters: HashMap<u32, HashSet<u64>>,
+}
+
+impl ReporterReputations {
+    pub fn new(config: ReporterReputationConfig) -> ReporterReputations {
+        ReporterReputations {
+            config,
+            reporters: HashMap::new(),
+            feed_reporters: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, reporter_id: u64) -> Option<&ReporterReputation> {
+        self.reporters.get(&reporter_id)
+    }
+
+    pub fn reporters(&self) -> &HashMap<u64, ReporterReputation> {
+        &self.reporters
+    }
+
+    pub fn is_jailed(&self, reporter_id: u64) -> bool {
+        self.get(reporter_id).is_some_and(|r| r.jailed)
+    }
+
+    fn decay(&self) -> f64 {
+        0.5f64.powf(1.0 / self.config.half_life as f64)
+    }
+
+    /// Records whether a vote of `reporter_id` arrived before the end of its slot.
+    pub fn record_timeliness(&mut self, reporter_id: u64, is_timely: bool) {
+        let decay = self.decay();
+        let reputation = self.reporters.entry(reporter_id).or_default();
+        update_average(&mut reputation.punctuality, is_timely as u8 as f64, decay);
+    }
+
+    /// Scores the reporters of feed `feed_id` on a finished slot, all at once. The votes in
+    /// `reports` were timely, unlike its late votes. Of `reporter_ids`, only those that have
+    /// voted for the feed are expected to vote: a reporter without a valid vote loses liveness;
+    /// a valid vote is compared against `aggregate`, if the slot produced one.
+    pub fn record_slot(
+        &mut self,
+        feed_id: u32,
+        reporter_ids: &[u64],
+        reports: &FeedReports,
+        aggregate: Option<&FeedType>,
+    ) {
+        for reporter_id in reports.report.keys() {
+            self.record_timeliness(*reporter_id, true);
+        }
+        for (reporter_id, late_votes) in &reports.late_votes {
+            for _ in 0..*late_votes {
+                self.record_timeliness(*reporter_id, false);
+            }
+        }
+
+        let decay = self.decay();
+        let feed_reporters = self.feed_reporters.entry(feed_id).or_default();
+        feed_reporters.extend(reports.report.keys().chain(reports.late_votes.keys()));
+        for reporter_id in reporter_ids
+            .iter()
+            .filter(|reporter_id| feed_reporters.contains(reporter_id))
+        {
+            let vote = reports
+                .report
+                .get(reporter_id)
+                .and_then(|v| v.result.as_ref().ok());
+            let reputation = self.reporters.entry(*reporter_id).or_default();
+            update_average(&mut reputation.liveness, vote.is_some() as u8 as f64, decay);
+            if let (Some(vote), Some(aggregate)) = (vote, aggregate) {
+                let accuracy = vote_accuracy(vote, aggregate, self.config.max_deviation_percentage);
+                update_average(&mut reputation.accuracy, accuracy, decay);
+            }
+            reputation.observations += 1;
+            update_jail_status(&self.config, *reporter_id, reputation);
+        }
+    }
+
+    /// Weights of the votes of the reporters for the aggregation. Jailed reporters weigh 0;
+    /// the rest weigh their score if enabled in the config. Reporters that are not in the
+    /// result weigh 1.
+    pub fn aggregation_weights(&self) -> HashMap<u64, f64> {
+        self.reporters
+            .iter()
+            .filter_map(|(reporter_id, reputation)| {
+                if reputation.jailed {
+                    Some((*reporter_id, 0.0))
+                } else if self.config.use_as_aggregation_weights {
+                    Some((*reporter_id, reputation.score()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Writes a snapshot of all reputations to `path` through a temporary file, so a crash
+    /// mid-write never leaves a truncated snapshot behind.
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        let serialized = serde_json::to_vec(self)?;
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serialized)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Restores the reputations from a snapshot written by `save_to_file`. The jail status is
+    /// re-evaluated, since the config might have changed in between. Returns the number of
+    /// restored reporters; a missing snapshot restores nothing.
+    pub fn load_from_file(&mut self, path: &Path) -> anyhow::Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let snapshot: ReporterReputations = serde_json::from_slice(&fs::read(path)?)?;
+        self.reporters = snapshot.reporters;
+        self.feed_reporters = snapshot.feed_reporters;
+        for (reporter_id, reputation) in self.reporters.iter_mut() {
+            update_jail_status(&self.config, *reporter_id, reputation);
+        }
+        Ok(self.reporters.len())
+    }
+}
+
+fn update_average(average: &mut f64, observation: f64, decay: f64) {
+    *average = decay * *average + (1.0 - decay) * observation;
+}
+
+fn update_jail_status(
+    config: &ReporterReputationConfig,
+    reporter_id: u64,
+    reputation: &mut ReporterReputation,
+) {
+    let jailed = config.jail_floor.is_some_and(|floor| {
+        reputation.observations >= config.min_observations && reputation.score() < floor
+    });
+    if jailed && !reputation.jailed {
+        warn!(
+            "Jailing reporter {reporter_id} with reputation score {}",
+            reputation.score()
+        );
+    } else if !jailed && reputation.jailed {
+        info!(
+            "Releasing reporter {reporter_id} from jail with reputation score {}",
+            reputation.score()
+        );
+    }
+    reputation.jailed = jailed;
+}
+
+fn as_f64(value: &FeedType) -> Option<f64> {
+    match value {
+        FeedType::Numerical(v) => Some(*v),
+        FeedType::Decimal(v) => Some(v.to_f64()),
+        _ => None,
+    }
+}
+
+/// Accuracy of `vote` in `[0, 1]`, decreasing linearly with its deviation from `aggregate`
+/// up to `max_deviation_percentage`. Non-numerical votes are either accurate or not, and
+/// the least accurate field determines the accuracy of a struct.
+fn vote_accuracy(vote: &FeedType, aggregate: &FeedType, max_deviation_percentage: f64) -> f64 {
+    match (vote, aggregate) {
+        (FeedType::Struct(vote_fields), FeedType::Struct(aggregate_fields)) => {
+            if vote_fields.len() != aggregate_fields.len() {
+                return 0.0;
+            }
+            vote_fields
+                .iter()
+                .zip(aggregate_fields)
+                .map(|(v, a)| {
+                    if v.name == a.name {
+                        vote_accuracy(&v.value, &a.value, max_deviation_percentage)
+                    } else {
+                        0.0
+                    }
+                })
+                .fold(1.0, f64::min)
+        }
+        _ => match (as_f64(vote), as_f64(aggregate)) {
+            (Some(v), Some(a)) => {
+                let deviation_percentage = if a == 0.0 {
+                    if v == 0.0 {
+                        0.0
+                    } else {
+                        f64::INFINITY
+                    }
+                } else {
+                    ((v - a) / a).abs() * 100.0
+                };
+                if deviation_percentage.is_nan() {
+                    return 0.0;
+                }
+                1.0 - (deviation_percentage / max_deviation_percentage).min(1.0)
+            }
+            _ => (vote == aggregate) as u8 as f64,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{test_payload_from_result, FeedError, StructField};
+
+    fn test_config() -> ReporterReputationConfig {
+        ReporterReputationConfig {
+            half_life: 1,
+            max_deviation_percentage: 10.0,
+            use_as_aggregation_weights: false,
+            jail_floor: Some(0.5),
+            min_observations: 2,
+        }
+    }
+
+    fn votes(values: &[(u64, f64)]) -> FeedReports {
+        FeedReports {
+            report: values
+                .iter()
+                .map(|(id, v)| (*id, test_payload_from_result(Ok(FeedType::Numerical(*v)))))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn with_failed_vote(mut reports: FeedReports, reporter_id: u64) -> FeedReports {
+        reports.report.insert(
+            reporter_id,
+            test_payload_from_result(Err(FeedError::APIError("down".to_string()))),
+        );
+        reports
+    }
+
+    #[test]
+    fn test_vote_accuracy() {
+        let aggregate = FeedType::Numerical(100.0);
+        assert_eq!(
+            vote_accuracy(&FeedType::Numerical(100.0), &aggregate, 10.0),
+            1.0
+        );
+        assert!((vote_accuracy(&FeedType::Numerical(105.0), &aggregate, 10.0) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            vote_accuracy(&FeedType::Numerical(150.0), &aggregate, 10.0),
+            0.0
+        );
+        assert_eq!(
+            vote_accuracy(&FeedType::Numerical(1.0), &FeedType::Numerical(0.0), 10.0),
+            0.0
+        );
+
+        let text = FeedType::Text("a".to_string());
+        assert_eq!(vote_accuracy(&text, &text, 10.0), 1.0);
+        assert_eq!(
+            vote_accuracy(&FeedType::Text("b".to_string()), &text, 10.0),
+            0.0
+        );
+
+        let bid_ask = |bid, ask| {
+            FeedType::Struct(vec![
+                StructField::new("bid", FeedType::Numerical(bid)),
+                StructField::new("ask", FeedType::Numerical(ask)),
+            ])
+        };
+        let accuracy = vote_accuracy(&bid_ask(100.0, 108.0), &bid_ask(100.0, 100.0), 10.0);
+        assert!((accuracy - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_slot_jails_and_releases() {
+        let mut reputations = ReporterReputations::new(test_config());
+        let reporters = [1, 2, 3, 4];
+        let aggregate = FeedType::Numerical(100.0);
+
+        // Reporter 2 is off by 50% and the vote of reporter 3 failed, which counts as a
+        // missed one. Reporter 4 never voted for the feed, so it is not expected to.
+        let slot = || with_failed_vote(votes(&[(1, 100.0), (2, 150.0)]), 3);
+        reputations.record_slot(1, &reporters, &slot(), Some(&aggregate));
+        assert_eq!(reputations.get(1).unwrap().score(), 1.0);
+        assert_eq!(reputations.get(2).unwrap().accuracy, 0.5);
+        assert_eq!(reputations.get(3).unwrap().liveness, 0.5);
+        assert!(reputations.get(4).is_none());
+        // Not enough observations yet.
+        assert!(!reputations.is_jailed(2));
+        assert!(reputations.aggregation_weights().is_empty());
+
+        reputations.record_slot(1, &reporters, &slot(), Some(&aggregate));
+        assert!(!reputations.is_jailed(1));
+        assert!(reputations.is_jailed(2));
+        assert!(reputations.is_jailed(3));
+        let weights = reputations.aggregation_weights();
+        assert_eq!(weights.get(&2), Some(&0.0));
+        assert_eq!(weights.get(&1), None);
+
+        // Missing a slot of the feed counts once the reporter has voted for it.
+        reputations.record_slot(
+            1,
+            &reporters,
+            &votes(&[(1, 100.0), (2, 100.0)]),
+            Some(&aggregate),
+        );
+        assert!(!reputations.is_jailed(2));
+        assert!(reputations.is_jailed(3));
+        assert!(reputations.get(4).is_none());
+        // Reporters are only expected to vote for the feeds they voted for.
+        reputations.record_slot(2, &reporters, &votes(&[(4, 100.0)]), Some(&aggregate));
+        assert_eq!(reputations.get(1).unwrap().observations, 3);
+        assert_eq!(reputations.get(4).unwrap().observations, 1);
+    }
+
+    #[test]
+    fn test_timeliness_and_weights() {
+        let config = ReporterReputationConfig {
+            use_as_aggregation_weights: true,
+            jail_floor: None,
+            ..test_config()
+        };
+        let mut reputations = ReporterReputations::new(config);
+        reputations.record_timeliness(1, true);
+        reputations.record_timeliness(2, false);
+        let weights = reputations.aggregation_weights();
+        assert_eq!(weights.get(&1), Some(&1.0));
+        assert_eq!(weights.get(&2), Some(&0.5));
+        assert!(!reputations.is_jailed(2));
+    }
+
+    #[test]
+    fn test_record_slot_scores_timeliness() {
+        let mut reputations = ReporterReputations::new(test_config());
+        let mut reports = votes(&[(1, 100.0)]);
+        reports.late_votes.insert(2, 2);
+        reputations.record_slot(1, &[], &reports, None);
+        assert_eq!(reputations.get(1).unwrap().punctuality, 1.0);
+        assert_eq!(reputations.get(2).unwrap().punctuality, 0.25);
+        assert_eq!(reputations.get(2).unwrap().observations, 0);
+    }
+
+    #[test]
+    fn test_reputation_snapshot_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "reporter_reputation_roundtrip_{}.json",
+            std::process::id()
+        ));
+        let mut reputations = ReporterReputations::new(test_config());
+        for _ in 0..2 {
+            reputations.record_slot(
+                1,
+                &[1, 2],
+                &with_failed_vote(votes(&[(1, 100.0)]), 2),
+                Some(&FeedType::Numerical(100.0)),
+            );
+        }
+        assert!(reputations.is_jailed(2));
+        reputations.save_to_file(&path).unwrap();
+
+        // Without a jail floor in the new config the reporter is released.
+        let mut restored = ReporterReputations::new(ReporterReputationConfig::default());
+        assert_eq!(restored.load_from_file(&path).unwrap(), 2);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(restored.get(1), reputations.get(1));
+        assert_eq!(
+            restored.get(2).unwrap().liveness,
+            reputations.get(2).unwrap().liveness
+        );
+        assert!(!restored.is_jailed(2));
+
+        let missing = std::env::temp_dir().join("reporter_reputation_does_not_exist.json");
+        assert_eq!(restored.load_from_file(&missing).unwrap(), 0);
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fmt::{self, Display},
+    str::FromStr,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
@@ -10,8 +11,9 @@ use tracing::debug;
 
 use crate::aggregate::FeedAggregate;
 use blocksense_crypto::{JsonSerializableSignature, Signature};
-use blocksense_registry::config::FeedConfig;
-use num::BigUint;
+pub use blocksense_registry::config::ReportSigningScheme;
+use blocksense_registry::config::{FeedConfig, NumericalEncoding, StructFieldConfig};
+use num::{bigint::Sign, BigInt, BigUint, Signed, ToPrimitive};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Repeatability {
@@ -42,16 +44,24 @@ pub struct FeedMetaData {
     feed_aggregator: FeedAggregate,
     pub value_type: String,
     pub aggregate_type: String,
+    struct_fields: Vec<StructFieldConfig>,
     pub processor_cmd_chan: Option<UnboundedSender<FeedsSlotProcessorCmds>>,
 }
 
 impl FeedMetaData {
+    /// Creates an event feed, whose single voting slot is open from
+    /// `voting_start_time` until `voting_end_time`.
     pub fn new_oneshot(
         name: String,
-        report_interval_ms: u64, // Consider oneshot feeds.
         quorum_percentage: f32,
-        first_report_start_time: SystemTime,
+        voting_start_time: SystemTime,
+        voting_end_time: SystemTime,
     ) -> FeedMetaData {
+        let report_interval_ms = voting_end_time
+            .duration_since(voting_start_time)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let first_report_start_time = voting_start_time;
         let skip_publish_if_less_then_percentage = 0.0f32;
         let always_publish_heartbeat_ms = None;
         FeedMetaData {
@@ -65,6 +75,7 @@ impl FeedMetaData {
             feed_aggregator: FeedAggregate::MajorityVoteAggregator,
             value_type: "text".to_string(),
             aggregate_type: "average".to_string(),
+            struct_fields: vec![],
             processor_cmd_chan: None,
         }
     }
@@ -80,8 +91,8 @@ impl FeedMetaData {
         value_type: String,
         aggregate_type: String,
         processor_cmd_chan: Option<UnboundedSender<FeedsSlotProcessorCmds>>,
-    ) -> FeedMetaData {
-        FeedMetaData {
+    ) -> anyhow::Result<FeedMetaData> {
+        Ok(FeedMetaData {
             name,
             voting_repeatability: Repeatability::Periodic,
             report_interval_ms,
@@ -89,16 +100,28 @@ impl FeedMetaData {
             skip_publish_if_less_then_percentage,
             always_publish_heartbeat_ms,
             first_report_start_time,
-            feed_aggregator: FeedAggregate::create_from_str(aggregate_type.as_str())
-                .expect("Could not convert {aggregate_type} to a valid aggregator!"), //TODO(snikolov): This should be resolved based upon the ConsensusMetric enum sent from the reporter or directly based on the feed_id
+            feed_aggregator: FeedAggregate::create_from_str(aggregate_type.as_str())?, //TODO(snikolov): This should be resolved based upon the ConsensusMetric enum sent from the reporter or directly based on the feed_id
             value_type,
             aggregate_type,
+            struct_fields: vec![],
             processor_cmd_chan,
-        }
+        })
+    }
+
+    /// Turns the feed into a struct feed, whose fields are aggregated
+    /// separately. Fields without an aggregation of their own use the feed's.
+    pub fn with_struct_fields(
+        mut self,
+        struct_fields: Vec<StructFieldConfig>,
+    ) -> anyhow::Result<FeedMetaData> {
+        self.feed_aggregator =
+            FeedAggregate::create_for_struct(&struct_fields, self.aggregate_type.as_str())?;
+        self.struct_fields = struct_fields;
+        Ok(self)
     }
 
-    pub fn from_config(cfg: &FeedConfig) -> Self {
-        Self::new(
+    pub fn from_config(cfg: &FeedConfig) -> anyhow::Result<Self> {
+        let feed = Self::new(
             cfg.full_name.clone(),
             cfg.schedule.interval_ms,
             cfg.quorum.percentage,
@@ -108,7 +131,21 @@ impl FeedMetaData {
             cfg.value_type.clone(),
             cfg.quorum.aggregation.clone(),
             None,
-        )
+        )?;
+        if cfg.struct_fields.is_empty() {
+            Ok(feed)
+        } else {
+            feed.with_struct_fields(cfg.struct_fields.clone())
+        }
+    }
+
+    /// Replaces the parameters of the feed with the ones in `cfg`, keeping the channel to its
+    /// running slots processor. The feed is left as it is if `cfg` is invalid.
+    pub fn update_from_config(&mut self, cfg: &FeedConfig) -> anyhow::Result<()> {
+        let mut updated = Self::from_config(cfg)?;
+        updated.processor_cmd_chan = self.processor_cmd_chan.take();
+        *self = updated;
+        Ok(())
     }
 
     pub fn set_processor_cmd_chan(&mut self, send_chan: UnboundedSender<FeedsSlotProcessorCmds>) {
@@ -138,6 +175,10 @@ impl FeedMetaData {
             .expect("Time went backwards");
         since_the_epoch.as_millis()
     }
+    /// End of the voting window of oneshot feeds, or of the first slot of periodic ones.
+    pub fn get_voting_end_time_ms(&self) -> u128 {
+        self.get_first_report_start_time_ms() + self.report_interval_ms as u128
+    }
     pub fn get_slot(&self, current_time_as_ms: u128) -> u64 {
         if self.voting_repeatability == Repeatability::Oneshot {
             // Oneshots only have the zero slot
@@ -147,7 +188,15 @@ impl FeedMetaData {
             / self.report_interval_ms as u128) as u64
     }
     pub fn get_feed_aggregator(&self) -> FeedAggregate {
-        self.feed_aggregator
+        self.feed_aggregator.clone()
+    }
+    /// Placeholder of the type of the feed's values, see `FeedType::from_bytes`.
+    pub fn get_value_variant(&self) -> Result<FeedType, String> {
+        if self.struct_fields.is_empty() {
+            FeedType::get_variant_from_string(self.value_type.as_str())
+        } else {
+            FeedType::get_struct_variant(&self.struct_fields)
+        }
     }
     pub fn check_report_relevance(
         &self,
@@ -158,6 +207,10 @@ impl FeedMetaData {
             + (self.get_slot(current_time_as_ms) as u128 * self.get_report_interval_ms() as u128);
         let end_of_voting_round = start_of_voting_round + self.get_report_interval_ms() as u128;
 
+        if self.is_oneshot() && current_time_as_ms > end_of_voting_round {
+            debug!("Rejected report, voting for the oneshot feed is closed.");
+            return ReportRelevance::NonRelevantOld;
+        }
         if msg_timestamp < start_of_voting_round {
             debug!("Rejected report, time stamp is in a past slot.");
             return ReportRelevance::NonRelevantOld;
@@ -186,80 +239,165 @@ impl FeedMetaData {
     }
 }
 
+/// Exact fixed-point number equal to `mantissa * 10^(-scale)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u8,
+}
+
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u8) -> Decimal {
+        Decimal { mantissa, scale }
+    }
+
+    /// Returns the same value expressed with `scale` digits in the fraction.
+    /// Surplus digits are truncated towards zero. Returns `None` on overflow.
+    pub fn rescale(&self, scale: u8) -> Option<Decimal> {
+        let mantissa = if scale >= self.scale {
+            10i128
+                .checked_pow((scale - self.scale) as u32)
+                .and_then(|factor| self.mantissa.checked_mul(factor))?
+        } else {
+            match 10i128.checked_pow((self.scale - scale) as u32) {
+                Some(factor) => self.mantissa / factor,
+                // The divisor is larger than any mantissa.
+                None => 0,
+            }
+        };
+        Some(Decimal { mantissa, scale })
+    }
+
+    /// Lossy conversion, only meant for statistics over the values (e.g.
+    /// anomaly detection), never for the values that get published.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Integer equal to the value scaled by `10^digits_in_fraction`, with
+    /// surplus digits truncated towards zero.
+    fn scaled_integer(&self, digits_in_fraction: usize) -> BigInt {
+        let mantissa = BigInt::from(self.mantissa);
+        let scale = self.scale as usize;
+        if digits_in_fraction >= scale {
+            mantissa * BigInt::from(10u32).pow((digits_in_fraction - scale) as u32)
+        } else {
+            mantissa / BigInt::from(10u32).pow((scale - digits_in_fraction) as u32)
+        }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{integer}.{fraction}")
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    /// Parses plain decimal notation like `-1234.5678` without any rounding.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let scale = u8::try_from(fraction.len())
+            .map_err(|_| format!("Too many fractional digits in {s}"))?;
+        let digits = format!("{integer}{fraction}");
+        if fraction.starts_with(['+', '-']) || digits.is_empty() {
+            return Err(format!("Invalid decimal {s}"));
+        }
+        let mantissa = digits
+            .parse::<i128>()
+            .map_err(|e| format!("Invalid decimal {s}: {e}"))?;
+        Ok(Decimal { mantissa, scale })
+    }
+}
+
+/// A named field of a `FeedType::Struct` value.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StructField {
+    pub name: String,
+    pub value: FeedType,
+}
+
+impl StructField {
+    pub fn new(name: &str, value: FeedType) -> StructField {
+        StructField {
+            name: name.to_string(),
+            value,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum FeedType {
     Numerical(f64),
     Text(String),
     Bytes(Vec<u8>),
+    Decimal(Decimal),
+    /// Ordered numerical fields, e.g. bid/ask or OHLC. Each field takes a
+    /// 32 byte slot of the feed's storage with the layout of a numerical value.
+    Struct(Vec<StructField>),
 }
 
 impl FeedType {
     pub fn sizeof(&self) -> usize {
         match self {
             FeedType::Numerical(_) => std::mem::size_of::<f64>(),
+            FeedType::Decimal(_) => std::mem::size_of::<Decimal>(),
             FeedType::Bytes(v) => v.len(),
             FeedType::Text(s) => s.len(),
+            FeedType::Struct(fields) => fields.iter().map(|f| f.value.sizeof()).sum(),
         }
     }
 
-    pub fn as_bytes(&self, digits_in_fraction: usize, timestamp: u64) -> Vec<u8> {
+    /// Encodes the value the way it is stored on chain. Fails for numerical values that can
+    /// not be represented there, i.e. non-finite ones or ones that do not fit in 24 bytes.
+    pub fn as_bytes(&self, digits_in_fraction: usize, timestamp: u64) -> Result<Vec<u8>, String> {
         match self {
-            FeedType::Numerical(val) => {
-                let truncate =
-                    |s: String, max_width: usize| -> String { s.chars().take(max_width).collect() };
-
-                let str_val = val.to_string();
-                let val_split: Vec<&str> = str_val.split('.').collect();
-
-                // BUG: A crash happened on the reporter side. Our guess is that the value was
-                // some of the special values of the floating point standard (IEEE 754).
-                // Left these logs in order to diagnose it later.
-                let integer = match val_split[0].parse::<BigUint>() {
-                    Ok(v) => v,
-                    Err(err) => {
-                        panic!("FeedType::as_bytes error: {err:?}. Value was {val}. Formatted like {str_val:?}");
-                    }
-                };
-
-                let actual_digits_in_fraction: usize;
-                let fraction: BigUint;
-
-                if val_split.len() > 1 {
-                    actual_digits_in_fraction =
-                        truncate(val_split[1].to_string(), digits_in_fraction).len();
-
-                    fraction = truncate(val_split[1].to_string(), digits_in_fraction)
-                        .parse::<BigUint>()
-                        .unwrap();
-                } else {
-                    actual_digits_in_fraction = 0;
-                    fraction = BigUint::from(0u32);
+            FeedType::Numerical(val) => encode_scaled_integer(
+                numerical_to_scaled_integer(*val, digits_in_fraction)?,
+                timestamp,
+            ),
+            FeedType::Decimal(val) => {
+                encode_scaled_integer(val.scaled_integer(digits_in_fraction), timestamp)
+            }
+            FeedType::Text(s) => Ok(s.as_bytes().to_vec()),
+            FeedType::Bytes(bytes) => Ok(bytes.clone()),
+            FeedType::Struct(fields) => {
+                let mut bytes = Vec::new();
+                for field in fields {
+                    let field_bytes = field
+                        .value
+                        .as_bytes(digits_in_fraction, timestamp)
+                        .map_err(|e| format!("Struct field {}: {e}", field.name))?;
+                    bytes.extend(field_bytes);
                 }
-
-                let result = (integer * BigUint::from(10u32).pow(digits_in_fraction as u32))
-                    + (fraction
-                        * BigUint::from(10u32)
-                            .pow(digits_in_fraction as u32 - actual_digits_in_fraction as u32));
-
-                let mut value_bytes = result.to_bytes_be();
-                let mut bytes_vec = vec![0; 32 - value_bytes.len()];
-                bytes_vec.append(&mut value_bytes);
-
-                bytes_vec.drain(..8);
-                bytes_vec.extend(timestamp.to_be_bytes());
-
-                bytes_vec
+                Ok(bytes)
             }
-            FeedType::Text(s) => s.as_bytes().to_vec(),
-            FeedType::Bytes(bytes) => bytes.clone(),
         }
     }
 
     pub fn parse_to_string(&self) -> String {
         match self {
             FeedType::Numerical(val) => format!("{}", val),
+            FeedType::Decimal(val) => val.to_string(),
             FeedType::Text(s) => s.clone(),
             FeedType::Bytes(bytes) => format!("{:?}", bytes),
+            FeedType::Struct(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|f| format!("{}: {}", f.name, f.value.parse_to_string()))
+                    .collect();
+                format!("{{{}}}", fields.join(", "))
+            }
         }
     }
 
@@ -267,25 +405,22 @@ impl FeedType {
         bytes: Vec<u8>,
         variant: FeedType,
         digits_in_fraction: usize,
+        encoding: NumericalEncoding,
     ) -> Result<FeedType, String> {
         match variant {
             FeedType::Numerical(_) => {
-                if bytes.len() < 32 {
-                    return Err("Bytes len less than required!".to_string());
-                }
-
-                let mut bytes_mut = bytes;
-                bytes_mut.truncate(24);
-
-                let mut result = vec![0_u8; 8];
-                result.append(&mut bytes_mut);
+                let combined = decode_scaled_integer(&bytes, encoding)?;
 
-                let combined = BigUint::from_bytes_be(&result);
+                let sign = if combined.is_negative() { "-" } else { "" };
+                let combined = combined.magnitude();
+                let fraction = combined % BigUint::from(10u32).pow(digits_in_fraction as u32);
+                let integer = combined / BigUint::from(10u32).pow(digits_in_fraction as u32);
 
-                let fraction = &combined % BigUint::from(10u32).pow(digits_in_fraction as u32);
-                let integer = &combined / BigUint::from(10u32).pow(digits_in_fraction as u32);
-
-                let str_val = format!("{}.{:0>digits_in_fraction$}", integer, fraction.to_string());
+                let str_val = format!(
+                    "{sign}{}.{:0>digits_in_fraction$}",
+                    integer,
+                    fraction.to_string()
+                );
 
                 let val = match str_val.parse::<f64>() {
                     Ok(v) => v,
@@ -294,12 +429,54 @@ impl FeedType {
 
                 Ok(FeedType::Numerical(val))
             }
+            FeedType::Decimal(_) => {
+                let scale = u8::try_from(digits_in_fraction)
+                    .map_err(|_| format!("Unsupported digits in fraction {digits_in_fraction}"))?;
+                // Any mantissa fits in 16 bytes, so values are read as two's complement whatever
+                // the encoding of the feed: the unsigned ones in range have the top bit clear.
+                let mantissa = decode_scaled_integer(&bytes, NumericalEncoding::Signed)?
+                    .to_i128()
+                    .ok_or("Bytes do not fit in a decimal mantissa!".to_string())?;
+
+                Ok(FeedType::Decimal(Decimal { mantissa, scale }))
+            }
             FeedType::Text(_) => {
                 let s =
                     String::from_utf8(bytes).map_err(|_| "Invalid UTF-8 sequence".to_string())?;
                 Ok(FeedType::Text(s))
             }
             FeedType::Bytes(_) => Ok(FeedType::Bytes(bytes)),
+            FeedType::Struct(fields) => {
+                if bytes.len() < STRUCT_FIELD_SIZE * fields.len() {
+                    return Err(format!(
+                        "Bytes len less than required for {} struct fields!",
+                        fields.len()
+                    ));
+                }
+                let mut values = Vec::with_capacity(fields.len());
+                for (field, field_bytes) in fields.into_iter().zip(bytes.chunks(STRUCT_FIELD_SIZE))
+                {
+                    if !matches!(field.value, FeedType::Numerical(_) | FeedType::Decimal(_)) {
+                        return Err(format!(
+                            "Struct field {} has unsupported type {}",
+                            field.name,
+                            field.value.enum_type_to_string()
+                        ));
+                    }
+                    let value = FeedType::from_bytes(
+                        field_bytes.to_vec(),
+                        field.value,
+                        digits_in_fraction,
+                        encoding,
+                    )
+                    .map_err(|e| format!("Struct field {}: {e}", field.name))?;
+                    values.push(StructField {
+                        name: field.name,
+                        value,
+                    });
+                }
+                Ok(FeedType::Struct(values))
+            }
         }
     }
 
@@ -308,6 +485,8 @@ impl FeedType {
             FeedType::Numerical(_) => "FeedType::Numerical",
             FeedType::Text(_) => "FeedType::Text",
             FeedType::Bytes(_) => "FeedType::Bytes",
+            FeedType::Decimal(_) => "FeedType::Decimal",
+            FeedType::Struct(_) => "FeedType::Struct",
         }
     }
 
@@ -316,6 +495,10 @@ impl FeedType {
             "numerical" => FeedType::Numerical(0.0f64),
             "text" => FeedType::Text("".to_string()),
             "bytes" => FeedType::Bytes(vec![]),
+            "decimal" => FeedType::Decimal(Decimal::new(0, 0)),
+            "struct" => {
+                return Err("The struct feed type requires struct fields".to_string());
+            }
             _ => {
                 return Err(format!("Unsupported feed type {feed_type}"));
             }
@@ -323,9 +506,122 @@ impl FeedType {
         Ok(feed_type)
     }
 
+    /// Placeholder for the values of a struct feed with the given fields.
+    pub fn get_struct_variant(fields: &[StructFieldConfig]) -> Result<FeedType, String> {
+        let mut variant = Vec::with_capacity(fields.len());
+        for field in fields {
+            let value = match field.value_type.as_str() {
+                "numerical" | "decimal" => FeedType::get_variant_from_string(&field.value_type)?,
+                other => {
+                    return Err(format!(
+                        "Unsupported type {other} of struct field {}",
+                        field.name
+                    ));
+                }
+            };
+            variant.push(StructField {
+                name: field.name.clone(),
+                value,
+            });
+        }
+        Ok(FeedType::Struct(variant))
+    }
+
+    pub fn get_variant_from_config(cfg: &FeedConfig) -> Result<FeedType, String> {
+        if cfg.struct_fields.is_empty() {
+            FeedType::get_variant_from_string(cfg.value_type.as_str())
+        } else {
+            FeedType::get_struct_variant(&cfg.struct_fields)
+        }
+    }
+
+    /// True for numerical values below zero, which can only be published by
+    /// feeds with signed encoding.
+    pub fn is_negative(&self) -> bool {
+        match self {
+            FeedType::Numerical(val) => *val < 0.0,
+            FeedType::Decimal(val) => val.mantissa < 0,
+            FeedType::Struct(fields) => fields.iter().any(|f| f.value.is_negative()),
+            FeedType::Text(_) | FeedType::Bytes(_) => false,
+        }
+    }
+
+    /// False for NaN and infinite numbers, also within struct fields.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            FeedType::Numerical(val) => val.is_finite(),
+            FeedType::Struct(fields) => fields.iter().all(|f| f.value.is_finite()),
+            FeedType::Decimal(_) | FeedType::Text(_) | FeedType::Bytes(_) => true,
+        }
+    }
+
+    /// Struct values are only of the same type if they have the same fields
+    /// in the same order.
     pub fn same_enum_type_as(&self, other: &FeedType) -> bool {
-        std::mem::discriminant(self) == std::mem::discriminant(other)
+        match (self, other) {
+            (FeedType::Struct(fields), FeedType::Struct(other_fields)) => {
+                fields.len() == other_fields.len()
+                    && fields
+                        .iter()
+                        .zip(other_fields)
+                        .all(|(a, b)| a.name == b.name && a.value.same_enum_type_as(&b.value))
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+/// Every struct field is laid out like a numerical value.
+const STRUCT_FIELD_SIZE: usize = 32;
+
+/// Converts `val` to an integer equal to `val * 10^digits_in_fraction`, with
+/// surplus fraction digits truncated.
+fn numerical_to_scaled_integer(val: f64, digits_in_fraction: usize) -> Result<BigInt, String> {
+    // `Display` for f64 never uses exponent notation, but special values like
+    // NaN and infinity can not be represented on-chain.
+    if !val.is_finite() {
+        return Err(format!("Can not encode non-finite value {val}"));
+    }
+    let str_val = val.to_string();
+    let (sign, magnitude) = match str_val.strip_prefix('-') {
+        Some(magnitude) => (Sign::Minus, magnitude),
+        None => (Sign::Plus, str_val.as_str()),
+    };
+    let (integer, fraction) = magnitude.split_once('.').unwrap_or((magnitude, ""));
+    let fraction: String = fraction.chars().take(digits_in_fraction).collect();
+    let digits = format!("{integer}{fraction:0<digits_in_fraction$}");
+    let magnitude = digits
+        .parse::<BigUint>()
+        .map_err(|e| format!("{e:?}. Value was {val}. Formatted like {str_val:?}"))?;
+    Ok(BigInt::from_biguint(sign, magnitude))
+}
+
+/// Lays out a scaled integer as 24 bytes of two's complement followed by the
+/// 8 bytes of the timestamp. Non-negative values look exactly like the
+/// unsigned encoding.
+fn encode_scaled_integer(value: BigInt, timestamp: u64) -> Result<Vec<u8>, String> {
+    let mut value_bytes = value.to_signed_bytes_be();
+    if value_bytes.len() > 24 {
+        return Err(format!("Scaled value {value} does not fit in 24 bytes"));
+    }
+    let fill = if value.is_negative() { 0xff } else { 0 };
+    let mut bytes_vec = vec![fill; 24 - value_bytes.len()];
+    bytes_vec.append(&mut value_bytes);
+
+    bytes_vec.extend(timestamp.to_be_bytes());
+
+    Ok(bytes_vec)
+}
+
+fn decode_scaled_integer(bytes: &[u8], encoding: NumericalEncoding) -> Result<BigInt, String> {
+    if bytes.len() < 32 {
+        return Err("Bytes len less than required!".to_string());
     }
+    let value_bytes = &bytes[..24];
+    Ok(match encoding {
+        NumericalEncoding::Unsigned => BigInt::from(BigUint::from_bytes_be(value_bytes)),
+        NumericalEncoding::Signed => BigInt::from_signed_bytes_be(value_bytes),
+    })
 }
 
 pub type Timestamp = u128;
@@ -377,6 +673,7 @@ pub fn test_payload_from_result(result: FeedResult) -> DataFeedPayload {
                 ])
                 .expect("Signature::deserialize failed!"),
             },
+            signing_scheme: ReportSigningScheme::V0,
         },
         result,
     }
@@ -395,6 +692,9 @@ pub struct PayloadMetaData {
     pub timestamp: Timestamp,
     /// signature of feed_id + timestamp + result
     pub signature: JsonSerializableSignature,
+    /// scheme of the signed message, `V0` for reporters that do not send it
+    #[serde(default, skip_serializing_if = "ReportSigningScheme::is_v0")]
+    pub signing_scheme: ReportSigningScheme,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
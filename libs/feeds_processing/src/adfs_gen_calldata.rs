@@ -83,7 +83,6 @@ pub async fn adfs_serialize_updates(
     // Fill the value updates:
     for update in updates.iter() {
         let feed_id = update.feed_id;
-        feeds_ids_with_value_updates.insert(feed_id);
 
         let (stride, digits_in_fraction) = match &strides_and_decimals.get(&feed_id) {
             Some(f) => (f.stride, f.decimals),
@@ -93,6 +92,20 @@ pub async fn adfs_serialize_updates(
             }
         };
 
+        // Encode before touching the rounds table, so a skipped update leaves no gap in it.
+        // Key is not needed. It is the bytes of the feed_id
+        let (_key, val) = match update.encode(
+            digits_in_fraction as usize,
+            update.end_slot_timestamp as u64,
+        ) {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                error!("Skipping feed update: {e}");
+                continue;
+            }
+        };
+        feeds_ids_with_value_updates.insert(feed_id);
+
         let mut round = match &round_counters {
             Some(rc) => {
                 let mut updated_feed_id_round: u64 = 0;
@@ -125,11 +138,6 @@ pub async fn adfs_serialize_updates(
 
         round %= MAX_HISTORY_ELEMENTS_PER_FEED;
 
-        let (_key, val) = update.encode(
-            digits_in_fraction as usize,
-            update.end_slot_timestamp as u64,
-        ); // Key is not needed. It is the bytes of the feed_id
-
         let id = U256::from(update.feed_id);
         let round = U256::from(round);
         let index = (id * U256::from(2).pow(U256::from(13u32)) + round)
@@ -249,7 +257,8 @@ pub fn get_neighbour_feed_ids(feed_id: u32) -> Vec<u32> {
 #[cfg(test)]
 pub mod tests {
     use blocksense_data_feeds::feeds_processing::VotedFeedUpdate;
-    use blocksense_feed_registry::types::FeedType;
+    use blocksense_feed_registry::types::{Decimal, FeedType, StructField};
+    use blocksense_registry::config::NumericalEncoding;
 
     use super::*;
 
@@ -262,7 +271,13 @@ pub mod tests {
             let bytes = from_hex_string(value).unwrap();
             VotedFeedUpdate {
                 feed_id,
-                value: FeedType::from_bytes(bytes, FeedType::Bytes(Vec::new()), 18).unwrap(),
+                value: FeedType::from_bytes(
+                    bytes,
+                    FeedType::Bytes(Vec::new()),
+                    18,
+                    NumericalEncoding::Unsigned,
+                )
+                .unwrap(),
                 end_slot_timestamp: 0,
             }
         }
@@ -334,4 +349,55 @@ pub mod tests {
             )
         );
     }
+
+    #[tokio::test]
+    async fn test_adfs_serialize_struct_feed() {
+        let updates = BatchedAggegratesToSend {
+            block_height: 1,
+            updates: vec![VotedFeedUpdate {
+                feed_id: 1,
+                value: FeedType::Struct(vec![
+                    StructField::new("bid", FeedType::Numerical(1.5)),
+                    StructField::new("ask", FeedType::Decimal(Decimal::new(16, 1))),
+                ]),
+                end_slot_timestamp: 0,
+            }],
+        };
+        let config = HashMap::from([(
+            1,
+            FeedStrideAndDecimals {
+                stride: 1,
+                decimals: 2,
+            },
+        )]);
+        let mut round_counters = RoundCounters::new();
+        round_counters.insert(1, 0);
+
+        let serialized = hex::encode(
+            adfs_serialize_updates(
+                "ETH",
+                &updates,
+                Some(&round_counters),
+                config,
+                &mut HashMap::new(),
+            )
+            .await
+            .unwrap(),
+        );
+
+        // Every field takes a 32 byte slot of the 64 bytes of stride 1.
+        let slot = |value: &str| format!("{}{value}{}", "00".repeat(23), "00".repeat(8));
+        let expected_update = format!(
+            "{}{}{}{}{}",
+            "00000000000000000100000001", // block height and number of updates
+            "01024000",                   // stride and index of round 0 of feed 1
+            "0140",                       // length of the value
+            slot("96"),                   // bid: 1.50
+            slot("a0"),                   // ask: 1.60
+        );
+        assert!(
+            serialized.starts_with(&expected_update),
+            "{serialized} does not start with {expected_update}"
+        );
+    }
 }
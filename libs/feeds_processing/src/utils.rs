@@ -9,10 +9,12 @@ use blocksense_data_feeds::feeds_processing::{
     BatchedAggegratesToSend, DoSkipReason, DontSkipReason, SkipDecision, VotedFeedUpdate,
     VotedFeedUpdateWithProof,
 };
+use blocksense_data_feeds::generate_signature::report_signing_message;
 use blocksense_feed_registry::{
     aggregate::FeedAggregate,
+    market_hours::MarketCalendar,
     registry::FeedAggregateHistory,
-    types::{DataFeedPayload, FeedResult, FeedType, Timestamp},
+    types::{DataFeedPayload, FeedResult, FeedType, ReportSigningScheme, Timestamp},
 };
 use blocksense_gnosis_safe::{
     data_types::ConsensusSecondRoundBatch,
@@ -31,21 +33,14 @@ pub const AD_MIN_DATA_POINTS_THRESHOLD: usize = 100;
 pub fn check_signature(
     signature: &Signature,
     pub_key: &PublicKey,
+    scheme: ReportSigningScheme,
+    deployment_id: &str,
     feed_id: &str,
     timestamp: Timestamp,
     feed_result: &FeedResult,
-) -> bool {
-    let mut byte_buffer: Vec<u8> = feed_id
-        .as_bytes()
-        .iter()
-        .copied()
-        .chain(timestamp.to_be_bytes().to_vec())
-        .collect();
-
-    if let Ok(result) = feed_result {
-        byte_buffer.extend(result.as_bytes(18, timestamp as u64));
-    }
-    verify_signature(pub_key, signature, &byte_buffer)
+) -> anyhow::Result<bool> {
+    let message = report_signing_message(scheme, deployment_id, feed_id, timestamp, feed_result)?;
+    Ok(verify_signature(pub_key, signature, &message))
 }
 
 #[derive(Debug)]
@@ -55,6 +50,9 @@ pub struct ConsumedReports {
     pub ad_score: Option<f64>,
     pub result_post_to_contract: Option<VotedFeedUpdateWithProof>,
     pub end_slot_timestamp: Timestamp,
+    /// Votes left out of the aggregation, because their value was not finite or not of the
+    /// type of the feed.
+    pub dropped_votes: usize,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -71,9 +69,12 @@ pub async fn consume_reports(
     is_oneshot: bool,
     aggregator: FeedAggregate,
     history: Option<Arc<RwLock<FeedAggregateHistory>>>,
+    market_calendar: Option<&MarketCalendar>,
     feed_id: u32,
+    reporter_weights: &HashMap<u64, f64>,
 ) -> ConsumedReports {
-    let values = collect_reported_values(feed_type, feed_id, reports, slot);
+    let (values, weights, dropped_votes) =
+        collect_reported_weighted_values(feed_type, feed_id, reports, slot, reporter_weights);
 
     if values.is_empty() {
         info!("No reports found for feed: {} slot: {}!", name, &slot);
@@ -83,6 +84,7 @@ pub async fn consume_reports(
             ad_score: None,
             result_post_to_contract: None,
             end_slot_timestamp,
+            dropped_votes,
         }
     } else {
         let total_votes_count = values.len() as f32;
@@ -96,9 +98,26 @@ pub async fn consume_reports(
         }
 
         // Dispatch to concrete FeedAggregate implementation.
+        let value = match aggregator.aggregate_weighted(&values[..], &weights[..]) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(
+                    "Could not aggregate reports for feed: {} slot: {} using {}: {}",
+                    name, &slot, aggregator, e
+                );
+                return ConsumedReports {
+                    is_quorum_reached,
+                    skip_decision: SkipDecision::DoSkip(DoSkipReason::AggregationFailed),
+                    ad_score: None,
+                    result_post_to_contract: None,
+                    end_slot_timestamp,
+                    dropped_votes,
+                };
+            }
+        };
         let result_post_to_contract = VotedFeedUpdate {
             feed_id,
-            value: aggregator.aggregate(&values[..]), // Perform the concrete aggregation
+            value,
             end_slot_timestamp,
         };
 
@@ -112,7 +131,12 @@ pub async fn consume_reports(
         // Oneshot feeds have no history, so we cannot perform anomaly detection on them.
         let skip_decision = if !is_oneshot {
             if let Some(history) = history {
-                if let FeedType::Numerical(candidate_value) = result_post_to_contract.value {
+                let candidate_value = match &result_post_to_contract.value {
+                    FeedType::Numerical(value) => Some(*value),
+                    FeedType::Decimal(value) => Some(value.to_f64()),
+                    _ => None,
+                };
+                if let Some(candidate_value) = candidate_value {
                     let ad_score =
                         perform_anomaly_detection(feed_id, history.clone(), candidate_value).await;
                     match ad_score {
@@ -137,8 +161,11 @@ pub async fn consume_reports(
                         };
                         debug!("Get a read lock on history [feed {feed_id}]");
                         let history_guard = history.read().await;
-                        let skip_decision =
-                            result_post_to_contract.should_skip(&criteria, &history_guard);
+                        let skip_decision = result_post_to_contract.should_skip_with_market_hours(
+                            &criteria,
+                            &history_guard,
+                            market_calendar,
+                        );
                         debug!("Release the read lock on history [feed {feed_id}]");
                         skip_decision
                     }
@@ -160,6 +187,7 @@ pub async fn consume_reports(
                 proof,
             }),
             end_slot_timestamp,
+            dropped_votes,
         };
         info!("[feed {feed_id}] result_post_to_contract = {:?}", res);
         res
@@ -172,14 +200,47 @@ pub fn collect_reported_values(
     reports: &HashMap<u64, DataFeedPayload>,
     slot: u64,
 ) -> Vec<FeedType> {
+    collect_reported_weighted_values(expected_feed_type, feed_id, reports, slot, &HashMap::new()).0
+}
+
+/// Like `collect_reported_values`, but also returns the weight of every value, taken from
+/// `reporter_weights` by the id of its reporter. Reporters missing from `reporter_weights`
+/// weigh 1, and the votes of reporters that weigh nothing (e.g. jailed ones) are dropped.
+/// Votes that are not finite or not of the expected type are dropped as well, and counted in
+/// the last returned value, so that one bad vote does not fail the aggregation of the rest.
+pub fn collect_reported_weighted_values(
+    expected_feed_type: &FeedType,
+    feed_id: u32,
+    reports: &HashMap<u64, DataFeedPayload>,
+    slot: u64,
+    reporter_weights: &HashMap<u64, f64>,
+) -> (Vec<FeedType>, Vec<f64>, usize) {
     let mut values: Vec<FeedType> = vec![];
+    let mut weights: Vec<f64> = vec![];
+    let mut dropped_votes = 0;
     for kv in reports {
+        let weight = reporter_weights.get(kv.0).copied().unwrap_or(1.0);
+        if weight <= 0.0 {
+            debug!(
+                "Ignoring vote of jailed reporter {} for feed id {} slot {}",
+                kv.0, feed_id, slot
+            );
+            continue;
+        }
         match &kv.1.result {
             Ok(value) => {
-                if value.same_enum_type_as(expected_feed_type) {
-                    values.push(value.clone());
-                } else {
+                if !value.same_enum_type_as(expected_feed_type) {
                     warn!("Wrong value type reported by reporter {} for feed id {} slot {}! {} expected", kv.0, feed_id, slot, expected_feed_type.enum_type_to_string());
+                    dropped_votes += 1;
+                } else if !value.is_finite() {
+                    warn!(
+                        "Non-finite value {:?} reported by reporter {} for feed id {} slot {}",
+                        value, kv.0, feed_id, slot
+                    );
+                    dropped_votes += 1;
+                } else {
+                    values.push(value.clone());
+                    weights.push(weight);
                 }
             }
             Err(_) => {
@@ -190,7 +251,7 @@ pub fn collect_reported_values(
             }
         }
     }
-    values
+    (values, weights, dropped_votes)
 }
 
 pub async fn perform_anomaly_detection(
@@ -215,6 +276,8 @@ pub async fn perform_anomaly_detection(
             .filter_map(|feed| {
                 if let FeedType::Numerical(value) = feed {
                     Some(*value)
+                } else if let FeedType::Decimal(value) = feed {
+                    Some(value.to_f64())
                 } else if let FeedType::Text(_) = feed {
                     warn!("Anomaly Detection not implemented for FeedType::Text, skipping...");
                     None
@@ -265,16 +328,18 @@ fn check_aggregated_votes_deviation(
             anyhow::bail!("Failed to get latest vote for feed_id: {}", feed_id);
         };
 
-        let update_aggregate_value = match update.value {
-            FeedType::Numerical(v) => v,
+        let update_aggregate_value = match &update.value {
+            FeedType::Numerical(v) => *v,
+            FeedType::Decimal(v) => v.to_f64(),
             _ => anyhow::bail!(
                 "Non numeric value in update_aggregate_value for feed_id: {}",
                 feed_id
             ),
         };
 
-        let reporter_voted_value = match reporter_vote.value {
-            FeedType::Numerical(v) => v,
+        let reporter_voted_value = match &reporter_vote.value {
+            FeedType::Numerical(v) => *v,
+            FeedType::Decimal(v) => v.to_f64(),
             _ => anyhow::bail!(
                 "Non numeric value in reporter_vote for feed_id: {}",
                 feed_id
@@ -616,4 +681,82 @@ pub mod tests {
             error_message
         );
     }
+
+    #[tokio::test]
+    async fn test_consume_reports_drops_non_finite_and_mistyped_votes() {
+        use blocksense_feed_registry::types::test_payload_from_result;
+
+        let mut reports = HashMap::new();
+        reports.insert(0, test_payload_from_result(Ok(FeedType::Numerical(42.0))));
+        reports.insert(
+            1,
+            test_payload_from_result(Ok(FeedType::Numerical(f64::NAN))),
+        );
+        reports.insert(
+            2,
+            test_payload_from_result(Ok(FeedType::Text("42".to_string()))),
+        );
+
+        let consumed = consume_reports(
+            "nan_feed",
+            &reports,
+            &FeedType::Numerical(0.0),
+            0,
+            30.0,
+            0.0,
+            None,
+            0,
+            3,
+            false,
+            FeedAggregate::MedianAggregator,
+            None,
+            None,
+            0,
+            &HashMap::new(),
+        )
+        .await;
+
+        assert!(consumed.is_quorum_reached);
+        assert_eq!(consumed.dropped_votes, 2);
+        assert_eq!(
+            consumed.skip_decision,
+            SkipDecision::DontSkip(DontSkipReason::NoHistory)
+        );
+        assert_eq!(
+            consumed.result_post_to_contract.unwrap().update.value,
+            FeedType::Numerical(42.0)
+        );
+    }
+
+    #[test]
+    fn test_collect_reported_weighted_values_drops_jailed_reporters() {
+        use blocksense_feed_registry::types::test_payload_from_result;
+
+        let mut reports = HashMap::new();
+        for (reporter_id, value) in [(0, 1.0), (1, 2.0), (2, 3.0)] {
+            reports.insert(
+                reporter_id,
+                test_payload_from_result(Ok(FeedType::Numerical(value))),
+            );
+        }
+        let reporter_weights = HashMap::from([(1, 0.0), (2, 0.5)]);
+
+        let (values, weights, _) = collect_reported_weighted_values(
+            &FeedType::Numerical(0.0),
+            0,
+            &reports,
+            0,
+            &reporter_weights,
+        );
+        let mut weighted: Vec<(f64, f64)> = values
+            .iter()
+            .zip(weights)
+            .map(|(v, w)| match v {
+                FeedType::Numerical(v) => (*v, w),
+                _ => panic!("Unexpected value {v:?}"),
+            })
+            .collect();
+        weighted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(weighted, vec![(1.0, 1.0), (3.0, 0.5)]);
+    }
 }
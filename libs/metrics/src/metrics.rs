@@ -258,10 +258,16 @@ pub struct ReporterMetrics {
     pub json_scheme_error: IntCounterVec,
     pub non_valid_feed_id_reports: IntCounterVec,
     pub non_valid_signature: IntCounterVec,
+    pub unaccepted_signing_scheme: IntCounterVec,
     pub timely_reports_per_feed: IntCounterVec,
     pub late_reports_per_feed: IntCounterVec,
     pub in_future_reports_per_feed: IntCounterVec,
     pub total_revotes_for_same_slot_per_feed: IntCounterVec,
+    pub negative_reports_for_unsigned_feed: IntCounterVec,
+    pub rate_limited_reports: IntCounterVec,
+    pub revotes_over_limit_per_feed: IntCounterVec,
+    pub oversized_batches: IntCounter,
+    pub rate_limited_ip_requests: IntCounter,
 }
 
 impl ReporterMetrics {
@@ -287,6 +293,11 @@ impl ReporterMetrics {
                 "Total recvd reports with non valid signature from reporter",
                 &["ReporterId"]
             )?,
+            unaccepted_signing_scheme: register_int_counter_vec!(
+                format!("{}reporter_unaccepted_signing_scheme", prefix),
+                "Total recvd reports signed with a scheme that is not accepted from reporter",
+                &["ReporterId"]
+            )?,
             timely_reports_per_feed: register_int_counter_vec!(
                 format!("{}reporter_timely_reports_per_feed", prefix),
                 "Per feed accepted (valid) feed reports from reporter",
@@ -307,6 +318,29 @@ impl ReporterMetrics {
                 "Total recvd revotes for the same slot from reporter",
                 &["ReporterId", "FeedId"]
             )?,
+            negative_reports_for_unsigned_feed: register_int_counter_vec!(
+                format!("{}reporter_negative_reports_for_unsigned_feed", prefix),
+                "Per feed recvd negative values for feeds that are published unsigned",
+                &["ReporterId", "FeedId"]
+            )?,
+            rate_limited_reports: register_int_counter_vec!(
+                format!("{}reporter_rate_limited_reports", prefix),
+                "Total reports rejected for exceeding the rate limit of the reporter",
+                &["ReporterId"]
+            )?,
+            revotes_over_limit_per_feed: register_int_counter_vec!(
+                format!("{}reporter_revotes_over_limit_per_feed", prefix),
+                "Per feed revotes rejected for exceeding the revotes allowed in a slot",
+                &["ReporterId", "FeedId"]
+            )?,
+            oversized_batches: register_int_counter!(
+                format!("{}reporter_oversized_batches", prefix),
+                "Total batches of reports rejected for exceeding max_batch_len"
+            )?,
+            rate_limited_ip_requests: register_int_counter!(
+                format!("{}reporter_rate_limited_ip_requests", prefix),
+                "Total report requests rejected for exceeding the rate limit of their client IP"
+            )?,
         })
     }
 }
@@ -316,10 +350,13 @@ pub struct FeedsMetrics {
     pub quorums_reached: IntCounterVec,
     pub failures_to_reach_quorum: IntCounterVec,
     pub updates_to_networks: IntCounterVec,
+    pub dropped_votes: IntCounterVec,
 
     // skip-publishing related metrics
     pub skipped_too_similar_too_soon: IntCounterVec,
     pub skipped_nothing_to_post: IntCounterVec,
+    pub skipped_aggregation_failed: IntCounterVec,
+    pub skipped_market_closed: IntCounterVec,
 
     pub updated_threshold_crossed: IntCounterVec,
     pub updated_heartbeat_timed_out: IntCounterVec,
@@ -347,6 +384,11 @@ impl FeedsMetrics {
                 "Number of updates for a given feed id per Network",
                 &["FeedId", "Network"]
             )?,
+            dropped_votes: register_int_counter_vec!(
+                format!("{}dropped_votes", prefix),
+                "Number of votes for a given feed id left out of the aggregation, because their value was not finite or not of the type of the feed",
+                &["FeedId"]
+            )?,
 
             skipped_too_similar_too_soon: register_int_counter_vec!(
                 format!("{}skipped_too_similar_too_soon", prefix),
@@ -358,6 +400,16 @@ impl FeedsMetrics {
                 "Number of updates skipped for a given feed, because there was nothing to post",
                 &["FeedId"]
             )?,
+            skipped_aggregation_failed: register_int_counter_vec!(
+                format!("{}skipped_aggregation_failed", prefix),
+                "Number of updates skipped for a given feed, because the reported values could not be aggregated",
+                &["FeedId"]
+            )?,
+            skipped_market_closed: register_int_counter_vec!(
+                format!("{}skipped_market_closed", prefix),
+                "Number of slots skipped for a given feed, because its market was closed",
+                &["FeedId"]
+            )?,
 
             updated_threshold_crossed: register_int_counter_vec!(
                 format!("{}updated_threshold_crossed", prefix),
@@ -392,3 +444,26 @@ impl FeedsMetrics {
         })
     }
 }
+
+#[derive(Debug)]
+pub struct AdminMetrics {
+    pub unauthorized_calls: IntCounterVec,
+    pub forbidden_calls: IntCounterVec,
+}
+
+impl AdminMetrics {
+    pub fn new(prefix: &str) -> Result<AdminMetrics> {
+        Ok(AdminMetrics {
+            unauthorized_calls: register_int_counter_vec!(
+                format!("{}admin_unauthorized_calls", prefix),
+                "Calls to the admin API rejected for a missing or unknown token",
+                &["Endpoint"]
+            )?,
+            forbidden_calls: register_int_counter_vec!(
+                format!("{}admin_forbidden_calls", prefix),
+                "Calls to the admin API rejected because the token lacks the required role",
+                &["Endpoint"]
+            )?,
+        })
+    }
+}
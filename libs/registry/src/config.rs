@@ -54,6 +54,62 @@ pub struct CompatibilityInfo {
     pub chainlink: String,
 }
 
+/// On-chain layout of the 24 value bytes of numerical feeds.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericalEncoding {
+    /// Non-negative values only.
+    #[default]
+    Unsigned,
+    /// Two's complement, so that negative values can be published.
+    Signed,
+}
+
+/// Bytes a reporter signs for a report, see `blocksense_data_feeds::generate_signature`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSigningScheme {
+    /// `feed_id || timestamp || value`, with numbers encoded with 18 decimals and errors not
+    /// signed. Valid for any deployment.
+    #[default]
+    V0,
+    /// Domain separated, bound to a deployment and covering the type of the value.
+    V1,
+}
+
+impl ReportSigningScheme {
+    pub fn version(self) -> u8 {
+        match self {
+            ReportSigningScheme::V0 => 0,
+            ReportSigningScheme::V1 => 1,
+        }
+    }
+
+    pub fn from_version(version: u8) -> Option<ReportSigningScheme> {
+        match version {
+            0 => Some(ReportSigningScheme::V0),
+            1 => Some(ReportSigningScheme::V1),
+            _ => None,
+        }
+    }
+
+    pub fn is_v0(&self) -> bool {
+        *self == ReportSigningScheme::V0
+    }
+}
+
+/// A single field of a struct feed value. Fields are stored on-chain one
+/// 32 byte slot each, in the order in which they are configured.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct StructFieldConfig {
+    pub name: String,
+    /// Either "numerical" or "decimal".
+    pub value_type: String,
+    /// Defaults to the aggregation of the feed's quorum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation: Option<String>,
+}
+
 //TODO(melatron): This is duplicated from the config crate
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct FeedConfig {
@@ -64,6 +120,11 @@ pub struct FeedConfig {
     pub feed_type: String,
     pub oracle_id: String,
     pub value_type: String,
+    #[serde(default)]
+    pub value_encoding: NumericalEncoding,
+    /// Layout of the values of feeds with `value_type` "struct".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub struct_fields: Vec<StructFieldConfig>,
     pub stride: u16,
     pub quorum: FeedQuorum,
     pub schedule: FeedSchedule,
@@ -82,6 +82,33 @@ pub fn oracle_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
                         ::blocksense_sdk::oracle::DataFeedResultValue::Numerical(value) => Self::Numerical(value),
                         ::blocksense_sdk::oracle::DataFeedResultValue::Text(value) => Self::Text(value),
                         ::blocksense_sdk::oracle::DataFeedResultValue::Error(error) => Self::Error(error),
+                        ::blocksense_sdk::oracle::DataFeedResultValue::Decimal { mantissa, scale } => {
+                            Self::Decimal(self::preamble::blocksense::oracle::oracle_types::Decimal {
+                                mantissa: mantissa.to_string(),
+                                scale,
+                            })
+                        }
+                        ::blocksense_sdk::oracle::DataFeedResultValue::Struct(fields) => {
+                            Self::Struct(fields.into_iter().map(From::from).collect())
+                        }
+                    }
+                }
+            }
+
+            impl From<::blocksense_sdk::oracle::StructField> for self::preamble::blocksense::oracle::oracle_types::StructField {
+                fn from(field: ::blocksense_sdk::oracle::StructField) -> Self {
+                    use self::preamble::blocksense::oracle::oracle_types::StructFieldValue;
+                    Self {
+                        name: field.name,
+                        value: match field.value {
+                            ::blocksense_sdk::oracle::StructFieldValue::Numerical(value) => StructFieldValue::Numerical(value),
+                            ::blocksense_sdk::oracle::StructFieldValue::Decimal { mantissa, scale } => {
+                                StructFieldValue::Decimal(self::preamble::blocksense::oracle::oracle_types::Decimal {
+                                    mantissa: mantissa.to_string(),
+                                    scale,
+                                })
+                            }
+                        },
                     }
                 }
             }
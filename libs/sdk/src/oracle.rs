@@ -32,6 +32,25 @@ pub enum DataFeedResultValue {
     Numerical(f64),
     Text(String),
     Error(String),
+    /// Exact fixed-point value equal to `mantissa * 10^(-scale)`.
+    Decimal {
+        mantissa: i128,
+        scale: u8,
+    },
+    /// Ordered named fields, e.g. bid and ask, published together.
+    Struct(Vec<StructField>),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct StructField {
+    pub name: String,
+    pub value: StructFieldValue,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub enum StructFieldValue {
+    Numerical(f64),
+    Decimal { mantissa: i128, scale: u8 },
 }
 
 //TODO(adikov): Start using FeedType from feed_registry
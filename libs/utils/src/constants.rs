@@ -7,3 +7,6 @@ pub const REPORTER_CONFIG_DIR: &str = "REPORTER_CONFIG_DIR";
 pub const FEEDS_CONFIG_FILE: &str = "feeds_config_v2.json";
 pub const SEQUENCER_CONFIG_FILE: &str = "sequencer_config.json";
 pub const REPORTER_CONFIG_FILE: &str = "reporter_config.json";
+
+// limit on the number of fields of struct feeds, so that their configs fit in a block
+pub const MAX_STRUCT_FIELDS: usize = 1024;